@@ -0,0 +1,407 @@
+//! `zap audit`: known vulnerabilities in installed packages
+
+mod osv;
+
+use anyhow::{Context, Result};
+use futures::stream::{self, StreamExt};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::Command;
+
+use crate::backend::npm::NodeClient;
+use crate::backend::{self, bootstrap, sudo, System};
+use crate::ui::{print_advisories, print_info, print_install_summary, print_warning};
+pub use osv::{compare_versions, Severity};
+
+/// How many advisories are fetched from the OSV API at the same time
+const MAX_CONCURRENT_FETCHES: usize = 8;
+
+/// Where advisories come from
+pub enum Source {
+    /// The OSV API
+    Online,
+    /// Databases cached under ~/.cache/zap/osv, downloaded first when `download` is set
+    Cache { download: bool },
+    /// OSV .json or .zip files in a directory
+    Directory(PathBuf),
+}
+
+/// A package installed through one of zap's backends
+struct InstalledPackage {
+    backend: &'static str,
+    /// OSV ecosystem, e.g. "PyPI" or "Debian:12"
+    ecosystem: String,
+    /// Name in the OSV database
+    name: String,
+    /// Name to pass back to the backend when upgrading
+    package: String,
+    version: String,
+}
+
+/// A known vulnerability affecting an installed package
+#[derive(Debug, Clone)]
+pub struct Advisory {
+    pub id: String,
+    pub aliases: Vec<String>,
+    pub summary: Option<String>,
+    pub severity: Severity,
+    pub backend: String,
+    pub package: String,
+    pub version: String,
+    /// Lowest version with a fix, if there is one
+    pub fixed: Option<String>,
+}
+
+pub async fn run(system: &System, source: Source) -> Result<()> {
+    run_native_audits(system);
+
+    let installed = find_installed(system);
+    if installed.is_empty() {
+        print_warning("No packages found to check against OSV");
+        return Ok(());
+    }
+
+    print_info(&format!(
+        "Checking {} installed packages against OSV...",
+        installed.len()
+    ));
+
+    let client = reqwest::Client::builder()
+        .user_agent("zap/0.1.0")
+        .build()
+        .context("Failed to create HTTP client")?;
+
+    let mut advisories = match source {
+        Source::Online => check_online(&client, &installed).await?,
+        Source::Cache { download } => {
+            let vulns = load_cache(&client, &installed, download).await?;
+            check_offline(&installed, &vulns)
+        }
+        Source::Directory(dir) => {
+            let vulns = osv::load_database(&dir)?;
+            check_offline(&installed, &vulns)
+        }
+    };
+    advisories.sort_by(|a, b| {
+        b.severity
+            .cmp(&a.severity)
+            .then_with(|| a.package.cmp(&b.package))
+    });
+
+    print_advisories(&advisories);
+    offer_upgrades(&advisories).await
+}
+
+/// The distribution's own audit tools, where they exist
+fn run_native_audits(system: &System) {
+    match system {
+        System::Arch => {
+            if command_exists("arch-audit") {
+                print_info("Running arch-audit (Arch Linux security tracker)...");
+                if let Err(e) = Command::new("arch-audit").status() {
+                    print_warning(&format!("arch-audit failed: {}", e));
+                }
+            } else {
+                print_info(
+                    "Install arch-audit to check pacman packages against the Arch security tracker",
+                );
+            }
+        }
+        System::FreeBSD => {
+            print_info("Running pkg audit (FreeBSD VuXML)...");
+            // -F fetches the current vulnerability database into /var/db/pkg
+            if let Err(e) = sudo::run_sudo(&["pkg", "audit", "-F"]) {
+                print_warning(&format!("pkg audit failed: {}", e));
+            }
+        }
+        _ => {}
+    }
+
+    // Project dependencies are audited by the project's own client
+    if let Ok(cwd) = std::env::current_dir() {
+        if cwd.join("package.json").is_file() {
+            let client = NodeClient::detect(&cwd).unwrap_or(NodeClient::Npm);
+            if client.is_available() {
+                print_info(&format!(
+                    "Running {} audit for this project...",
+                    client.name()
+                ));
+                if let Err(e) = client.run(&["audit"]) {
+                    print_warning(&format!("{} audit failed: {}", client.name(), e));
+                }
+            }
+        }
+    }
+}
+
+/// Everything installed through backends whose packages OSV tracks
+fn find_installed(system: &System) -> Vec<InstalledPackage> {
+    let available = backend::detect_available_package_managers();
+    let mut installed = vec![];
+
+    for (id, ecosystem) in [("npm", "npm"), ("pip", "PyPI"), ("cargo", "crates.io")] {
+        if !available.contains(&id) {
+            continue;
+        }
+        let packages = backend::multi::create_backend_by_id(id).and_then(|pm| pm.list_installed());
+        match packages {
+            Ok(packages) => {
+                installed.extend(
+                    packages
+                        .into_iter()
+                        .map(|(name, version)| InstalledPackage {
+                            backend: id,
+                            ecosystem: ecosystem.to_string(),
+                            package: name.clone(),
+                            name,
+                            version,
+                        }),
+                )
+            }
+            Err(e) => print_warning(&format!("Skipping {}: {}", id, e)),
+        }
+    }
+
+    // OSV tracks Go modules, not the package paths the binaries were built from
+    if available.contains(&"go") {
+        installed.extend(
+            backend::go::GoBackend::find_installed_binaries()
+                .into_iter()
+                .filter(|b| b.version.starts_with('v'))
+                .map(|b| InstalledPackage {
+                    backend: "go",
+                    ecosystem: "Go".to_string(),
+                    name: b.module,
+                    package: b.path,
+                    version: b.version.trim_start_matches('v').to_string(),
+                }),
+        );
+    }
+
+    if matches!(system, System::Debian | System::Ubuntu) {
+        match debian_source_packages() {
+            Ok(packages) => installed.extend(packages),
+            Err(e) => print_warning(&format!("Skipping dpkg packages: {}", e)),
+        }
+    }
+
+    installed
+}
+
+/// Debian advisories are filed against source packages, per release
+fn debian_source_packages() -> Result<Vec<InstalledPackage>> {
+    let os_release = std::fs::read_to_string("/etc/os-release")?;
+    let field = |key: &str| {
+        os_release.lines().find_map(|line| {
+            line.strip_prefix(key)?
+                .strip_prefix('=')
+                .map(|v| v.trim_matches('"').to_string())
+        })
+    };
+
+    let id = field("ID").unwrap_or_default();
+    let version_id = field("VERSION_ID").context("VERSION_ID missing from /etc/os-release")?;
+    let ecosystem = if id == "ubuntu" {
+        let lts = field("VERSION").is_some_and(|v| v.contains("LTS"));
+        format!("Ubuntu:{}{}", version_id, if lts { ":LTS" } else { "" })
+    } else {
+        let major = version_id.split('.').next().unwrap_or(&version_id);
+        format!("Debian:{}", major)
+    };
+
+    let output = Command::new("dpkg-query")
+        .args(["-W", "-f", "${source:Package}\t${source:Version}\n"])
+        .output()
+        .context("Failed to run dpkg-query")?;
+
+    let mut seen = std::collections::HashSet::new();
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .filter(|(name, _)| seen.insert(name.to_string()))
+        .map(|(name, version)| InstalledPackage {
+            backend: "apt",
+            ecosystem: ecosystem.clone(),
+            name: name.to_string(),
+            package: name.to_string(),
+            version: version.to_string(),
+        })
+        .collect())
+}
+
+async fn check_online(
+    client: &reqwest::Client,
+    installed: &[InstalledPackage],
+) -> Result<Vec<Advisory>> {
+    let queries: Vec<(&str, &str, &str)> = installed
+        .iter()
+        .map(|p| (p.ecosystem.as_str(), p.name.as_str(), p.version.as_str()))
+        .collect();
+    let ids = osv::query_batch(client, &queries).await?;
+
+    let mut unique: Vec<&String> = ids.iter().flatten().collect();
+    unique.sort();
+    unique.dedup();
+
+    let fetches = unique
+        .into_iter()
+        .map(|id| async move { (id, osv::fetch(client, id).await) });
+    let fetched: Vec<_> = stream::iter(fetches)
+        .buffer_unordered(MAX_CONCURRENT_FETCHES)
+        .collect()
+        .await;
+
+    let mut vulns = HashMap::new();
+    for (id, result) in fetched {
+        match result {
+            Ok(vuln) => {
+                vulns.insert(id.as_str(), vuln);
+            }
+            Err(e) => print_warning(&format!("{:#}", e)),
+        }
+    }
+
+    Ok(installed
+        .iter()
+        .zip(&ids)
+        .flat_map(|(pkg, ids)| {
+            ids.iter()
+                .filter_map(|id| vulns.get(id.as_str()))
+                .map(|vuln| advisory(pkg, vuln))
+                .collect::<Vec<_>>()
+        })
+        .collect())
+}
+
+fn check_offline(installed: &[InstalledPackage], vulns: &[osv::Vulnerability]) -> Vec<Advisory> {
+    let mut index: HashMap<(&str, String), Vec<&osv::Vulnerability>> = HashMap::new();
+    for vuln in vulns {
+        for key in vuln.packages() {
+            index.entry(key).or_default().push(vuln);
+        }
+    }
+
+    let mut advisories = vec![];
+    for pkg in installed {
+        let key = (
+            pkg.ecosystem.as_str(),
+            osv::normalize_name(&pkg.ecosystem, &pkg.name),
+        );
+        for vuln in index.get(&key).into_iter().flatten() {
+            if vuln.affects(&pkg.ecosystem, &pkg.name, &pkg.version) {
+                advisories.push(advisory(pkg, vuln));
+            }
+        }
+    }
+    advisories
+}
+
+fn advisory(pkg: &InstalledPackage, vuln: &osv::Vulnerability) -> Advisory {
+    Advisory {
+        id: vuln.id.clone(),
+        aliases: vuln.aliases.clone(),
+        summary: vuln.summary.clone(),
+        severity: vuln.severity(&pkg.ecosystem, &pkg.name),
+        backend: pkg.backend.to_string(),
+        package: pkg.package.clone(),
+        version: pkg.version.clone(),
+        fixed: vuln.fixed_version(&pkg.ecosystem, &pkg.name, &pkg.version),
+    }
+}
+
+/// Load the cached database of each ecosystem in use, downloading it if asked
+async fn load_cache(
+    client: &reqwest::Client,
+    installed: &[InstalledPackage],
+    download: bool,
+) -> Result<Vec<osv::Vulnerability>> {
+    let dir = get_database_dir()?;
+
+    // "Debian:12" advisories ship in the Debian database
+    let mut ecosystems: Vec<&str> = installed
+        .iter()
+        .filter_map(|p| p.ecosystem.split(':').next())
+        .collect();
+    ecosystems.sort();
+    ecosystems.dedup();
+
+    let mut vulns = vec![];
+    for ecosystem in ecosystems {
+        let path = dir.join(format!("{}.zip", ecosystem));
+        if download {
+            print_info(&format!("Downloading OSV database for {}...", ecosystem));
+            osv::download_database(client, ecosystem, &path).await?;
+        } else if !path.exists() {
+            anyhow::bail!(
+                "No offline database for {}. Run `zap audit --download-db` first.",
+                ecosystem
+            );
+        }
+        vulns.extend(osv::load_database(&path)?);
+    }
+    Ok(vulns)
+}
+
+fn get_database_dir() -> Result<PathBuf> {
+    let dirs = directories::ProjectDirs::from("com", "zap", "zap")
+        .context("Could not determine cache directory")?;
+    Ok(dirs.cache_dir().join("osv"))
+}
+
+/// Offer to upgrade the affected language packages that have a fix
+async fn offer_upgrades(advisories: &[Advisory]) -> Result<()> {
+    let mut upgrades: Vec<(&str, Vec<&str>)> = vec![];
+    for advisory in advisories.iter().filter(|a| a.fixed.is_some()) {
+        // System packages are upgraded as a whole with `zap update`
+        if advisory.backend == "apt" {
+            continue;
+        }
+        match upgrades.iter_mut().find(|(id, _)| *id == advisory.backend) {
+            Some((_, names)) if names.contains(&advisory.package.as_str()) => {}
+            Some((_, names)) => names.push(&advisory.package),
+            None => upgrades.push((&advisory.backend, vec![&advisory.package])),
+        }
+    }
+
+    if advisories
+        .iter()
+        .any(|a| a.backend == "apt" && a.fixed.is_some())
+    {
+        print_info("Run `zap update` to install fixed system packages");
+    }
+
+    let count: usize = upgrades.iter().map(|(_, names)| names.len()).sum();
+    if count == 0 || !bootstrap::confirm(&format!("Upgrade {} affected packages?", count))? {
+        return Ok(());
+    }
+
+    let mut results = vec![];
+    for (id, names) in upgrades {
+        let pm = backend::multi::create_backend_by_id(id)?;
+        let packages = pm.info(&names).await?;
+        results.extend(pm.update(&packages).await?);
+    }
+    print_install_summary(&results);
+
+    Ok(())
+}
+
+fn command_exists(cmd: &str) -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        Command::new("where")
+            .arg(cmd)
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        Command::new("which")
+            .arg(cmd)
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    }
+}
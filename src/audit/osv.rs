@@ -0,0 +1,592 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::io::Read;
+use std::path::Path;
+
+const OSV_API: &str = "https://api.osv.dev/v1";
+/// Bucket with a zip of every advisory per ecosystem
+const OSV_BUCKET: &str = "https://osv-vulnerabilities.storage.googleapis.com";
+/// querybatch accepts at most 1000 queries per request
+const MAX_BATCH: usize = 1000;
+
+/// An advisory in the OSV schema (https://ossf.github.io/osv-schema/)
+#[derive(Debug, Clone, Deserialize)]
+pub struct Vulnerability {
+    pub id: String,
+    #[serde(default)]
+    pub summary: Option<String>,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    #[serde(default)]
+    severity: Vec<SeverityScore>,
+    #[serde(default)]
+    affected: Vec<Affected>,
+    #[serde(default)]
+    database_specific: Option<Value>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct SeverityScore {
+    #[serde(rename = "type")]
+    kind: String,
+    score: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Affected {
+    #[serde(default)]
+    package: Option<AffectedPackage>,
+    #[serde(default)]
+    ranges: Vec<Range>,
+    #[serde(default)]
+    versions: Vec<String>,
+    #[serde(default)]
+    severity: Vec<SeverityScore>,
+    #[serde(default)]
+    ecosystem_specific: Option<Value>,
+    #[serde(default)]
+    database_specific: Option<Value>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct AffectedPackage {
+    ecosystem: String,
+    name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Range {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    events: Vec<Event>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct Event {
+    introduced: Option<String>,
+    fixed: Option<String>,
+    last_affected: Option<String>,
+    limit: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Unknown,
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl Severity {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Unknown => "UNKNOWN",
+            Self::Low => "LOW",
+            Self::Medium => "MEDIUM",
+            Self::High => "HIGH",
+            Self::Critical => "CRITICAL",
+        }
+    }
+
+    fn from_label(label: &str) -> Option<Self> {
+        match label.to_uppercase().as_str() {
+            "CRITICAL" => Some(Self::Critical),
+            "HIGH" => Some(Self::High),
+            "MODERATE" | "MEDIUM" => Some(Self::Medium),
+            "LOW" => Some(Self::Low),
+            _ => None,
+        }
+    }
+
+    fn from_score(score: f64) -> Self {
+        match score {
+            s if s >= 9.0 => Self::Critical,
+            s if s >= 7.0 => Self::High,
+            s if s >= 4.0 => Self::Medium,
+            s if s > 0.0 => Self::Low,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+impl Vulnerability {
+    /// Whether `version` of the package is affected by this advisory
+    pub fn affects(&self, ecosystem: &str, name: &str, version: &str) -> bool {
+        self.affected_entry(ecosystem, name)
+            .is_some_and(|affected| affected.contains(version))
+    }
+
+    /// The lowest version that fixes this advisory for an installed `version`
+    pub fn fixed_version(&self, ecosystem: &str, name: &str, version: &str) -> Option<String> {
+        self.affected_entry(ecosystem, name)?
+            .ranges
+            .iter()
+            .flat_map(|range| range.events.iter())
+            .filter_map(|event| event.fixed.as_deref())
+            .filter(|fixed| compare_versions(fixed, version) == Ordering::Greater)
+            .min_by(|a, b| compare_versions(a, b))
+            .map(|fixed| fixed.to_string())
+    }
+
+    /// Severity from the database's own rating, or computed from a CVSS v3 vector
+    pub fn severity(&self, ecosystem: &str, name: &str) -> Severity {
+        let affected = self.affected_entry(ecosystem, name);
+
+        let labels = [
+            affected.and_then(|a| a.ecosystem_specific.as_ref()),
+            affected.and_then(|a| a.database_specific.as_ref()),
+            self.database_specific.as_ref(),
+        ];
+        for specific in labels.into_iter().flatten() {
+            if let Some(severity) = specific
+                .get("severity")
+                .and_then(|s| s.as_str())
+                .and_then(Severity::from_label)
+            {
+                return severity;
+            }
+        }
+
+        affected
+            .map(|a| a.severity.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .chain(&self.severity)
+            .filter(|s| s.kind == "CVSS_V3")
+            .find_map(|s| cvss3_base_score(&s.score))
+            .map(Severity::from_score)
+            .unwrap_or(Severity::Unknown)
+    }
+
+    /// (ecosystem, normalized name) of every package this advisory covers
+    pub fn packages(&self) -> impl Iterator<Item = (&str, String)> {
+        self.affected
+            .iter()
+            .filter_map(|affected| affected.package.as_ref())
+            .map(|p| (p.ecosystem.as_str(), normalize_name(&p.ecosystem, &p.name)))
+    }
+
+    fn affected_entry(&self, ecosystem: &str, name: &str) -> Option<&Affected> {
+        self.affected.iter().find(|affected| {
+            affected.package.as_ref().is_some_and(|package| {
+                package.ecosystem == ecosystem
+                    && normalize_name(ecosystem, &package.name) == normalize_name(ecosystem, name)
+            })
+        })
+    }
+}
+
+impl Affected {
+    fn contains(&self, version: &str) -> bool {
+        self.versions.iter().any(|v| v == version)
+            || self
+                .ranges
+                .iter()
+                // GIT ranges are commit hashes, which installed packages don't report
+                .filter(|range| range.kind != "GIT")
+                .any(|range| range.contains(version))
+    }
+}
+
+impl Range {
+    /// Evaluate the events in version order, as the OSV schema specifies
+    fn contains(&self, version: &str) -> bool {
+        let mut events: Vec<&Event> = self.events.iter().collect();
+        events.sort_by(|a, b| compare_versions(a.version(), b.version()));
+
+        let mut affected = false;
+        for event in events {
+            if let Some(introduced) = &event.introduced {
+                if introduced == "0" || compare_versions(version, introduced) != Ordering::Less {
+                    affected = true;
+                }
+            } else if let Some(fixed) = &event.fixed {
+                if compare_versions(version, fixed) != Ordering::Less {
+                    affected = false;
+                }
+            } else if let Some(last) = &event.last_affected {
+                if compare_versions(version, last) == Ordering::Greater {
+                    affected = false;
+                }
+            }
+        }
+        affected
+    }
+}
+
+impl Event {
+    fn version(&self) -> &str {
+        self.introduced
+            .as_deref()
+            .or(self.fixed.as_deref())
+            .or(self.last_affected.as_deref())
+            .or(self.limit.as_deref())
+            .unwrap_or("0")
+    }
+}
+
+/// PyPI names are case-insensitive and treat `-`, `_` and `.` alike
+pub fn normalize_name(ecosystem: &str, name: &str) -> String {
+    if ecosystem == "PyPI" {
+        name.to_lowercase().replace(['_', '.'], "-")
+    } else {
+        name.to_string()
+    }
+}
+
+#[derive(Serialize)]
+struct BatchQuery<'a> {
+    package: BatchPackage<'a>,
+    version: &'a str,
+}
+
+#[derive(Serialize)]
+struct BatchPackage<'a> {
+    name: &'a str,
+    ecosystem: &'a str,
+}
+
+#[derive(Deserialize)]
+struct BatchResponse {
+    #[serde(default)]
+    results: Vec<BatchResult>,
+}
+
+#[derive(Deserialize)]
+struct BatchResult {
+    #[serde(default)]
+    vulns: Vec<BatchVuln>,
+}
+
+#[derive(Deserialize)]
+struct BatchVuln {
+    id: String,
+}
+
+/// Ask the OSV API which advisories affect each (ecosystem, name, version);
+/// returns the advisory ids for each query, in order
+pub async fn query_batch(
+    client: &reqwest::Client,
+    queries: &[(&str, &str, &str)],
+) -> Result<Vec<Vec<String>>> {
+    let mut ids = Vec::with_capacity(queries.len());
+
+    for chunk in queries.chunks(MAX_BATCH) {
+        let body: Vec<BatchQuery> = chunk
+            .iter()
+            .map(|(ecosystem, name, version)| BatchQuery {
+                package: BatchPackage { name, ecosystem },
+                version,
+            })
+            .collect();
+
+        let response: BatchResponse = client
+            .post(format!("{}/querybatch", OSV_API))
+            .json(&serde_json::json!({ "queries": body }))
+            .send()
+            .await
+            .context("Failed to query OSV")?
+            .error_for_status()
+            .context("OSV rejected the query")?
+            .json()
+            .await
+            .context("Failed to parse OSV response")?;
+
+        let mut results = response.results.into_iter();
+        for _ in chunk {
+            let result = results.next().map(|r| r.vulns).unwrap_or_default();
+            ids.push(result.into_iter().map(|v| v.id).collect());
+        }
+    }
+
+    Ok(ids)
+}
+
+/// Fetch a full advisory by id
+pub async fn fetch(client: &reqwest::Client, id: &str) -> Result<Vulnerability> {
+    client
+        .get(format!("{}/vulns/{}", OSV_API, id))
+        .send()
+        .await
+        .with_context(|| format!("Failed to fetch {}", id))?
+        .error_for_status()?
+        .json()
+        .await
+        .with_context(|| format!("Failed to parse {}", id))
+}
+
+/// Download every advisory for an ecosystem (e.g. "PyPI") into `dest` as a zip
+pub async fn download_database(
+    client: &reqwest::Client,
+    ecosystem: &str,
+    dest: &Path,
+) -> Result<()> {
+    let url = format!("{}/{}/all.zip", OSV_BUCKET, ecosystem);
+    let bytes = client
+        .get(&url)
+        .send()
+        .await
+        .with_context(|| format!("Failed to download {}", url))?
+        .error_for_status()
+        .with_context(|| format!("Failed to download {}", url))?
+        .bytes()
+        .await?;
+
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(dest, &bytes).with_context(|| format!("Failed to write {}", dest.display()))
+}
+
+/// Load advisories from an OSV zip, a single JSON file, or a directory of either
+pub fn load_database(path: &Path) -> Result<Vec<Vulnerability>> {
+    if path.is_dir() {
+        let mut vulns = vec![];
+        let entries = std::fs::read_dir(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let is_db = path.is_dir()
+                || path
+                    .extension()
+                    .is_some_and(|ext| ext == "json" || ext == "zip");
+            if is_db {
+                vulns.extend(load_database(&path)?);
+            }
+        }
+        return Ok(vulns);
+    }
+
+    if path.extension().is_some_and(|ext| ext == "zip") {
+        let file = std::fs::File::open(path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        let mut archive = zip::ZipArchive::new(file)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+
+        let mut vulns = Vec::with_capacity(archive.len());
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i)?;
+            if !entry.name().ends_with(".json") {
+                continue;
+            }
+            let mut content = String::new();
+            entry.read_to_string(&mut content)?;
+            // Skip records newer schema versions can't be read as
+            if let Ok(vuln) = serde_json::from_str(&content) {
+                vulns.push(vuln);
+            }
+        }
+        return Ok(vulns);
+    }
+
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let vuln = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+    Ok(vec![vuln])
+}
+
+/// Compare two versions: as semver when both parse, otherwise segment by
+/// segment (numbers numerically, `~` and trailing letters as pre-releases)
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let (a, b) = (a.trim_start_matches('v'), b.trim_start_matches('v'));
+    if let (Ok(a), Ok(b)) = (semver::Version::parse(a), semver::Version::parse(b)) {
+        return a.cmp(&b);
+    }
+
+    let (a, b) = (tokenize(a), tokenize(b));
+    for i in 0..a.len().max(b.len()) {
+        let ordering = match (a.get(i), b.get(i)) {
+            (Some(x), Some(y)) => x.cmp(y),
+            (Some(x), None) => x.cmp_to_end(),
+            (None, Some(y)) => y.cmp_to_end().reverse(),
+            (None, None) => Ordering::Equal,
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Token {
+    /// Debian's `~`, which sorts before everything
+    Tilde,
+    Alpha(String),
+    Num(u64),
+}
+
+impl Token {
+    /// How a version with this extra trailing token compares to one without it
+    fn cmp_to_end(&self) -> Ordering {
+        match self {
+            Token::Num(_) => Ordering::Greater,
+            Token::Alpha(s) if s == "post" => Ordering::Greater,
+            _ => Ordering::Less,
+        }
+    }
+}
+
+fn tokenize(version: &str) -> Vec<Token> {
+    let mut tokens = vec![];
+    let mut chars = version.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_ascii_digit() {
+            let mut num = String::new();
+            while let Some(&d) = chars.peek().filter(|d| d.is_ascii_digit()) {
+                num.push(d);
+                chars.next();
+            }
+            tokens.push(Token::Num(num.parse().unwrap_or(u64::MAX)));
+        } else if c.is_ascii_alphabetic() {
+            let mut word = String::new();
+            while let Some(&l) = chars.peek().filter(|l| l.is_ascii_alphabetic()) {
+                word.push(l.to_ascii_lowercase());
+                chars.next();
+            }
+            tokens.push(Token::Alpha(word));
+        } else {
+            if c == '~' {
+                tokens.push(Token::Tilde);
+            }
+            chars.next();
+        }
+    }
+
+    tokens
+}
+
+/// CVSS v3.x base score from a vector like `CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H`
+fn cvss3_base_score(vector: &str) -> Option<f64> {
+    let metric = |name: &str| {
+        vector
+            .split('/')
+            .find_map(|part| part.strip_prefix(name)?.strip_prefix(':'))
+    };
+    let changed = metric("S")? == "C";
+
+    let av = match metric("AV")? {
+        "N" => 0.85,
+        "A" => 0.62,
+        "L" => 0.55,
+        "P" => 0.2,
+        _ => return None,
+    };
+    let ac = match metric("AC")? {
+        "L" => 0.77,
+        "H" => 0.44,
+        _ => return None,
+    };
+    let pr = match (metric("PR")?, changed) {
+        ("N", _) => 0.85,
+        ("L", false) => 0.62,
+        ("L", true) => 0.68,
+        ("H", false) => 0.27,
+        ("H", true) => 0.5,
+        _ => return None,
+    };
+    let ui = match metric("UI")? {
+        "N" => 0.85,
+        "R" => 0.62,
+        _ => return None,
+    };
+    let cia = |name: &str| match metric(name) {
+        Some("H") => Some(0.56),
+        Some("L") => Some(0.22),
+        Some("N") => Some(0.0),
+        _ => None,
+    };
+    let (c, i, a) = (cia("C")?, cia("I")?, cia("A")?);
+
+    let iss = 1.0 - (1.0 - c) * (1.0 - i) * (1.0 - a);
+    let impact = if changed {
+        7.52 * (iss - 0.029) - 3.25 * (iss - 0.02_f64).powi(15)
+    } else {
+        6.42 * iss
+    };
+    if impact <= 0.0 {
+        return Some(0.0);
+    }
+
+    let exploitability = 8.22 * av * ac * pr * ui;
+    let score = if changed {
+        1.08 * (impact + exploitability)
+    } else {
+        impact + exploitability
+    };
+    Some(round_up(score.min(10.0)))
+}
+
+/// The spec's Roundup: the smallest one-decimal number >= the input
+fn round_up(value: f64) -> f64 {
+    let int = (value * 100_000.0).round() as i64;
+    if int % 10_000 == 0 {
+        int as f64 / 100_000.0
+    } else {
+        ((int / 10_000) + 1) as f64 / 10.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(compare_versions("1.2.10", "1.2.9"), Ordering::Greater);
+        assert_eq!(compare_versions("v0.30.0", "0.30.0"), Ordering::Equal);
+        assert_eq!(compare_versions("2.0.0-rc.1", "2.0.0"), Ordering::Less);
+        assert_eq!(compare_versions("1.0rc1", "1.0"), Ordering::Less);
+        assert_eq!(compare_versions("1.0.post1", "1.0"), Ordering::Greater);
+        assert_eq!(compare_versions("1.2~beta1", "1.2"), Ordering::Less);
+        assert_eq!(compare_versions("2.31", "2.4"), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_affected_ranges() {
+        let vuln: Vulnerability = serde_json::from_str(
+            r#"{
+                "id": "GHSA-test",
+                "affected": [{
+                    "package": {"ecosystem": "PyPI", "name": "Requests"},
+                    "ranges": [{"type": "ECOSYSTEM", "events": [
+                        {"introduced": "0"}, {"fixed": "2.31.0"},
+                        {"introduced": "3.0"}, {"last_affected": "3.0.2"}
+                    ]}],
+                    "database_specific": {"severity": "MODERATE"}
+                }]
+            }"#,
+        )
+        .unwrap();
+
+        assert!(vuln.affects("PyPI", "requests", "2.30.0"));
+        assert!(!vuln.affects("PyPI", "requests", "2.31.0"));
+        assert!(vuln.affects("PyPI", "requests", "3.0.2"));
+        assert!(!vuln.affects("PyPI", "requests", "3.0.3"));
+        assert!(!vuln.affects("npm", "requests", "2.30.0"));
+        assert_eq!(
+            vuln.fixed_version("PyPI", "requests", "2.28.1").as_deref(),
+            Some("2.31.0")
+        );
+        assert_eq!(vuln.severity("PyPI", "requests"), Severity::Medium);
+    }
+
+    #[test]
+    fn test_cvss3_base_score() {
+        assert_eq!(
+            cvss3_base_score("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H"),
+            Some(9.8)
+        );
+        assert_eq!(
+            cvss3_base_score("CVSS:3.1/AV:N/AC:L/PR:N/UI:R/S:C/C:L/I:L/A:N"),
+            Some(6.1)
+        );
+        assert_eq!(cvss3_base_score("CVSS:3.1/AV:N"), None);
+    }
+}
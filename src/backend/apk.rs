@@ -0,0 +1,379 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::collections::HashSet;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use super::sudo;
+use super::{InstallResult, Package, PackageManager};
+
+/// apk package manager backend for Alpine Linux
+pub struct ApkBackend;
+
+impl ApkBackend {
+    pub fn new() -> Result<Self> {
+        if !command_exists("apk") {
+            anyhow::bail!("apk is not available on this system");
+        }
+        Ok(Self)
+    }
+
+    fn installed_names(&self) -> HashSet<String> {
+        self.list_installed()
+            .map(|installed| installed.into_iter().map(|(name, _)| name).collect())
+            .unwrap_or_default()
+    }
+
+    /// Run `apk add` or `apk del` as root, with one result per package
+    fn run_root(&self, action: &str, packages: &[&str]) -> Result<Vec<InstallResult>> {
+        if packages.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut args = vec!["apk", action];
+        args.extend(packages.iter().copied());
+
+        let status =
+            sudo::run_sudo(&args).with_context(|| format!("Failed to run apk {}", action))?;
+
+        let success = status.success();
+        Ok(packages
+            .iter()
+            .map(|name| InstallResult {
+                package: name.to_string(),
+                success,
+                message: if success {
+                    None
+                } else {
+                    Some(format!("apk {} failed", action))
+                },
+            })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl PackageManager for ApkBackend {
+    fn name(&self) -> &str {
+        "apk (Alpine)"
+    }
+
+    fn id(&self) -> &str {
+        "apk"
+    }
+
+    async fn search(&self, query: &str) -> Result<Vec<Package>> {
+        if query.len() < 2 {
+            return Ok(vec![]);
+        }
+
+        let output = Command::new("apk")
+            .args(["search", "-v", query])
+            .output()
+            .context("Failed to run apk search")?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let installed = self.installed_names();
+        let mut packages = parse_package_list(&stdout);
+        for pkg in &mut packages {
+            pkg.installed = installed.contains(&pkg.name);
+        }
+        packages.truncate(30);
+
+        Ok(packages)
+    }
+
+    async fn info(&self, packages: &[&str]) -> Result<Vec<Package>> {
+        let mut results = vec![];
+        let installed = self.installed_names();
+
+        for pkg_name in packages {
+            let output = Command::new("apk")
+                .args(["info", "-a", pkg_name])
+                .output()
+                .context("Failed to run apk info")?;
+
+            if output.status.success() {
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                if let Some(mut pkg) = parse_info(&stdout) {
+                    pkg.installed = installed.contains(&pkg.name);
+                    results.push(pkg);
+                }
+            }
+        }
+
+        Ok(results)
+    }
+
+    async fn install(&self, packages: &[Package]) -> Result<Vec<InstallResult>> {
+        let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+        if !names.is_empty() {
+            println!("--> Installing packages with apk...");
+        }
+        self.run_root("add", &names)
+    }
+
+    async fn remove(&self, packages: &[&str]) -> Result<Vec<InstallResult>> {
+        if !packages.is_empty() {
+            println!("--> Removing packages with apk...");
+        }
+        self.run_root("del", packages)
+    }
+
+    fn is_installed(&self, package: &str) -> Result<bool> {
+        let status = Command::new("apk")
+            .args(["info", "-e", package])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()?;
+
+        Ok(status.success())
+    }
+
+    fn list_installed(&self) -> Result<Vec<(String, String)>> {
+        let output = Command::new("apk").args(["info", "-vv"]).output()?;
+
+        if !output.status.success() {
+            return Ok(vec![]);
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(parse_package_list(&stdout)
+            .into_iter()
+            .map(|pkg| (pkg.name, pkg.version))
+            .collect())
+    }
+
+    async fn check_updates(&self) -> Result<Vec<Package>> {
+        println!("--> Refreshing repository indexes...");
+        let _ = sudo::run_sudo_output(&["apk", "update"]);
+
+        let output = Command::new("apk")
+            .args(["version", "-l", "<"])
+            .output()
+            .context("Failed to check for updates")?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(parse_version_list(&stdout))
+    }
+
+    fn owns(&self, path: &Path) -> Result<Option<(String, String)>> {
+        let output = Command::new("apk")
+            .args(["info", "--who-owns"])
+            .arg(path)
+            .output()
+            .context("Failed to run apk info --who-owns")?;
+
+        if !output.status.success() {
+            return Ok(None);
+        }
+
+        // "/usr/bin/curl is owned by curl-8.5.0-r0"
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout
+            .lines()
+            .find_map(|line| line.split_once(" is owned by "))
+            .map(|(_, pkgver)| {
+                let (name, version) = split_pkgver(pkgver.trim());
+                (name.to_string(), version.to_string())
+            }))
+    }
+
+    async fn provides(&self, target: &str) -> Result<Vec<Package>> {
+        // Alpine has no local file index, but packages declare the commands
+        // they ship as `cmd:<name>` provides
+        let Some(cmd) = super::command_name(target) else {
+            return Ok(vec![]);
+        };
+
+        let output = Command::new("apk")
+            .args(["search", "-v", "-x", &format!("cmd:{}", cmd)])
+            .output()
+            .context("Failed to run apk search")?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let installed = self.installed_names();
+        let mut packages = parse_package_list(&stdout);
+        for pkg in &mut packages {
+            pkg.installed = installed.contains(&pkg.name);
+        }
+
+        Ok(packages)
+    }
+}
+
+/// Split "curl-8.5.0-r0" into ("curl", "8.5.0-r0"). Names may contain dashes,
+/// but the version always ends in a "-r<N>" package release.
+fn split_pkgver(pkgver: &str) -> (&str, &str) {
+    let mut parts = pkgver.rsplitn(3, '-');
+    if let (Some(release), Some(version), Some(name)) = (parts.next(), parts.next(), parts.next()) {
+        if release.starts_with('r') && version.starts_with(|c: char| c.is_ascii_digit()) {
+            return (name, &pkgver[name.len() + 1..]);
+        }
+    }
+    pkgver.rsplit_once('-').unwrap_or((pkgver, ""))
+}
+
+/// Parse "name-version - description" lines from `apk search -v` and `apk info -vv`
+fn parse_package_list(output: &str) -> Vec<Package> {
+    output
+        .lines()
+        .filter(|line| !line.trim().is_empty() && !line.starts_with("WARNING"))
+        .map(|line| {
+            let (pkgver, description) = match line.split_once(" - ") {
+                Some((pkgver, description)) => (pkgver.trim(), Some(description.trim())),
+                None => (line.trim(), None),
+            };
+            let (name, version) = split_pkgver(pkgver);
+            let description = description.filter(|d| !d.is_empty()).map(str::to_string);
+            Package::new(name, version).with_description(description)
+        })
+        .collect()
+}
+
+/// Parse `apk info -a`: blocks headed "curl-8.5.0-r0 description:" with the
+/// values on the following lines, separated by blank lines
+fn parse_info(output: &str) -> Option<Package> {
+    let mut pkg: Option<Package> = None;
+    let mut field = "";
+
+    for line in output.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            field = "";
+            continue;
+        }
+
+        if field.is_empty() {
+            if let Some((pkgver, name)) = line.strip_suffix(':').and_then(|l| l.split_once(' ')) {
+                if pkg.is_none() {
+                    let (name, version) = split_pkgver(pkgver);
+                    pkg = Some(Package::new(name, version));
+                }
+                field = name;
+                continue;
+            }
+        }
+
+        let Some(pkg) = pkg.as_mut() else {
+            continue;
+        };
+        match field {
+            "description" => pkg.description = Some(line.to_string()),
+            "webpage" => pkg.url = Some(line.to_string()),
+            "license" => pkg.extra.license.push(line.to_string()),
+            "depends on" => pkg.extra.depends.push(line.to_string()),
+            _ => {}
+        }
+    }
+
+    pkg
+}
+
+/// Parse `apk version -l '<'`: "curl-8.5.0-r0    < 8.5.0-r1" under an
+/// "Installed: Available:" header
+fn parse_version_list(output: &str) -> Vec<Package> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let (pkgver, op, available) = (parts.next()?, parts.next()?, parts.next()?);
+            if op != "<" {
+                return None;
+            }
+            let (name, _) = split_pkgver(pkgver);
+            let mut pkg = Package::new(name, available);
+            pkg.installed = true;
+            Some(pkg)
+        })
+        .collect()
+}
+
+fn command_exists(cmd: &str) -> bool {
+    Command::new("which")
+        .arg(cmd)
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_pkgver() {
+        assert_eq!(split_pkgver("curl-8.5.0-r0"), ("curl", "8.5.0-r0"));
+        assert_eq!(
+            split_pkgver("py3-setuptools-70.3.0-r0"),
+            ("py3-setuptools", "70.3.0-r0")
+        );
+        assert_eq!(split_pkgver("7zip-23.01-r0"), ("7zip", "23.01-r0"));
+    }
+
+    #[test]
+    fn test_parse_package_list() {
+        let output = "ripgrep-14.1.0-r0 - ripgrep combines the usability of The Silver Searcher with the raw speed of grep\n\
+                      ripgrep-doc-14.1.0-r0 - ripgrep (documentation)\n\
+                      ripgrep-fish-completion-14.1.0-r0 - Fish completions for ripgrep\n";
+        let packages = parse_package_list(output);
+        assert_eq!(packages.len(), 3);
+        assert_eq!(packages[0].name, "ripgrep");
+        assert_eq!(packages[0].version, "14.1.0-r0");
+        assert_eq!(
+            packages[1].description.as_deref(),
+            Some("ripgrep (documentation)")
+        );
+        assert_eq!(packages[2].name, "ripgrep-fish-completion");
+    }
+
+    #[test]
+    fn test_parse_info() {
+        let output = "curl-8.5.0-r0 description:\n\
+                      URL retrival utility and library\n\
+                      \n\
+                      curl-8.5.0-r0 webpage:\n\
+                      https://curl.se/\n\
+                      \n\
+                      curl-8.5.0-r0 installed size:\n\
+                      256 KiB\n\
+                      \n\
+                      curl-8.5.0-r0 depends on:\n\
+                      ca-certificates\n\
+                      so:libc.musl-x86_64.so.1\n\
+                      so:libcurl.so.4\n\
+                      \n\
+                      curl-8.5.0-r0 license:\n\
+                      curl\n\
+                      \n";
+        let pkg = parse_info(output).unwrap();
+        assert_eq!(pkg.name, "curl");
+        assert_eq!(pkg.version, "8.5.0-r0");
+        assert_eq!(
+            pkg.description.as_deref(),
+            Some("URL retrival utility and library")
+        );
+        assert_eq!(pkg.url.as_deref(), Some("https://curl.se/"));
+        assert_eq!(
+            pkg.extra.depends,
+            [
+                "ca-certificates",
+                "so:libc.musl-x86_64.so.1",
+                "so:libcurl.so.4"
+            ]
+        );
+        assert_eq!(pkg.extra.license, ["curl"]);
+    }
+
+    #[test]
+    fn test_parse_version_list() {
+        let output = "Installed:                                Available:\n\
+                      busybox-1.36.1-r15                      < 1.36.1-r19\n\
+                      libcrypto3-3.1.4-r2                     < 3.1.4-r5\n";
+        let updates = parse_version_list(output);
+        assert_eq!(updates.len(), 2);
+        assert_eq!(updates[0].name, "busybox");
+        assert_eq!(updates[0].version, "1.36.1-r19");
+        assert_eq!(updates[1].name, "libcrypto3");
+    }
+}
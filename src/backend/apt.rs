@@ -1,5 +1,6 @@
 use anyhow::{Context, Result};
 use async_trait::async_trait;
+use std::path::Path;
 use std::process::{Command, Stdio};
 
 use super::sudo;
@@ -227,6 +228,33 @@ impl PackageManager for AptBackend {
         Ok(results)
     }
 
+    async fn remove(&self, packages: &[&str]) -> Result<Vec<InstallResult>> {
+        if packages.is_empty() {
+            return Ok(vec![]);
+        }
+
+        println!("--> Removing packages with apt...");
+
+        let mut args = vec!["apt", "remove", "-y"];
+        args.extend(packages.iter().copied());
+
+        let status = sudo::run_sudo(&args).context("Failed to run apt remove")?;
+
+        let success = status.success();
+        Ok(packages
+            .iter()
+            .map(|name| InstallResult {
+                package: name.to_string(),
+                success,
+                message: if success {
+                    None
+                } else {
+                    Some("apt remove failed".to_string())
+                },
+            })
+            .collect())
+    }
+
     fn is_installed(&self, package: &str) -> Result<bool> {
         let output = Command::new("dpkg")
             .args(["-s", package])
@@ -286,6 +314,76 @@ impl PackageManager for AptBackend {
 
         Ok(updates)
     }
+
+    fn owns(&self, path: &Path) -> Result<Option<(String, String)>> {
+        // Merged-/usr systems record some files under /bin and others under /usr/bin
+        let mut candidates = vec![path.to_path_buf()];
+        if let Ok(canonical) = path.canonicalize() {
+            if canonical != path {
+                candidates.push(canonical);
+            }
+        }
+        if let Ok(unmerged) = path.strip_prefix("/usr") {
+            candidates.push(Path::new("/").join(unmerged));
+        }
+
+        for candidate in candidates {
+            let output = Command::new("dpkg")
+                .arg("-S")
+                .arg(&candidate)
+                .output()
+                .context("Failed to run dpkg -S")?;
+            if !output.status.success() {
+                continue;
+            }
+
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            if let Some(name) = parse_dpkg_owner(&stdout) {
+                let version = Command::new("dpkg-query")
+                    .args(["-W", "-f=${Version}", name])
+                    .output()
+                    .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+                    .unwrap_or_default();
+                return Ok(Some((name.to_string(), version)));
+            }
+        }
+
+        Ok(None)
+    }
+
+    async fn provides(&self, target: &str) -> Result<Vec<Package>> {
+        if !command_exists("apt-file") {
+            anyhow::bail!("apt-file is not installed; run 'sudo apt install apt-file && sudo apt-file update'");
+        }
+
+        let output = Command::new("apt-file")
+            .args(["search", "--regexp", &super::provides_pattern(target)])
+            .output()
+            .context("Failed to run apt-file search")?;
+
+        // Format: "ripgrep: /usr/bin/rg"
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let mut names: Vec<&str> = vec![];
+        for line in stdout.lines() {
+            if let Some((name, path)) = line.split_once(": ") {
+                if super::provides_match(path, target) && !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        }
+
+        self.info(&names).await
+    }
+}
+
+/// The package from `dpkg -S`: "ripgrep: /usr/bin/rg", or "pkg1, pkg2: /path"
+/// for shared paths, after any "diversion by" lines
+fn parse_dpkg_owner(output: &str) -> Option<&str> {
+    output
+        .lines()
+        .filter(|line| !line.starts_with("diversion by"))
+        .find_map(|line| line.split_once(": "))
+        .and_then(|(packages, _)| packages.split(", ").next())
 }
 
 fn command_exists(cmd: &str) -> bool {
@@ -295,3 +393,22 @@ fn command_exists(cmd: &str) -> bool {
         .map(|o| o.status.success())
         .unwrap_or(false)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dpkg_owner() {
+        assert_eq!(parse_dpkg_owner("ripgrep: /usr/bin/rg\n"), Some("ripgrep"));
+        assert_eq!(
+            parse_dpkg_owner("libc6:amd64, libc6:i386: /usr/share/doc/libc6\n"),
+            Some("libc6:amd64")
+        );
+        let diverted = "diversion by dash from: /bin/sh\n\
+                        diversion by dash to: /bin/sh.distrib\n\
+                        dash: /bin/sh\n";
+        assert_eq!(parse_dpkg_owner(diverted), Some("dash"));
+        assert_eq!(parse_dpkg_owner(""), None);
+    }
+}
@@ -0,0 +1,144 @@
+use anyhow::{Context, Result};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::backend::sudo;
+
+/// A devtools build chroot: `<dir>/root` is the pristine copy that
+/// makechrootpkg clones for every build
+pub struct Chroot {
+    dir: PathBuf,
+}
+
+impl Chroot {
+    /// The chroot location: `$ZAP_AUR_CHROOT`, or `chroot` in the zap cache directory
+    pub fn locate() -> Result<Self> {
+        Self::locate_in(std::env::var_os("ZAP_AUR_CHROOT"))
+    }
+
+    /// The chroot location given the value of `$ZAP_AUR_CHROOT`
+    fn locate_in(configured: Option<OsString>) -> Result<Self> {
+        let dir = match configured {
+            Some(dir) => PathBuf::from(dir),
+            None => directories::ProjectDirs::from("com", "zap", "zap")
+                .ok_or_else(|| anyhow::anyhow!("Could not determine chroot directory"))?
+                .cache_dir()
+                .join("chroot"),
+        };
+        Ok(Self { dir })
+    }
+
+    /// Create the chroot if it does not exist yet, otherwise bring it up to date
+    pub fn prepare(&self) -> Result<()> {
+        for tool in ["mkarchroot", "arch-nspawn", "makechrootpkg"] {
+            if !command_exists(tool) {
+                anyhow::bail!(
+                    "{} not found. Chroot builds need devtools: sudo pacman -S devtools",
+                    tool
+                );
+            }
+        }
+
+        let root = self.dir.join("root");
+        let root_str = root.to_string_lossy();
+
+        let status = if root.exists() {
+            println!("--> Updating build chroot {}...", self.dir.display());
+            sudo::run_sudo(&["arch-nspawn", &root_str, "pacman", "-Syu", "--noconfirm"])?
+        } else {
+            println!("--> Creating build chroot {}...", self.dir.display());
+            std::fs::create_dir_all(&self.dir)
+                .with_context(|| format!("Failed to create {}", self.dir.display()))?;
+            sudo::run_sudo(&["mkarchroot", &root_str, "base-devel"])?
+        };
+
+        if !status.success() {
+            anyhow::bail!("Failed to prepare build chroot {}", self.dir.display());
+        }
+        Ok(())
+    }
+
+    /// Build the package in `pkg_dir` in a clean copy of the chroot. `deps` are
+    /// package files of AUR dependencies to install into the copy first.
+    pub fn build(&self, pkg_dir: &Path, deps: &[PathBuf]) -> Result<()> {
+        let status = Command::new("makechrootpkg")
+            .current_dir(pkg_dir)
+            .args(self.build_args(deps))
+            .stdin(Stdio::inherit())
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::inherit())
+            .status()
+            .context("Failed to run makechrootpkg")?;
+
+        if !status.success() {
+            anyhow::bail!("makechrootpkg failed in {}", pkg_dir.display());
+        }
+        Ok(())
+    }
+
+    /// makechrootpkg arguments: clean the working copy first, install each
+    /// dependency package file into it, and have makepkg overwrite package
+    /// files left by an earlier build
+    fn build_args(&self, deps: &[PathBuf]) -> Vec<OsString> {
+        let mut args: Vec<OsString> = vec!["-c".into(), "-r".into(), self.dir.clone().into()];
+        for dep in deps {
+            args.push("-I".into());
+            args.push(dep.clone().into());
+        }
+        args.extend(["--".into(), "-f".into()]);
+        args
+    }
+}
+
+fn command_exists(cmd: &str) -> bool {
+    Command::new("which")
+        .arg(cmd)
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_args() {
+        let chroot = Chroot {
+            dir: PathBuf::from("/var/cache/zap/chroot"),
+        };
+        assert_eq!(
+            chroot.build_args(&[]),
+            ["-c", "-r", "/var/cache/zap/chroot", "--", "-f"]
+        );
+
+        let deps = [
+            PathBuf::from("/tmp/builds/libfoo/libfoo-1.0-1-x86_64.pkg.tar.zst"),
+            PathBuf::from("/tmp/builds/bar/bar-2.1-3-any.pkg.tar.zst"),
+        ];
+        assert_eq!(
+            chroot.build_args(&deps),
+            [
+                "-c",
+                "-r",
+                "/var/cache/zap/chroot",
+                "-I",
+                "/tmp/builds/libfoo/libfoo-1.0-1-x86_64.pkg.tar.zst",
+                "-I",
+                "/tmp/builds/bar/bar-2.1-3-any.pkg.tar.zst",
+                "--",
+                "-f",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_locate_in() {
+        let chroot = Chroot::locate_in(Some("/srv/chroot".into())).unwrap();
+        assert_eq!(chroot.dir, PathBuf::from("/srv/chroot"));
+
+        let chroot = Chroot::locate_in(None).unwrap();
+        assert!(chroot.dir.ends_with("chroot"));
+    }
+}
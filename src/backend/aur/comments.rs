@@ -0,0 +1,170 @@
+/// A comment from a package's AUR web page
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AurComment {
+    pub author: String,
+    pub date: String,
+    pub text: String,
+    pub pinned: bool,
+}
+
+/// Extract the pinned and latest comments from an AUR package page.
+/// The AUR has no API for comments, so this reads the aurweb HTML.
+pub fn parse_comments(html: &str) -> Vec<AurComment> {
+    // Pinned comments come first, in their own section before "Latest Comments"
+    let latest_start = html.find("Latest Comments").unwrap_or(0);
+
+    let mut comments = vec![];
+    let mut rest = html;
+    let mut offset = 0;
+
+    while let Some(pos) = rest.find("class=\"comment-header\"") {
+        let start = offset + pos;
+        let chunk = &rest[pos..];
+
+        let Some(header_end) = chunk.find("</h4>") else {
+            break;
+        };
+        let header_start = chunk.find('>').map(|i| i + 1).unwrap_or(0);
+        let header = collapse_whitespace(&strip_tags(&chunk[header_start..header_end]));
+
+        // "<author> commented on <date>", optionally followed by "(edited on ...)"
+        let Some((author, date)) = header.split_once(" commented on ") else {
+            rest = &chunk[header_end..];
+            offset = start + header_end;
+            continue;
+        };
+        let date = date.split(" (edited").next().unwrap_or(date);
+
+        // The comment body runs until the next comment or section
+        let body = &chunk[header_end..];
+        let body_end = [
+            "class=\"comment-header\"",
+            "class=\"comments-header\"",
+            "class=\"comments-footer\"",
+        ]
+        .iter()
+        .filter_map(|marker| body.find(marker))
+        .min()
+        .unwrap_or(body.len());
+        let text = body[..body_end]
+            .find("article-content")
+            .map(|content| {
+                let content = &body[content..body_end];
+                let content = content.split_once('>').map(|(_, c)| c).unwrap_or(content);
+                // Drop the partial tag left where the next comment header starts
+                let content = content.rfind('<').map(|i| &content[..i]).unwrap_or(content);
+                strip_tags(content).trim().to_string()
+            })
+            .unwrap_or_default();
+
+        comments.push(AurComment {
+            author: author.to_string(),
+            date: date.trim().to_string(),
+            text,
+            pinned: latest_start > 0 && start < latest_start,
+        });
+
+        rest = body;
+        offset = start + header_end;
+    }
+
+    comments
+}
+
+/// Remove HTML tags and decode the entities aurweb uses, keeping line breaks
+fn strip_tags(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    let mut tag = String::new();
+
+    for c in html.chars() {
+        match c {
+            '<' => {
+                in_tag = true;
+                tag.clear();
+            }
+            '>' if in_tag => {
+                in_tag = false;
+                let name = tag
+                    .trim_start_matches('/')
+                    .split_whitespace()
+                    .next()
+                    .unwrap_or("");
+                if matches!(name, "br" | "p" | "li" | "pre") && !text.ends_with('\n') {
+                    text.push('\n');
+                }
+            }
+            _ if in_tag => tag.push(c),
+            _ => text.push(c),
+        }
+    }
+
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&amp;", "&")
+        .lines()
+        .map(|line| line.trim_end())
+        .filter(|line| !line.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn collapse_whitespace(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r##"
+<div class="comments package-comments">
+  <div class="comments-header"><h3><span class="text">Pinned Comments</span></h3></div>
+  <h4 id="comment-900" class="comment-header">
+    <a href="/account/alice">alice</a> commented on
+    <a href="#comment-900" class="date">2024-03-01 10:00 (UTC)</a>
+  </h4>
+  <div id="comment-900-content" class="article-content">
+    <div><p>Import the key first:<br>
+    gpg --recv-keys ABCD</p></div>
+  </div>
+</div>
+<div class="comments package-comments">
+  <div class="comments-header"><h3><span class="text">Latest Comments</span></h3></div>
+  <h4 id="comment-950" class="comment-header">
+    <a href="/account/bob">bob</a> commented on
+    <a href="#comment-950" class="date">2024-05-02 08:30 (UTC)</a>
+    (edited on 2024-05-02 09:00 (UTC) by bob)
+  </h4>
+  <div id="comment-950-content" class="article-content">
+    <div><p>Build fails with &quot;undefined reference&quot; &amp; gcc 14</p></div>
+  </div>
+</div>
+<div class="comments-footer"></div>
+"##;
+
+    #[test]
+    fn test_parse_comments() {
+        let comments = parse_comments(PAGE);
+        assert_eq!(comments.len(), 2);
+
+        assert_eq!(comments[0].author, "alice");
+        assert_eq!(comments[0].date, "2024-03-01 10:00 (UTC)");
+        assert_eq!(
+            comments[0].text,
+            "Import the key first:\n    gpg --recv-keys ABCD"
+        );
+        assert!(comments[0].pinned);
+
+        assert_eq!(comments[1].author, "bob");
+        assert_eq!(comments[1].date, "2024-05-02 08:30 (UTC)");
+        assert_eq!(
+            comments[1].text,
+            "Build fails with \"undefined reference\" & gcc 14"
+        );
+        assert!(!comments[1].pinned);
+    }
+}
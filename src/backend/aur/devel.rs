@@ -0,0 +1,382 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use super::SrcInfo;
+
+/// Version control system used by a PKGBUILD source
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VcsKind {
+    Git,
+    Hg,
+    Svn,
+}
+
+/// A VCS source from .SRCINFO, e.g. `name::git+https://host/repo.git#branch=main`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VcsSource {
+    pub kind: VcsKind,
+    /// The `name::` prefix, naming makepkg's checkout directory
+    pub name: Option<String>,
+    pub url: String,
+    /// Fragment after `#`, e.g. ("branch", "main")
+    pub fragment: Option<(String, String)>,
+}
+
+impl VcsSource {
+    pub fn parse(source: &str) -> Option<Self> {
+        // Split off the optional "name::" prefix
+        let (name, source) = match source.split_once("::") {
+            Some((name, source)) => (Some(name.to_string()), source),
+            None => (None, source),
+        };
+
+        let (kind, rest) = if let Some(rest) = source.strip_prefix("git+") {
+            (VcsKind::Git, rest)
+        } else if let Some(rest) = source.strip_prefix("hg+") {
+            (VcsKind::Hg, rest)
+        } else if let Some(rest) = source.strip_prefix("svn+") {
+            (VcsKind::Svn, rest)
+        } else if source.starts_with("git://") {
+            (VcsKind::Git, source)
+        } else if source.starts_with("svn://") {
+            (VcsKind::Svn, source)
+        } else {
+            return None;
+        };
+
+        // Query strings (e.g. ?signed) are makepkg options, not part of the URL
+        let (url, fragment) = match rest.split_once('#') {
+            Some((url, frag)) => (url, frag.split_once('=')),
+            None => (rest, None),
+        };
+        let url = url.split('?').next().unwrap_or(url);
+
+        Some(Self {
+            kind,
+            name,
+            url: url.to_string(),
+            fragment: fragment.map(|(k, v)| (k.to_string(), v.to_string())),
+        })
+    }
+
+    /// Sources pinned to a fixed commit/tag/revision never change upstream
+    pub fn is_pinned(&self) -> bool {
+        matches!(
+            self.fragment.as_ref().map(|(k, _)| k.as_str()),
+            Some("commit") | Some("tag") | Some("revision")
+        )
+    }
+
+    /// The directory makepkg checks this source out to: the `name::` prefix,
+    /// or the last URL component without `.git`
+    pub fn checkout_name(&self) -> String {
+        if let Some(name) = &self.name {
+            return name.clone();
+        }
+        let last = self
+            .url
+            .trim_end_matches('/')
+            .rsplit('/')
+            .next()
+            .unwrap_or_default();
+        match self.kind {
+            VcsKind::Git => last.split(".git").next().unwrap_or(last).to_string(),
+            _ => last.to_string(),
+        }
+    }
+
+    /// The revision makepkg built: from the working copy in `<pkg_dir>/src`,
+    /// or, when the build ran elsewhere (a chroot), from the clone in `srcdest`
+    pub fn built_revision(&self, pkg_dir: &Path, srcdest: &Path) -> Result<String> {
+        let name = self.checkout_name();
+        let working_copy = pkg_dir.join("src").join(&name);
+        // The working copy is at the built revision; the clone has the branch
+        let (dir, branch) = if working_copy.is_dir() {
+            (working_copy, None)
+        } else {
+            let branch = self.fragment.as_ref().map(|(_, v)| v.as_str());
+            (srcdest.join(&name), Some(branch))
+        };
+        let dir = dir.to_string_lossy();
+
+        let output = match self.kind {
+            VcsKind::Git => {
+                let git_ref = match branch {
+                    Some(Some(branch)) => format!("refs/heads/{}", branch),
+                    _ => "HEAD".to_string(),
+                };
+                run_vcs("git", &["-C", &dir, "rev-parse", &git_ref])?
+            }
+            VcsKind::Hg => {
+                let revision = match branch {
+                    None => ".",
+                    Some(branch) => branch.unwrap_or("default"),
+                };
+                run_vcs("hg", &["identify", "--id", "-R", &dir, "-r", revision])?
+            }
+            VcsKind::Svn => run_vcs("svn", &["info", "--show-item", "revision", &dir])?,
+        };
+        Ok(output.trim().to_string())
+    }
+
+    /// This source as recorded in the devel database, at `revision`
+    fn recorded(&self, revision: String) -> DevelSource {
+        let branch = match self.fragment.as_ref() {
+            Some((key, value)) if key == "branch" => Some(value.clone()),
+            _ => None,
+        };
+        DevelSource {
+            kind: self.kind,
+            url: self.url.clone(),
+            branch,
+            revision,
+        }
+    }
+
+    /// Query the upstream repository for the revision this source currently points at
+    pub fn latest_revision(&self) -> Result<String> {
+        let reference = self.fragment.as_ref().map(|(_, v)| v.as_str());
+
+        match self.kind {
+            VcsKind::Git => {
+                let git_ref = match self.fragment.as_ref().map(|(k, v)| (k.as_str(), v)) {
+                    Some(("branch", branch)) => format!("refs/heads/{}", branch),
+                    _ => "HEAD".to_string(),
+                };
+                let output = run_vcs("git", &["ls-remote", &self.url, &git_ref])?;
+                output
+                    .split_whitespace()
+                    .next()
+                    .map(|s| s.to_string())
+                    .ok_or_else(|| anyhow::anyhow!("{} has no ref {}", self.url, git_ref))
+            }
+            VcsKind::Hg => {
+                let branch = reference.unwrap_or("default");
+                let output = run_vcs("hg", &["identify", "--id", "-r", branch, &self.url])?;
+                Ok(output.trim().to_string())
+            }
+            VcsKind::Svn => {
+                let output = run_vcs("svn", &["info", "--show-item", "revision", &self.url])?;
+                Ok(output.trim().to_string())
+            }
+        }
+    }
+}
+
+/// Upstream revision of a VCS source recorded when the package was built
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DevelSource {
+    pub kind: VcsKind,
+    pub url: String,
+    #[serde(default)]
+    pub branch: Option<String>,
+    pub revision: String,
+}
+
+/// Database of upstream revisions for installed VCS packages (like yay's devel.json)
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DevelDb {
+    #[serde(default)]
+    packages: HashMap<String, Vec<DevelSource>>,
+}
+
+impl DevelDb {
+    pub fn load(path: &Path) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content)
+                .with_context(|| format!("Failed to parse {}", path.display())),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    pub fn get(&self, package: &str) -> Option<&[DevelSource]> {
+        self.packages.get(package).map(|v| v.as_slice())
+    }
+
+    pub fn record(&mut self, package: &str, sources: Vec<DevelSource>) {
+        self.packages.insert(package.to_string(), sources);
+    }
+}
+
+/// Whether a package name follows the AUR naming convention for VCS packages
+pub fn is_vcs_package(name: &str) -> bool {
+    ["-git", "-svn", "-hg", "-bzr", "-darcs", "-fossil"]
+        .iter()
+        .any(|suffix| name.ends_with(suffix))
+}
+
+/// Unpinned VCS sources of a package
+pub fn vcs_sources(srcinfo: &SrcInfo) -> Vec<VcsSource> {
+    srcinfo
+        .source
+        .iter()
+        .filter_map(|s| VcsSource::parse(s))
+        .filter(|s| !s.is_pinned())
+        .collect()
+}
+
+/// Look up the current upstream revision of each source
+pub fn current_revisions(sources: &[VcsSource]) -> Result<Vec<DevelSource>> {
+    sources
+        .iter()
+        .map(|source| Ok(source.recorded(source.latest_revision()?)))
+        .collect()
+}
+
+/// The revision of each source that makepkg built in `pkg_dir`, read from its
+/// checkouts so upstream commits pushed during the build are not recorded
+pub fn built_revisions(sources: &[VcsSource], pkg_dir: &Path) -> Result<Vec<DevelSource>> {
+    let srcdest = std::env::var_os("SRCDEST")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| pkg_dir.to_path_buf());
+    sources
+        .iter()
+        .map(|source| Ok(source.recorded(source.built_revision(pkg_dir, &srcdest)?)))
+        .collect()
+}
+
+/// Whether any recorded source has moved upstream since it was recorded
+pub fn has_upstream_changes(recorded: &[DevelSource]) -> Result<bool> {
+    for source in recorded {
+        let current = VcsSource {
+            kind: source.kind,
+            name: None,
+            url: source.url.clone(),
+            fragment: source
+                .branch
+                .as_ref()
+                .map(|b| ("branch".to_string(), b.clone())),
+        };
+        if current.latest_revision()? != source.revision {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+fn run_vcs(cmd: &str, args: &[&str]) -> Result<String> {
+    let output = Command::new(cmd)
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .with_context(|| format!("Failed to run {} (is it installed?)", cmd))?;
+
+    if !output.status.success() {
+        anyhow::bail!("{} {} failed", cmd, args.join(" "));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkout_name() {
+        let name = |source: &str| VcsSource::parse(source).unwrap().checkout_name();
+        assert_eq!(name("git+https://github.com/Morganamilo/paru.git"), "paru");
+        assert_eq!(
+            name("paru-src::git+https://github.com/Morganamilo/paru.git#branch=master"),
+            "paru-src"
+        );
+        assert_eq!(
+            name("git+https://gitlab.com/foo/bar/#branch=develop"),
+            "bar"
+        );
+        assert_eq!(name("svn+https://svn.example.org/project/trunk"), "trunk");
+    }
+
+    #[test]
+    fn test_parse_git_sources() {
+        let source = VcsSource::parse("git+https://github.com/Morganamilo/paru.git").unwrap();
+        assert_eq!(source.kind, VcsKind::Git);
+        assert_eq!(source.url, "https://github.com/Morganamilo/paru.git");
+        assert_eq!(source.fragment, None);
+        assert!(!source.is_pinned());
+
+        let source = VcsSource::parse("git+https://gitlab.com/foo/bar.git#branch=develop").unwrap();
+        assert_eq!(source.url, "https://gitlab.com/foo/bar.git");
+        assert_eq!(
+            source.fragment,
+            Some(("branch".to_string(), "develop".to_string()))
+        );
+        assert!(!source.is_pinned());
+
+        let source =
+            VcsSource::parse("git+https://github.com/foo/bar.git#commit=0123abcd").unwrap();
+        assert!(source.is_pinned());
+        let source =
+            VcsSource::parse("git+https://github.com/foo/bar.git?signed#tag=v1.2.0").unwrap();
+        assert_eq!(source.url, "https://github.com/foo/bar.git");
+        assert!(source.is_pinned());
+
+        let source = VcsSource::parse("git://anongit.freedesktop.org/mesa/mesa").unwrap();
+        assert_eq!(source.kind, VcsKind::Git);
+        assert_eq!(source.url, "git://anongit.freedesktop.org/mesa/mesa");
+    }
+
+    #[test]
+    fn test_parse_renamed_sources() {
+        let source = VcsSource::parse("neovim::git+https://github.com/neovim/neovim.git").unwrap();
+        assert_eq!(source.kind, VcsKind::Git);
+        assert_eq!(source.url, "https://github.com/neovim/neovim.git");
+
+        let source =
+            VcsSource::parse("repo::hg+https://hg.example.org/repo#branch=stable").unwrap();
+        assert_eq!(source.kind, VcsKind::Hg);
+        assert_eq!(source.url, "https://hg.example.org/repo");
+
+        let source =
+            VcsSource::parse("trunk::svn+https://svn.example.org/trunk#revision=1234").unwrap();
+        assert_eq!(source.kind, VcsKind::Svn);
+        assert!(source.is_pinned());
+    }
+
+    #[test]
+    fn test_parse_non_vcs_sources() {
+        assert_eq!(VcsSource::parse("https://example.org/foo-1.0.tar.gz"), None);
+        assert_eq!(
+            VcsSource::parse("foo-1.0.tar.gz::https://github.com/foo/foo/archive/v1.0.tar.gz"),
+            None
+        );
+        assert_eq!(VcsSource::parse("fix-build.patch"), None);
+    }
+
+    #[test]
+    fn test_is_vcs_package() {
+        assert!(is_vcs_package("paru-git"));
+        assert!(is_vcs_package("neovim-nightly-hg"));
+        assert!(is_vcs_package("foo-svn"));
+        assert!(!is_vcs_package("paru"));
+        assert!(!is_vcs_package("paru-bin"));
+        assert!(!is_vcs_package("gitkraken"));
+    }
+
+    #[test]
+    fn test_vcs_sources() {
+        let srcinfo = SrcInfo::parse(
+            "pkgbase = foo-git\n\
+             \tpkgver = r123.abcdef0\n\
+             \tpkgrel = 1\n\
+             \tsource = foo::git+https://github.com/foo/foo.git\n\
+             \tsource = git+https://github.com/foo/submodule.git#commit=0123abcd\n\
+             \tsource = fix-build.patch\n\
+             \n\
+             pkgname = foo-git\n",
+        );
+        let sources = vcs_sources(&srcinfo);
+        assert_eq!(sources.len(), 1);
+        assert_eq!(sources[0].url, "https://github.com/foo/foo.git");
+    }
+}
@@ -0,0 +1,1472 @@
+mod chroot;
+mod comments;
+mod devel;
+mod pgp;
+mod srcinfo;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use rayon::prelude::*;
+
+use super::{bootstrap, sudo};
+use super::{InstallResult, Package, PackageExtra, PackageManager};
+pub use comments::AurComment;
+pub use srcinfo::SrcInfo;
+
+const AUR_RPC_URL: &str = "https://aur.archlinux.org/rpc/v5";
+const AUR_BASE_URL: &str = "https://aur.archlinux.org";
+const MAX_RESULTS: usize = 30;
+/// Packages submitted more recently than this get a warning before building
+const NEW_PACKAGE_DAYS: i64 = 14;
+
+#[derive(Debug, Deserialize)]
+struct AurApiPackage {
+    #[serde(rename = "ID")]
+    id: u64,
+    #[serde(rename = "Name")]
+    name: String,
+    #[serde(rename = "PackageBase")]
+    package_base: Option<String>,
+    #[serde(rename = "Description")]
+    description: Option<String>,
+    #[serde(rename = "Version")]
+    version: String,
+    #[serde(rename = "NumVotes")]
+    num_votes: u32,
+    #[serde(rename = "Popularity")]
+    popularity: f64,
+    #[serde(rename = "Maintainer")]
+    maintainer: Option<String>,
+    #[serde(rename = "URL")]
+    url: Option<String>,
+    #[serde(rename = "URLPath")]
+    url_path: Option<String>,
+    #[serde(rename = "OutOfDate")]
+    out_of_date: Option<u64>,
+    #[serde(rename = "FirstSubmitted")]
+    first_submitted: Option<i64>,
+    #[serde(rename = "LastModified")]
+    last_modified: Option<i64>,
+    #[serde(rename = "Replaces", default)]
+    replaces: Vec<String>,
+    #[serde(rename = "Depends", default)]
+    depends: Vec<String>,
+    #[serde(rename = "License", default)]
+    license: Vec<String>,
+}
+
+impl From<AurApiPackage> for Package {
+    fn from(aur: AurApiPackage) -> Self {
+        Package {
+            name: aur.name,
+            version: aur.version,
+            description: aur.description,
+            popularity: aur.popularity,
+            installed: false,
+            maintainer: aur.maintainer,
+            url: aur.url,
+            extra: PackageExtra {
+                aur_id: Some(aur.id),
+                aur_votes: Some(aur.num_votes),
+                aur_url_path: aur.url_path,
+                aur_package_base: aur.package_base,
+                aur_first_submitted: aur.first_submitted,
+                aur_last_modified: aur.last_modified,
+                aur_replaces: aur.replaces,
+                out_of_date: aur.out_of_date,
+                depends: aur.depends,
+                license: aur.license,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct AurResponse {
+    #[serde(rename = "type")]
+    #[allow(dead_code)]
+    response_type: String,
+    #[allow(dead_code)]
+    resultcount: u32,
+    results: Vec<AurApiPackage>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+static DEVEL_MODE: AtomicBool = AtomicBool::new(false);
+
+/// Also check VCS (-git, -svn, ...) packages for upstream commits in check_updates
+pub fn set_devel(value: bool) {
+    DEVEL_MODE.store(value, Ordering::Relaxed);
+}
+
+fn devel_mode() -> bool {
+    DEVEL_MODE.load(Ordering::Relaxed)
+}
+
+static CHROOT_MODE: AtomicBool = AtomicBool::new(false);
+
+/// Build AUR packages in a clean devtools chroot instead of the user environment
+pub fn set_chroot(value: bool) {
+    CHROOT_MODE.store(value, Ordering::Relaxed);
+}
+
+fn chroot_mode() -> bool {
+    CHROOT_MODE.load(Ordering::Relaxed)
+}
+
+/// AUR (Arch User Repository) package manager backend
+pub struct AurBackend {
+    client: reqwest::Client,
+    build_dir: PathBuf,
+    chroot: OnceLock<chroot::Chroot>,
+}
+
+/// The AUR packages a package depends on, directly or not
+struct AurDeps {
+    /// In the order they were found, so the deepest come last
+    packages: Vec<Package>,
+    /// The direct AUR dependencies of each package in the tree
+    edges: HashMap<String, Vec<String>>,
+}
+
+impl AurDeps {
+    /// Package files of the AUR dependencies of `name` that were built already,
+    /// to install into the chroot before building it
+    fn chroot_inputs(&self, name: &str, built: &HashMap<String, PathBuf>) -> Vec<PathBuf> {
+        let mut seen = HashSet::new();
+        let mut stack = vec![name];
+        let mut inputs = vec![];
+
+        while let Some(current) = stack.pop() {
+            for dep in self.edges.get(current).into_iter().flatten() {
+                if seen.insert(dep.as_str()) {
+                    inputs.extend(built.get(dep).cloned());
+                    stack.push(dep);
+                }
+            }
+        }
+        inputs
+    }
+}
+
+impl AurBackend {
+    pub fn new() -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .user_agent("zap/0.1.0")
+            .gzip(true)
+            .build()
+            .context("Failed to create HTTP client")?;
+
+        let build_dir = get_build_dir()?;
+        std::fs::create_dir_all(&build_dir)?;
+
+        Ok(Self {
+            client,
+            build_dir,
+            chroot: OnceLock::new(),
+        })
+    }
+
+    /// Clone the AUR git repository for a package, or fetch and merge upstream
+    /// changes into an existing clone. Returns the package directory.
+    pub fn sync_repo(&self, package: &Package) -> Result<PathBuf> {
+        let pkgbase = package
+            .extra
+            .aur_package_base
+            .as_deref()
+            .unwrap_or(&package.name);
+        let pkg_dir = self.build_dir.join(pkgbase);
+
+        if pkg_dir.join(".git").exists() {
+            println!("--> Fetching upstream changes for {}...", pkgbase);
+            let old_head = git_head(&pkg_dir);
+
+            run_git(&pkg_dir, &["fetch", "--quiet", "origin"])
+                .with_context(|| format!("Failed to fetch {}", pkgbase))?;
+            // pkgver() in VCS packages makes makepkg rewrite the PKGBUILD
+            run_git(&pkg_dir, &["reset", "--hard", "--quiet", "HEAD"])?;
+            run_git(
+                &pkg_dir,
+                &["merge", "--ff-only", "--quiet", "origin/master"],
+            )
+            .with_context(|| {
+                format!(
+                    "Failed to merge upstream changes for {} (resolve manually in {})",
+                    pkgbase,
+                    pkg_dir.display()
+                )
+            })?;
+
+            let new_head = git_head(&pkg_dir);
+            if let (Some(old), Some(new)) = (old_head, new_head) {
+                if old != new {
+                    show_repo_changes(&pkg_dir, &old, &new);
+                }
+            }
+        } else {
+            // Leftover from snapshot-based builds
+            if pkg_dir.exists() {
+                std::fs::remove_dir_all(&pkg_dir)?;
+            }
+
+            println!("--> Cloning {}...", pkgbase);
+            let url = format!("{}/{}.git", AUR_BASE_URL, pkgbase);
+            let dir = pkg_dir.to_string_lossy();
+            run_git(&self.build_dir, &["clone", "--quiet", &url, &dir])
+                .with_context(|| format!("Failed to clone {}", url))?;
+        }
+
+        if !pkg_dir.join("PKGBUILD").exists() {
+            anyhow::bail!("AUR repository for {} has no PKGBUILD", pkgbase);
+        }
+
+        Ok(pkg_dir)
+    }
+
+    /// Find the package file makepkg produces for the current PKGBUILD, if it
+    /// has already been built
+    fn find_built_package(&self, pkg_dir: &Path, pkg_name: &str) -> Option<PathBuf> {
+        package_list(pkg_dir)?
+            .into_iter()
+            .filter(|path| path.exists())
+            .find(|path| {
+                path.file_name()
+                    .map(|name| built_package_name(&name.to_string_lossy()) == Some(pkg_name))
+                    .unwrap_or(false)
+            })
+    }
+
+    /// Remove stale builds from the build directory. Clones of packages that are
+    /// no longer installed are deleted entirely; for installed packages only
+    /// outdated package files and makepkg work directories are removed.
+    /// With `all`, every clone is deleted.
+    pub fn clean(&self, all: bool) -> Result<CleanStats> {
+        let mut stats = CleanStats::default();
+
+        let entries = match std::fs::read_dir(&self.build_dir) {
+            Ok(entries) => entries,
+            Err(_) => return Ok(stats),
+        };
+
+        for entry in entries.filter_map(|e| e.ok()) {
+            let pkg_dir = entry.path();
+            if !pkg_dir.is_dir() {
+                continue;
+            }
+
+            let srcinfo = SrcInfo::from_dir(&pkg_dir).ok();
+            let installed = srcinfo
+                .as_ref()
+                .map(|info| {
+                    info.pkgnames
+                        .iter()
+                        .any(|name| self.is_installed(name).unwrap_or(false))
+                })
+                .unwrap_or(false);
+
+            if all || !installed {
+                std::fs::remove_dir_all(&pkg_dir)?;
+                stats.removed_repos += 1;
+                continue;
+            }
+
+            // Without the current package list nothing is known to be stale
+            let current = package_list(&pkg_dir).unwrap_or_default();
+            if !current.is_empty() {
+                for file in built_package_files(&pkg_dir) {
+                    if !current
+                        .iter()
+                        .any(|path| path.file_name() == file.file_name())
+                    {
+                        std::fs::remove_file(&file)?;
+                        stats.removed_files += 1;
+                    }
+                }
+            }
+
+            for work_dir in ["src", "pkg"] {
+                let path = pkg_dir.join(work_dir);
+                if path.is_dir() {
+                    std::fs::remove_dir_all(&path)?;
+                    stats.removed_files += 1;
+                }
+            }
+        }
+
+        Ok(stats)
+    }
+
+    /// Parse PKGBUILD to extract all dependencies
+    fn parse_pkgbuild_dependencies(&self, pkg_dir: &PathBuf) -> Result<Vec<String>> {
+        let pkgbuild = pkg_dir.join("PKGBUILD");
+        if !pkgbuild.exists() {
+            return Ok(vec![]);
+        }
+
+        let content = std::fs::read_to_string(&pkgbuild).context("Failed to read PKGBUILD")?;
+
+        let mut deps = Vec::new();
+        let mut in_array = false;
+        let mut current_deps = Vec::new();
+
+        for line in content.lines() {
+            let line = line.trim();
+
+            // Skip comments and empty lines
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            // Check for dependency arrays: depends, makedepends, checkdepends
+            if line.starts_with("depends=")
+                || line.starts_with("makedepends=")
+                || line.starts_with("checkdepends=")
+            {
+                // Check if it's an array assignment (bash array syntax)
+                if line.contains("(") {
+                    in_array = true;
+                    current_deps.clear();
+
+                    // Extract dependencies from the line
+                    if let Some(start) = line.find('(') {
+                        let rest = &line[start + 1..];
+
+                        // Check if array closes on same line
+                        if let Some(end) = rest.rfind(')') {
+                            let deps_str = &rest[..end];
+                            // Parse quoted strings
+                            let mut current = String::new();
+                            let mut in_quotes = false;
+                            let mut quote_char = '\0';
+
+                            for ch in deps_str.chars() {
+                                match ch {
+                                    '\'' | '"' if !in_quotes => {
+                                        in_quotes = true;
+                                        quote_char = ch;
+                                    }
+                                    '\'' | '"' if in_quotes && ch == quote_char => {
+                                        in_quotes = false;
+                                        quote_char = '\0';
+                                        if !current.is_empty() {
+                                            current_deps.push(current.clone());
+                                            current.clear();
+                                        }
+                                    }
+                                    _ if in_quotes => {
+                                        current.push(ch);
+                                    }
+                                    _ if ch.is_whitespace() && !in_quotes => {
+                                        if !current.is_empty() {
+                                            current_deps.push(current.clone());
+                                            current.clear();
+                                        }
+                                    }
+                                    _ if !in_quotes => {
+                                        current.push(ch);
+                                    }
+                                    _ => {}
+                                }
+                            }
+
+                            if !current.is_empty() {
+                                current_deps.push(current);
+                            }
+
+                            in_array = false;
+                            deps.extend(current_deps.drain(..));
+                        } else {
+                            // Multi-line array, continue reading
+                            let deps_str = rest;
+                            let mut current = String::new();
+                            let mut in_quotes = false;
+                            let mut quote_char = '\0';
+
+                            for ch in deps_str.chars() {
+                                match ch {
+                                    '\'' | '"' if !in_quotes => {
+                                        in_quotes = true;
+                                        quote_char = ch;
+                                    }
+                                    '\'' | '"' if in_quotes && ch == quote_char => {
+                                        in_quotes = false;
+                                        quote_char = '\0';
+                                        if !current.is_empty() {
+                                            current_deps.push(current.clone());
+                                            current.clear();
+                                        }
+                                    }
+                                    _ if in_quotes => {
+                                        current.push(ch);
+                                    }
+                                    _ if ch.is_whitespace() && !in_quotes => {
+                                        if !current.is_empty() {
+                                            current_deps.push(current.clone());
+                                            current.clear();
+                                        }
+                                    }
+                                    _ if !in_quotes => {
+                                        current.push(ch);
+                                    }
+                                    _ => {}
+                                }
+                            }
+                        }
+                    }
+                } else {
+                    // Single dependency (not an array)
+                    if let Some(dep) = line.split('=').nth(1) {
+                        let dep = dep.trim().trim_matches('"').trim_matches('\'');
+                        if !dep.is_empty() {
+                            deps.push(dep.to_string());
+                        }
+                    }
+                }
+            } else if in_array {
+                // Continue reading multi-line array
+                let line = line.trim();
+                if line == ")" {
+                    in_array = false;
+                    deps.extend(current_deps.drain(..));
+                } else {
+                    // Parse quoted strings from this line
+                    let mut current = String::new();
+                    let mut in_quotes = false;
+                    let mut quote_char = '\0';
+
+                    for ch in line.chars() {
+                        match ch {
+                            '\'' | '"' if !in_quotes => {
+                                in_quotes = true;
+                                quote_char = ch;
+                            }
+                            '\'' | '"' if in_quotes && ch == quote_char => {
+                                in_quotes = false;
+                                quote_char = '\0';
+                                if !current.is_empty() {
+                                    current_deps.push(current.clone());
+                                    current.clear();
+                                }
+                            }
+                            _ if in_quotes => {
+                                current.push(ch);
+                            }
+                            _ if ch.is_whitespace() && !in_quotes => {
+                                if !current.is_empty() {
+                                    current_deps.push(current.clone());
+                                    current.clear();
+                                }
+                            }
+                            _ if !in_quotes && ch != '(' && ch != ')' => {
+                                current.push(ch);
+                            }
+                            _ => {}
+                        }
+                    }
+
+                    if !current.is_empty() && in_quotes {
+                        // Unclosed quote, might continue on next line
+                    } else if !current.is_empty() {
+                        current_deps.push(current);
+                    }
+                }
+            }
+        }
+
+        // Clean up dependencies (remove version constraints, etc.)
+        let cleaned_deps: Vec<String> = deps
+            .iter()
+            .map(|dep| {
+                // Remove version constraints like >=, =, etc.
+                // Also handle package names with operators: package>=1.0 -> package
+                dep.split_whitespace()
+                    .next()
+                    .unwrap_or(dep)
+                    .split(|c: char| c == '>' || c == '<' || c == '=')
+                    .next()
+                    .unwrap_or(dep)
+                    .to_string()
+            })
+            .filter(|dep| !dep.is_empty() && dep != "(" && dep != ")")
+            .collect();
+
+        Ok(cleaned_deps)
+    }
+
+    /// Check if a package exists in the main Arch repositories
+    fn is_in_main_repos(&self, package: &str) -> bool {
+        // Remove version constraints if present
+        let pkg_name = package.split_whitespace().next().unwrap_or(package);
+
+        let output = Command::new("pacman")
+            .args(["-Ss", "^", pkg_name, "$"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .output();
+
+        if let Ok(output) = output {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            stdout.lines().any(|line| {
+                line.starts_with(&format!("extra/{}", pkg_name))
+                    || line.starts_with(&format!("community/{}", pkg_name))
+                    || line.starts_with(&format!("core/{}", pkg_name))
+                    || line.starts_with(&format!("multilib/{}", pkg_name))
+            })
+        } else {
+            false
+        }
+    }
+
+    /// Check if a package exists in AUR
+    async fn is_in_aur(&self, package: &str) -> bool {
+        // Remove version constraints
+        let pkg_name = package.split_whitespace().next().unwrap_or(package);
+
+        let url = format!("{}/info/{}", AUR_RPC_URL, urlencoded(pkg_name));
+        if let Ok(response) = self.client.get(&url).send().await {
+            if let Ok(aur_response) = response.json::<AurResponse>().await {
+                return !aur_response.results.is_empty();
+            }
+        }
+        false
+    }
+
+    /// Resolve AUR dependencies for packages iteratively (avoids recursion issues).
+    /// For a chroot build, installed dependencies are resolved too.
+    async fn resolve_aur_dependencies(&self, package: &Package, chroot: bool) -> Result<AurDeps> {
+        let mut all_deps = Vec::new();
+        let mut edges: HashMap<String, Vec<String>> = HashMap::new();
+        let mut visited = HashSet::new();
+        let mut to_process = vec![package.clone()];
+
+        while let Some(current_pkg) = to_process.pop() {
+            let pkg_name = &current_pkg.name;
+
+            // Skip if already processed
+            if visited.contains(pkg_name) {
+                continue;
+            }
+
+            visited.insert(pkg_name.clone());
+
+            // Clone or update the package repo and parse PKGBUILD to get dependencies
+            let pkg_dir = match self.sync_repo(&current_pkg) {
+                Ok(d) => d,
+                Err(e) => {
+                    eprintln!(
+                        "Warning: Could not fetch dependencies for {}: {}",
+                        pkg_name, e
+                    );
+                    continue;
+                }
+            };
+
+            let deps = match self.parse_pkgbuild_dependencies(&pkg_dir) {
+                Ok(d) => d,
+                Err(e) => {
+                    eprintln!(
+                        "Warning: Could not parse dependencies for {}: {}",
+                        pkg_name, e
+                    );
+                    continue;
+                }
+            };
+
+            // Resolve each dependency
+            for dep in deps {
+                // Skip if already installed, unless it has to be built for the chroot
+                if !chroot && self.is_installed(&dep).unwrap_or(false) {
+                    continue;
+                }
+
+                // Check if it's in main repos (pacman will handle it)
+                if self.is_in_main_repos(&dep) {
+                    continue;
+                }
+
+                // Check if it's in AUR
+                if self.is_in_aur(&dep).await {
+                    // Get package info from AUR
+                    if let Ok(mut aur_packages) = self.info(&[&dep]).await {
+                        if let Some(dep_pkg) = aur_packages.pop() {
+                            edges
+                                .entry(pkg_name.clone())
+                                .or_default()
+                                .push(dep_pkg.name.clone());
+                            // Add to list if not already present
+                            if !all_deps.iter().any(|p: &Package| p.name == dep_pkg.name) {
+                                all_deps.push(dep_pkg.clone());
+                                // Add to processing queue
+                                to_process.push(dep_pkg);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(AurDeps {
+            packages: all_deps,
+            edges,
+        })
+    }
+
+    /// Install a single package with dependency resolution. With `reinstall`,
+    /// packages that are already installed are rebuilt (used for updates).
+    async fn install_with_deps(&self, package: &Package, reinstall: bool) -> Result<()> {
+        // Check if already installed
+        if !reinstall && self.is_installed(&package.name)? {
+            return Ok(());
+        }
+
+        let chroot = chroot_mode();
+
+        // Resolve dependencies
+        println!("--> Resolving dependencies for {}...", package.name);
+        let tree = match self.resolve_aur_dependencies(package, chroot).await {
+            Ok(d) => d,
+            Err(e) => {
+                eprintln!("Warning: Could not fully resolve dependencies: {}", e);
+                // Continue anyway
+                AurDeps {
+                    packages: vec![],
+                    edges: HashMap::new(),
+                }
+            }
+        };
+        let deps = &tree.packages;
+        // Package files built so far, for the chroot builds of later packages
+        let mut built: HashMap<String, PathBuf> = HashMap::new();
+        let chroot_inputs = |name: &str, built: &HashMap<String, PathBuf>| {
+            chroot.then(|| tree.chroot_inputs(name, built))
+        };
+
+        let to_build: Vec<&Package> = deps
+            .iter()
+            .filter(|dep| !self.is_installed(&dep.name).unwrap_or(false))
+            .chain(std::iter::once(package))
+            .collect();
+        if !review_packages(&to_build)? {
+            anyhow::bail!("Installation of {} cancelled", package.name);
+        }
+
+        // Install dependencies first
+        if !deps.is_empty() {
+            println!("--> Installing {} dependencies...", deps.len());
+            let mut failed_deps = Vec::new();
+
+            // Dependencies found deeper in the tree come last, so install in reverse
+            for dep in deps.iter().rev() {
+                if self.is_installed(&dep.name).unwrap_or(false) {
+                    // The chroot still needs a package file of installed dependencies
+                    if chroot {
+                        let inputs = chroot_inputs(&dep.name, &built);
+                        match self.build_package(dep, inputs.as_deref()) {
+                            Ok((_, pkg_file)) => {
+                                built.insert(dep.name.clone(), pkg_file);
+                            }
+                            Err(e) => {
+                                eprintln!("  --> Warning: Failed to build {}: {}", dep.name, e)
+                            }
+                        }
+                    }
+                    continue;
+                }
+
+                println!("  --> Installing dependency: {}...", dep.name);
+                let inputs = chroot_inputs(&dep.name, &built);
+                match self.install_single_package(dep, inputs.as_deref()).await {
+                    Ok(pkg_file) => {
+                        println!("  --> {} installed successfully", dep.name);
+                        built.insert(dep.name.clone(), pkg_file);
+                    }
+                    Err(e) => {
+                        eprintln!("  --> Warning: Failed to install {}: {}", dep.name, e);
+                        failed_deps.push((dep.name.clone(), e));
+                        // Continue with other dependencies
+                    }
+                }
+            }
+
+            // If some dependencies failed, warn but continue
+            if !failed_deps.is_empty() {
+                eprintln!(
+                    "Warning: {} dependencies failed to install, continuing anyway...",
+                    failed_deps.len()
+                );
+            }
+        }
+
+        let inputs = chroot_inputs(&package.name, &built);
+        self.install_single_package(package, inputs.as_deref())
+            .await
+            .map(|_| ())
+    }
+
+    /// Install a single package without dependency resolution, built in the
+    /// chroot when `chroot_inputs` is given. Returns the package file.
+    async fn install_single_package(
+        &self,
+        package: &Package,
+        chroot_inputs: Option<&[PathBuf]>,
+    ) -> Result<PathBuf> {
+        let (pkg_dir, pkg_file) = self.build_package(package, chroot_inputs)?;
+
+        println!("--> Installing {}...", package.name);
+        let pkg_path_str = pkg_file.to_string_lossy();
+        let status = sudo::run_sudo(&["pacman", "-U", "--noconfirm", "--needed", &pkg_path_str])
+            .context("Failed to install package")?;
+
+        if !status.success() {
+            anyhow::bail!("Failed to install {}", package.name);
+        }
+
+        if let Err(e) = self.record_devel_revisions(&pkg_dir, &package.name) {
+            eprintln!("Warning: Could not record upstream revisions: {}", e);
+        }
+
+        println!("--> {} installed successfully!", package.name);
+        Ok(pkg_file)
+    }
+
+    /// Build a package without installing it. A package file built earlier for
+    /// the same version is reused instead of rebuilding. With `chroot_inputs`,
+    /// the build runs in the clean chroot with those package files of its AUR
+    /// dependencies installed. Returns the package directory and the package file.
+    fn build_package(
+        &self,
+        package: &Package,
+        chroot_inputs: Option<&[PathBuf]>,
+    ) -> Result<(PathBuf, PathBuf)> {
+        let pkg_dir = match self.sync_repo(package) {
+            Ok(d) => d,
+            Err(e) => anyhow::bail!("Failed to fetch {}: {}", package.name, e),
+        };
+
+        // VCS packages build whatever upstream has now, so a cached file is never
+        // current. A cached file may also come from a host build, which a chroot
+        // build must not reuse.
+        let cached = if devel::is_vcs_package(&package.name) || chroot_inputs.is_some() {
+            None
+        } else {
+            self.find_built_package(&pkg_dir, &package.name)
+        };
+
+        let pkg_file = match cached {
+            Some(cached) => {
+                println!("--> Reusing cached build of {}...", package.name);
+                cached
+            }
+            None => {
+                self.verify_sources(&pkg_dir, &package.name)?;
+
+                if let Some(inputs) = chroot_inputs {
+                    println!("--> Building {} in chroot...", package.name);
+                    self.chroot()?.build(&pkg_dir, inputs)?;
+                } else {
+                    println!("--> Building {}...", package.name);
+
+                    // makepkg -s installs missing repo dependencies (we handle AUR deps ourselves).
+                    // -f overwrites stale package files left from a previous build of this version.
+                    let status = Command::new("makepkg")
+                        .current_dir(&pkg_dir)
+                        .arg("-s")
+                        .arg("-f")
+                        .arg("--needed")
+                        .arg("--noconfirm")
+                        .stdin(Stdio::inherit())
+                        .stdout(Stdio::inherit())
+                        .stderr(Stdio::inherit())
+                        .status()
+                        .context("Failed to run makepkg")?;
+
+                    if !status.success() {
+                        anyhow::bail!("makepkg failed for {}", package.name);
+                    }
+                }
+
+                self.find_built_package(&pkg_dir, &package.name)
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("Could not find built package file for {}", package.name)
+                    })?
+            }
+        };
+
+        println!("--> Package file: {}", pkg_file.display());
+        Ok((pkg_dir, pkg_file))
+    }
+
+    /// Fetch the pinned and latest comments from a package's AUR page
+    pub async fn comments(&self, package: &str) -> Result<Vec<AurComment>> {
+        let url = format!("{}/packages/{}", AUR_BASE_URL, urlencoded(package));
+        let html = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .context("Failed to fetch AUR package page")?
+            .error_for_status()
+            .with_context(|| format!("Package '{}' not found in the AUR", package))?
+            .text()
+            .await
+            .context("Failed to read AUR package page")?;
+
+        Ok(comments::parse_comments(&html))
+    }
+
+    /// Download the sources and check their checksums and PGP signatures,
+    /// importing the keys listed in validpgpkeys if the user agrees
+    fn verify_sources(&self, pkg_dir: &Path, pkg_name: &str) -> Result<()> {
+        let srcinfo = SrcInfo::from_dir(pkg_dir)?;
+        let keyring = pgp::GpgKeyring::from_env();
+        pgp::ensure_keys(&keyring, pkg_name, &srcinfo.validpgpkeys, |missing| {
+            println!(
+                "--> {} is signed with keys missing from your keyring: {}",
+                pkg_name,
+                missing.join(", ")
+            );
+            bootstrap::confirm("Import them from the keyserver?")
+        })?;
+
+        println!("--> Verifying sources for {}...", pkg_name);
+        let status = Command::new("makepkg")
+            .current_dir(pkg_dir)
+            .args(["--verifysource", "-f", "--noconfirm"])
+            .stdin(Stdio::inherit())
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::inherit())
+            .status()
+            .context("Failed to run makepkg")?;
+
+        if status.success() {
+            return Ok(());
+        }
+
+        // Sources are downloaded now, so a quiet second run only repeats the checks
+        // and tells us which one failed
+        let output = Command::new("makepkg")
+            .current_dir(pkg_dir)
+            .args(["--verifysource", "-f", "--noconfirm"])
+            .stdin(Stdio::null())
+            .output()
+            .context("Failed to run makepkg")?;
+        let log = format!(
+            "{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        match pgp::classify_failure(pkg_name, &log) {
+            Some(err) => Err(err.into()),
+            None => anyhow::bail!("Could not download sources for {}", pkg_name),
+        }
+    }
+
+    /// The build chroot, created or updated on first use
+    fn chroot(&self) -> Result<&chroot::Chroot> {
+        if let Some(chroot) = self.chroot.get() {
+            return Ok(chroot);
+        }
+
+        let chroot = chroot::Chroot::locate()?;
+        chroot.prepare()?;
+        Ok(self.chroot.get_or_init(|| chroot))
+    }
+
+    /// Build packages and their AUR dependencies in the clean chroot without
+    /// installing anything on the host. Returns the built package files.
+    pub async fn build(&self, packages: &[Package]) -> Result<Vec<PathBuf>> {
+        let mut outputs = vec![];
+        let mut built: HashMap<String, PathBuf> = HashMap::new();
+
+        for package in packages {
+            println!("--> Resolving dependencies for {}...", package.name);
+            let tree = self.resolve_aur_dependencies(package, true).await?;
+
+            for dep in tree.packages.iter().rev().chain(std::iter::once(package)) {
+                let inputs = tree.chroot_inputs(&dep.name, &built);
+                let (_, pkg_file) = self
+                    .build_package(dep, Some(&inputs))
+                    .with_context(|| format!("Failed to build {}", dep.name))?;
+                built.insert(dep.name.clone(), pkg_file.clone());
+                if !outputs.contains(&pkg_file) {
+                    outputs.push(pkg_file);
+                }
+            }
+        }
+
+        Ok(outputs)
+    }
+
+    /// Remember which upstream revision a VCS package was built from
+    fn record_devel_revisions(&self, pkg_dir: &Path, pkg_name: &str) -> Result<()> {
+        let srcinfo = SrcInfo::from_dir(pkg_dir)?;
+        let sources = devel::vcs_sources(&srcinfo);
+        if sources.is_empty() {
+            return Ok(());
+        }
+
+        let path = get_devel_db_path()?;
+        let mut db = devel::DevelDb::load(&path)?;
+        db.record(pkg_name, devel::built_revisions(&sources, pkg_dir)?);
+        db.save(&path)
+    }
+
+    /// Installed VCS packages whose upstream moved since they were built.
+    /// Packages without a recorded revision are seeded so the next check can compare.
+    async fn check_devel_updates(&self, installed: &[(String, String)]) -> Result<Vec<String>> {
+        let path = get_devel_db_path()?;
+        let mut db = devel::DevelDb::load(&path)?;
+
+        let (tracked, untracked): (Vec<&str>, Vec<&str>) = installed
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .filter(|name| devel::is_vcs_package(name) || db.get(name).is_some())
+            .partition(|name| db.get(name).is_some());
+
+        println!(
+            "--> Checking {} development packages upstream...",
+            tracked.len()
+        );
+        let outdated: Vec<String> = tracked
+            .par_iter()
+            .filter(|name| {
+                db.get(name)
+                    .map(|sources| devel::has_upstream_changes(sources).unwrap_or(false))
+                    .unwrap_or(false)
+            })
+            .map(|name| name.to_string())
+            .collect();
+
+        if !untracked.is_empty() {
+            let packages = self.info(&untracked).await?;
+            let seeded: Vec<(String, Vec<devel::DevelSource>)> = packages
+                .par_iter()
+                .filter_map(|pkg| {
+                    let pkg_dir = self.sync_repo(pkg).ok()?;
+                    let srcinfo = SrcInfo::from_dir(&pkg_dir).ok()?;
+                    let sources = devel::vcs_sources(&srcinfo);
+                    let revisions = devel::current_revisions(&sources).ok()?;
+                    Some((pkg.name.clone(), revisions))
+                })
+                .collect();
+
+            if !seeded.is_empty() {
+                println!(
+                    "--> Recorded upstream revisions for {} untracked development packages",
+                    seeded.len()
+                );
+            }
+            for (name, revisions) in seeded {
+                db.record(&name, revisions);
+            }
+            db.save(&path)?;
+        }
+
+        Ok(outdated)
+    }
+
+    fn build_and_install(&self, pkg_dir: &PathBuf, pkg_name: &str) -> Result<()> {
+        let pkgbuild = pkg_dir.join("PKGBUILD");
+        if !pkgbuild.exists() {
+            anyhow::bail!("PKGBUILD not found in {}", pkg_dir.display());
+        }
+
+        let status = Command::new("makepkg")
+            .current_dir(pkg_dir)
+            .arg("-si")
+            .arg("--needed")
+            .arg("--noconfirm")
+            .stdin(Stdio::inherit())
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::inherit())
+            .status()
+            .context("Failed to run makepkg")?;
+
+        if !status.success() {
+            anyhow::bail!("makepkg failed for {}", pkg_name);
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PackageManager for AurBackend {
+    fn name(&self) -> &str {
+        "AUR (Arch User Repository)"
+    }
+
+    fn id(&self) -> &str {
+        "aur"
+    }
+
+    async fn search(&self, query: &str) -> Result<Vec<Package>> {
+        if query.len() < 2 {
+            return Ok(vec![]);
+        }
+
+        // Try name-only search first
+        let url = format!("{}/search/{}?by=name", AUR_RPC_URL, urlencoded(query));
+
+        let response: AurResponse = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .context("Failed to send search request")?
+            .json()
+            .await
+            .context("Failed to parse search response")?;
+
+        if response.error.is_some() || response.results.is_empty() {
+            // Fall back to name-desc search
+            let url = format!("{}/search/{}?by=name-desc", AUR_RPC_URL, urlencoded(query));
+
+            let response: AurResponse = self
+                .client
+                .get(&url)
+                .send()
+                .await
+                .context("Failed to send search request")?
+                .json()
+                .await
+                .context("Failed to parse search response")?;
+
+            if let Some(error) = response.error {
+                if error.contains("Too many") {
+                    return Ok(vec![]);
+                }
+                anyhow::bail!("AUR API error: {}", error);
+            }
+
+            let mut results: Vec<Package> =
+                response.results.into_iter().map(Package::from).collect();
+            results.sort_by(|a, b| {
+                b.popularity
+                    .partial_cmp(&a.popularity)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+            results.truncate(MAX_RESULTS);
+            return Ok(results);
+        }
+
+        let mut results: Vec<Package> = response.results.into_iter().map(Package::from).collect();
+        results.sort_by(|a, b| {
+            b.popularity
+                .partial_cmp(&a.popularity)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        results.truncate(MAX_RESULTS);
+        Ok(results)
+    }
+
+    async fn info(&self, packages: &[&str]) -> Result<Vec<Package>> {
+        if packages.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let args: String = packages
+            .iter()
+            .map(|p| format!("arg[]={}", urlencoded(p)))
+            .collect::<Vec<_>>()
+            .join("&");
+
+        let url = format!("{}/info?{}", AUR_RPC_URL, args);
+
+        let response: AurResponse = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .context("Failed to send info request")?
+            .json()
+            .await
+            .context("Failed to parse info response")?;
+
+        if let Some(error) = response.error {
+            anyhow::bail!("AUR API error: {}", error);
+        }
+
+        // Filter out packages without URL paths - these are in AUR database but not installable
+        // (they're likely in main repos and shouldn't be installed via AUR)
+        let aur_packages: Vec<Package> = response
+            .results
+            .into_iter()
+            .map(Package::from)
+            .filter(|pkg| {
+                // Only return packages that have URL paths (are installable via AUR)
+                // Packages without URL paths are in the AUR database but not actually AUR packages
+                pkg.extra.aur_url_path.is_some()
+            })
+            .collect();
+
+        Ok(aur_packages)
+    }
+
+    async fn install(&self, packages: &[Package]) -> Result<Vec<InstallResult>> {
+        let mut results = vec![];
+
+        for package in packages {
+            // Validate that package has AUR URL path before attempting installation
+            if package.extra.aur_url_path.is_none() {
+                results.push(InstallResult {
+                    package: package.name.clone(),
+                    success: false,
+                    message: Some(format!(
+                        "Package {} has no AUR URL path (likely in main repos, try pacman instead)",
+                        package.name
+                    )),
+                });
+                continue;
+            }
+
+            let result = self.install_with_deps(package, false).await;
+
+            results.push(InstallResult {
+                package: package.name.clone(),
+                success: result.is_ok(),
+                message: result.err().map(|e| describe_error(&e)),
+            });
+        }
+
+        Ok(results)
+    }
+
+    async fn remove(&self, packages: &[&str]) -> Result<Vec<InstallResult>> {
+        super::pacman::PacmanBackend::new()?.remove(packages).await
+    }
+
+    fn is_installed(&self, package: &str) -> Result<bool> {
+        let output = Command::new("pacman")
+            .args(["-Q", package])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()?;
+
+        Ok(output.success())
+    }
+
+    fn owns(&self, path: &Path) -> Result<Option<(String, String)>> {
+        super::pacman::query_owner(path, "-Qm")
+    }
+
+    fn list_installed(&self) -> Result<Vec<(String, String)>> {
+        let output = Command::new("pacman")
+            .args(["-Qm"]) // Foreign packages (AUR)
+            .output()?;
+
+        if !output.status.success() {
+            return Ok(vec![]);
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout
+            .lines()
+            .filter_map(|line| {
+                let parts: Vec<_> = line.split_whitespace().collect();
+                if parts.len() >= 2 {
+                    Some((parts[0].to_string(), parts[1].to_string()))
+                } else {
+                    None
+                }
+            })
+            .collect())
+    }
+
+    async fn check_updates(&self) -> Result<Vec<Package>> {
+        let installed = self.list_installed()?;
+        if installed.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let names: Vec<&str> = installed.iter().map(|(n, _)| n.as_str()).collect();
+        let aur_packages = self.info(&names).await?;
+
+        let mut updates = vec![];
+        for pkg in aur_packages {
+            if let Some((_, installed_ver)) = installed.iter().find(|(n, _)| *n == pkg.name) {
+                if installed_ver != &pkg.version {
+                    updates.push(pkg);
+                }
+            }
+        }
+
+        if devel_mode() {
+            let outdated = self.check_devel_updates(&installed).await?;
+            let outdated: Vec<&str> = outdated
+                .iter()
+                .map(|n| n.as_str())
+                .filter(|name| !updates.iter().any(|p| p.name == *name))
+                .collect();
+            if !outdated.is_empty() {
+                updates.extend(self.info(&outdated).await?);
+            }
+        }
+
+        Ok(updates)
+    }
+
+    async fn update(&self, packages: &[Package]) -> Result<Vec<InstallResult>> {
+        let mut results = vec![];
+
+        for package in packages {
+            let result = self.install_with_deps(package, true).await;
+
+            results.push(InstallResult {
+                package: package.name.clone(),
+                success: result.is_ok(),
+                message: result.err().map(|e| describe_error(&e)),
+            });
+        }
+
+        Ok(results)
+    }
+}
+
+/// Summary of what `AurBackend::clean` removed
+#[derive(Debug, Default)]
+pub struct CleanStats {
+    pub removed_repos: usize,
+    pub removed_files: usize,
+}
+
+fn run_git(dir: &Path, args: &[&str]) -> Result<()> {
+    let status = Command::new("git")
+        .current_dir(dir)
+        .args(args)
+        .stdin(Stdio::null())
+        .status()
+        .context("Failed to run git (is it installed?)")?;
+
+    if !status.success() {
+        anyhow::bail!("git {} failed", args.join(" "));
+    }
+    Ok(())
+}
+
+fn git_head(dir: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .current_dir(dir)
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Print the commits and files changed between two revisions of a package repo
+fn show_repo_changes(dir: &Path, old: &str, new: &str) {
+    let range = format!("{}..{}", old, new);
+    println!("--> Upstream changes:");
+    let _ = Command::new("git")
+        .current_dir(dir)
+        .args(["--no-pager", "log", "--oneline", &range])
+        .status();
+    let _ = Command::new("git")
+        .current_dir(dir)
+        .args(["--no-pager", "diff", "--stat", old, new])
+        .status();
+}
+
+/// List built package files (*.pkg.tar.*, excluding signatures) in a directory
+fn built_package_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return vec![];
+    };
+
+    entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|path| {
+            let name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            name.contains(".pkg.tar") && !name.ends_with(".sig")
+        })
+        .collect()
+}
+
+/// Warnings about AUR packages that deserve a closer look before building
+fn health_warnings(pkg: &Package, now: i64) -> Vec<String> {
+    let mut warnings = vec![];
+
+    if pkg.maintainer.is_none() {
+        warnings.push("is orphaned (no maintainer)".to_string());
+    }
+
+    if let Some(flagged) = pkg.extra.out_of_date {
+        warnings.push(format!(
+            "was flagged out of date on {}",
+            format_date(flagged as i64)
+        ));
+    }
+
+    if let Some(submitted) = pkg.extra.aur_first_submitted {
+        let days = (now - submitted) / 86_400;
+        if days < NEW_PACKAGE_DAYS {
+            warnings.push(format!(
+                "was submitted only {} days ago ({})",
+                days,
+                format_date(submitted)
+            ));
+        }
+    }
+
+    if !pkg.extra.aur_replaces.is_empty() {
+        warnings.push(format!(
+            "replaces {} (renamed or taking over another package)",
+            pkg.extra.aur_replaces.join(", ")
+        ));
+    }
+
+    warnings
+}
+
+/// Print health warnings for packages about to be built and ask whether to continue
+fn review_packages(packages: &[&Package]) -> Result<bool> {
+    let now = chrono::Utc::now().timestamp();
+    let mut any = false;
+
+    for pkg in packages {
+        for warning in health_warnings(pkg, now) {
+            eprintln!("Warning: {} {}", pkg.name, warning);
+            any = true;
+        }
+    }
+
+    if !any {
+        return Ok(true);
+    }
+    bootstrap::confirm_unsafe("Review the PKGBUILDs and comments (zap info --comments). Continue?")
+}
+
+/// Format a unix timestamp as a UTC date
+fn format_date(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|| timestamp.to_string())
+}
+
+/// Error message for a failed build, with a hint for source verification failures
+fn describe_error(err: &anyhow::Error) -> String {
+    let hint = match err.downcast_ref::<pgp::VerificationError>() {
+        Some(pgp::VerificationError::MissingKeys { keys, .. }) => format!(
+            " (import with: gpg --recv-keys {}, or rerun and accept the import)",
+            keys.join(" ")
+        ),
+        Some(pgp::VerificationError::Signature { .. }) => {
+            " (do not install; check the AUR comments or contact the maintainer)".to_string()
+        }
+        Some(pgp::VerificationError::Checksum { .. }) => {
+            " (upstream may have re-released the file; check the AUR comments)".to_string()
+        }
+        None => String::new(),
+    };
+    format!("{}{}", err, hint)
+}
+
+/// The package files makepkg would build in `pkg_dir`. This sources the
+/// PKGBUILD, so the version reflects pkgver() rewrites done by a VCS build
+/// rather than the stale .SRCINFO.
+fn package_list(pkg_dir: &Path) -> Option<Vec<PathBuf>> {
+    let output = Command::new("makepkg")
+        .current_dir(pkg_dir)
+        .arg("--packagelist")
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    Some(
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(PathBuf::from)
+            .collect(),
+    )
+}
+
+/// Package name from a package file name (name-pkgver-pkgrel-arch.pkg.tar.*)
+fn built_package_name(file_name: &str) -> Option<&str> {
+    let stem = &file_name[..file_name.find(".pkg.tar")?];
+    stem.rsplitn(4, '-').nth(3)
+}
+
+fn get_devel_db_path() -> Result<PathBuf> {
+    let dirs = directories::ProjectDirs::from("com", "zap", "zap")
+        .ok_or_else(|| anyhow::anyhow!("Could not determine cache directory"))?;
+    Ok(dirs.cache_dir().join("devel.json"))
+}
+
+fn get_build_dir() -> Result<PathBuf> {
+    let dirs = directories::ProjectDirs::from("com", "zap", "zap")
+        .ok_or_else(|| anyhow::anyhow!("Could not determine build directory"))?;
+    Ok(dirs.cache_dir().join("builds"))
+}
+
+fn urlencoded(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' | '.' | '~' => c.to_string(),
+            ' ' => "+".to_string(),
+            _ => format!("%{:02X}", c as u8),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chroot_inputs() {
+        // app -> libfoo -> libbar; tool is built in the same session but unrelated
+        let deps = AurDeps {
+            packages: vec![],
+            edges: HashMap::from([
+                ("app".to_string(), vec!["libfoo".to_string()]),
+                ("libfoo".to_string(), vec!["libbar".to_string()]),
+            ]),
+        };
+        let built = HashMap::from([
+            (
+                "libbar".to_string(),
+                PathBuf::from("libbar-1-1-any.pkg.tar.zst"),
+            ),
+            (
+                "libfoo".to_string(),
+                PathBuf::from("libfoo-1-1-any.pkg.tar.zst"),
+            ),
+            (
+                "tool".to_string(),
+                PathBuf::from("tool-1-1-any.pkg.tar.zst"),
+            ),
+        ]);
+
+        assert_eq!(
+            deps.chroot_inputs("app", &built),
+            [
+                PathBuf::from("libfoo-1-1-any.pkg.tar.zst"),
+                PathBuf::from("libbar-1-1-any.pkg.tar.zst"),
+            ]
+        );
+        assert!(deps.chroot_inputs("libbar", &built).is_empty());
+        assert!(deps.chroot_inputs("tool", &built).is_empty());
+    }
+}
@@ -0,0 +1,194 @@
+use anyhow::{Context, Result};
+use std::process::{Command, Stdio};
+
+const DEFAULT_KEYSERVER: &str = "hkps://keyserver.ubuntu.com";
+
+/// Why makepkg refused a package's sources
+#[derive(Debug, thiserror::Error)]
+pub enum VerificationError {
+    #[error("{package} needs PGP keys that are not in your keyring: {}", .keys.join(", "))]
+    MissingKeys { package: String, keys: Vec<String> },
+
+    #[error(
+        "PGP signature verification failed for {package}; the sources may have been tampered with"
+    )]
+    Signature { package: String },
+
+    #[error("checksum verification failed for {package}; the sources do not match the PKGBUILD")]
+    Checksum { package: String },
+}
+
+/// Access to the user's PGP keyring
+pub trait Keyring {
+    fn has_key(&self, key: &str) -> bool;
+    fn import(&self, key: &str) -> Result<()>;
+}
+
+/// The gpg keyring makepkg verifies signatures against
+pub struct GpgKeyring {
+    keyserver: String,
+}
+
+impl GpgKeyring {
+    /// Keys are fetched from `$ZAP_KEYSERVER`, or keyserver.ubuntu.com
+    pub fn from_env() -> Self {
+        let keyserver =
+            std::env::var("ZAP_KEYSERVER").unwrap_or_else(|_| DEFAULT_KEYSERVER.to_string());
+        Self { keyserver }
+    }
+}
+
+impl Keyring for GpgKeyring {
+    fn has_key(&self, key: &str) -> bool {
+        Command::new("gpg")
+            .args(["--list-keys", key])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map(|s| s.success())
+            .unwrap_or(false)
+    }
+
+    fn import(&self, key: &str) -> Result<()> {
+        let status = Command::new("gpg")
+            .args(["--keyserver", &self.keyserver, "--recv-keys", key])
+            .status()
+            .context("Failed to run gpg (is gnupg installed?)")?;
+
+        if !status.success() {
+            anyhow::bail!("Could not import key {} from {}", key, self.keyserver);
+        }
+        Ok(())
+    }
+}
+
+/// Make sure every key in `keys` is in the keyring. Missing keys are imported
+/// after `confirm` agrees; otherwise a `MissingKeys` error is returned.
+pub fn ensure_keys(
+    keyring: &dyn Keyring,
+    package: &str,
+    keys: &[String],
+    confirm: impl FnOnce(&[String]) -> Result<bool>,
+) -> Result<()> {
+    let missing: Vec<String> = keys
+        .iter()
+        .filter(|key| !keyring.has_key(key))
+        .cloned()
+        .collect();
+
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    if !confirm(&missing)? {
+        return Err(VerificationError::MissingKeys {
+            package: package.to_string(),
+            keys: missing,
+        }
+        .into());
+    }
+
+    for key in &missing {
+        println!("--> Importing PGP key {}...", key);
+        keyring.import(key)?;
+    }
+    Ok(())
+}
+
+/// Classify the output of a failed `makepkg --verifysource`
+pub fn classify_failure(package: &str, output: &str) -> Option<VerificationError> {
+    let package = package.to_string();
+    if output.contains("PGP signatures could not be verified") {
+        Some(VerificationError::Signature { package })
+    } else if output.contains("did not pass the validity check") {
+        Some(VerificationError::Checksum { package })
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    struct MockKeyring {
+        keys: RefCell<Vec<String>>,
+        imported: RefCell<Vec<String>>,
+    }
+
+    impl MockKeyring {
+        fn with_keys(keys: &[&str]) -> Self {
+            Self {
+                keys: RefCell::new(keys.iter().map(|k| k.to_string()).collect()),
+                imported: RefCell::new(vec![]),
+            }
+        }
+    }
+
+    impl Keyring for MockKeyring {
+        fn has_key(&self, key: &str) -> bool {
+            self.keys.borrow().iter().any(|k| k == key)
+        }
+
+        fn import(&self, key: &str) -> Result<()> {
+            self.keys.borrow_mut().push(key.to_string());
+            self.imported.borrow_mut().push(key.to_string());
+            Ok(())
+        }
+    }
+
+    fn keys(keys: &[&str]) -> Vec<String> {
+        keys.iter().map(|k| k.to_string()).collect()
+    }
+
+    #[test]
+    fn test_present_keys_need_no_prompt() {
+        let keyring = MockKeyring::with_keys(&["AAAA", "BBBB"]);
+        ensure_keys(&keyring, "foo", &keys(&["AAAA", "BBBB"]), |_| {
+            panic!("should not prompt")
+        })
+        .unwrap();
+        assert!(keyring.imported.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_missing_keys_are_imported() {
+        let keyring = MockKeyring::with_keys(&["AAAA"]);
+        ensure_keys(&keyring, "foo", &keys(&["AAAA", "BBBB"]), |missing| {
+            assert_eq!(missing, ["BBBB"]);
+            Ok(true)
+        })
+        .unwrap();
+        assert_eq!(*keyring.imported.borrow(), ["BBBB"]);
+    }
+
+    #[test]
+    fn test_declined_import_reports_missing_keys() {
+        let keyring = MockKeyring::with_keys(&[]);
+        let err = ensure_keys(&keyring, "foo", &keys(&["AAAA"]), |_| Ok(false)).unwrap_err();
+        match err.downcast_ref::<VerificationError>() {
+            Some(VerificationError::MissingKeys { package, keys }) => {
+                assert_eq!(package, "foo");
+                assert_eq!(keys, &["AAAA"]);
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+        assert!(keyring.imported.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_classify_failure() {
+        let out = "==> ERROR: One or more PGP signatures could not be verified!";
+        assert!(matches!(
+            classify_failure("foo", out),
+            Some(VerificationError::Signature { .. })
+        ));
+        let out = "==> ERROR: One or more files did not pass the validity check!";
+        assert!(matches!(
+            classify_failure("foo", out),
+            Some(VerificationError::Checksum { .. })
+        ));
+        assert!(classify_failure("foo", "==> ERROR: Failure while downloading").is_none());
+    }
+}
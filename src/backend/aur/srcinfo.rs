@@ -0,0 +1,86 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+
+/// Parsed contents of an AUR package's .SRCINFO file
+#[derive(Debug, Clone, Default)]
+pub struct SrcInfo {
+    pub pkgnames: Vec<String>,
+    pub source: Vec<String>,
+    pub validpgpkeys: Vec<String>,
+}
+
+impl SrcInfo {
+    /// Read and parse the .SRCINFO file in a package directory
+    pub fn from_dir(pkg_dir: &Path) -> Result<Self> {
+        let path = pkg_dir.join(".SRCINFO");
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Ok(Self::parse(&content))
+    }
+
+    pub fn parse(content: &str) -> Self {
+        let mut info = SrcInfo::default();
+
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let key = key.trim();
+            let value = value.trim().to_string();
+
+            match key {
+                "pkgname" => info.pkgnames.push(value),
+                "validpgpkeys" => info.validpgpkeys.push(value),
+                // Includes architecture specific sources (source_x86_64, ...)
+                _ if key == "source" || key.starts_with("source_") => info.source.push(value),
+                _ => {}
+            }
+        }
+
+        info
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let info = SrcInfo::parse(
+            "pkgbase = foo-git
+	pkgdesc = Foo tools
+	pkgver = 1.2.r3.gabcdef
+	pkgrel = 1
+	arch = x86_64
+	arch = aarch64
+	source = foo::git+https://example.com/foo.git
+	source_x86_64 = https://example.com/blob-x86_64.tar.gz
+	validpgpkeys = 0123456789ABCDEF0123456789ABCDEF01234567
+	sha256sums = SKIP
+
+pkgname = foo-git
+
+pkgname = libfoo-git
+	pkgdesc = Foo library
+",
+        );
+        assert_eq!(info.pkgnames, ["foo-git", "libfoo-git"]);
+        assert_eq!(
+            info.source,
+            [
+                "foo::git+https://example.com/foo.git",
+                "https://example.com/blob-x86_64.tar.gz",
+            ]
+        );
+        assert_eq!(
+            info.validpgpkeys,
+            ["0123456789ABCDEF0123456789ABCDEF01234567"]
+        );
+    }
+}
@@ -67,7 +67,32 @@ fn confirm_install(target: BootstrapTarget) -> Result<bool> {
     }
 
     println!("{} is required but not installed.", target.display_name());
-    print!("Install {} now? [Y/n]: ", target.display_name());
+    ask(&format!("Install {} now?", target.display_name()))
+}
+
+/// Ask a yes/no question (default yes); `--yes` answers it automatically
+pub(crate) fn confirm(question: &str) -> Result<bool> {
+    if auto_approve() {
+        println!("--> {} [auto-confirmed]", question);
+        return Ok(true);
+    }
+    ask(question)
+}
+
+/// Ask a yes/no question that defaults to no, for safety checks that
+/// `--yes` must not answer
+pub(crate) fn confirm_unsafe(question: &str) -> Result<bool> {
+    print!("{} [y/N]: ", question);
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let trimmed = input.trim().to_lowercase();
+    Ok(trimmed == "y" || trimmed == "yes")
+}
+
+fn ask(question: &str) -> Result<bool> {
+    print!("{} [Y/n]: ", question);
     io::stdout().flush()?;
 
     let mut input = String::new();
@@ -77,7 +102,9 @@ fn confirm_install(target: BootstrapTarget) -> Result<bool> {
 }
 
 impl BootstrapTarget {
-    fn display_name(&self) -> &'static str {
+    pub(crate) const ALL: [Self; 4] = [Self::Winget, Self::Scoop, Self::Choco, Self::Python];
+
+    pub(crate) fn display_name(&self) -> &'static str {
         match self {
             Self::Winget => "winget",
             Self::Scoop => "Scoop",
@@ -95,14 +122,25 @@ impl BootstrapTarget {
         }
     }
 
-    fn supported_on_current_platform(&self) -> bool {
+    /// A cheap invocation that fails when the tool is installed but broken
+    pub(crate) fn health_check(&self) -> Option<[&'static str; 2]> {
+        match self {
+            Self::Python => ["python3", "python", "py"]
+                .into_iter()
+                .find(|cmd| command_exists(cmd))
+                .map(|cmd| [cmd, "--version"]),
+            _ => self.command().map(|cmd| [cmd, "--version"]),
+        }
+    }
+
+    pub(crate) fn supported_on_current_platform(&self) -> bool {
         match self {
             Self::Winget | Self::Scoop | Self::Choco => cfg!(target_os = "windows"),
             Self::Python => true,
         }
     }
 
-    fn is_installed(&self) -> bool {
+    pub(crate) fn is_installed(&self) -> bool {
         match self {
             Self::Python => {
                 command_exists("python3") || command_exists("python") || command_exists("py")
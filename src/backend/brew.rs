@@ -1,6 +1,7 @@
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use serde::Deserialize;
+use std::path::Path;
 use std::process::{Command, Stdio};
 
 use super::{InstallResult, Package, PackageExtra, PackageManager};
@@ -249,6 +250,34 @@ impl PackageManager for BrewBackend {
         Ok(results)
     }
 
+    async fn remove(&self, packages: &[&str]) -> Result<Vec<InstallResult>> {
+        let mut results = vec![];
+
+        for package in packages {
+            println!("--> Removing {}...", package);
+
+            let status = Command::new("brew")
+                .args(["uninstall", package])
+                .stdin(Stdio::inherit())
+                .stdout(Stdio::inherit())
+                .stderr(Stdio::inherit())
+                .status()
+                .context("Failed to run brew uninstall")?;
+
+            results.push(InstallResult {
+                package: package.to_string(),
+                success: status.success(),
+                message: if status.success() {
+                    None
+                } else {
+                    Some("brew uninstall failed".to_string())
+                },
+            });
+        }
+
+        Ok(results)
+    }
+
     fn is_installed(&self, package: &str) -> Result<bool> {
         // Check formulae
         let formula_check = Command::new("brew")
@@ -340,6 +369,55 @@ impl PackageManager for BrewBackend {
 
         Ok(updates)
     }
+
+    fn owns(&self, path: &Path) -> Result<Option<(String, String)>> {
+        // Linked files are symlinks into Cellar/<formula>/<version> or Caskroom/<cask>/<version>
+        Ok(path
+            .canonicalize()
+            .ok()
+            .and_then(|target| keg_owner(&target)))
+    }
+
+    async fn provides(&self, target: &str) -> Result<Vec<Package>> {
+        let Some(command) = super::command_name(target) else {
+            return Ok(vec![]);
+        };
+
+        // Looks the command up in Homebrew's index of formula executables
+        let output = Command::new("brew")
+            .args(["which-formula", command])
+            .output()
+            .context("Failed to run brew which-formula")?;
+
+        if !output.status.success() {
+            return Ok(vec![]);
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let names: Vec<&str> = stdout
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .collect();
+        self.info(&names).await
+    }
+}
+
+/// Formula or cask and version of a path inside `Cellar/<formula>/<version>`
+/// or `Caskroom/<cask>/<version>`
+fn keg_owner(target: &Path) -> Option<(String, String)> {
+    let components: Vec<String> = target
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+
+    let i = components
+        .iter()
+        .position(|c| c == "Cellar" || c == "Caskroom")?;
+    Some((
+        components.get(i + 1)?.clone(),
+        components.get(i + 2)?.clone(),
+    ))
 }
 
 fn command_exists(cmd: &str) -> bool {
@@ -359,3 +437,24 @@ fn urlencoded(s: &str) -> String {
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keg_owner() {
+        assert_eq!(
+            keg_owner(Path::new("/opt/homebrew/Cellar/ripgrep/14.1.0/bin/rg")),
+            Some(("ripgrep".to_string(), "14.1.0".to_string()))
+        );
+        assert_eq!(
+            keg_owner(Path::new(
+                "/usr/local/Caskroom/visual-studio-code/1.92.0/Visual Studio Code.app"
+            )),
+            Some(("visual-studio-code".to_string(), "1.92.0".to_string()))
+        );
+        assert_eq!(keg_owner(Path::new("/opt/homebrew/Cellar/ripgrep")), None);
+        assert_eq!(keg_owner(Path::new("/usr/bin/rg")), None);
+    }
+}
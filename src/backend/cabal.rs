@@ -0,0 +1,552 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use super::{download_popularity, InstallResult, Package, PackageManager};
+use crate::audit::compare_versions;
+
+const HACKAGE_URL: &str = "https://hackage.haskell.org";
+
+/// cabal backend installing Haskell executables from Hackage
+pub struct CabalBackend {
+    client: reqwest::Client,
+    cabal: PathBuf,
+    installdir: PathBuf,
+}
+
+/// Body of Hackage's package browser search
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchRequest<'a> {
+    page: u32,
+    sort_column: &'a str,
+    sort_direction: &'a str,
+    search_query: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchResponse {
+    page_contents: Vec<SearchResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResult {
+    name: Link,
+    description: Option<String>,
+    #[serde(default)]
+    downloads: u64,
+    #[serde(default)]
+    maintainers: Vec<Link>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Link {
+    display: String,
+}
+
+/// `/package/<name>/preferred`
+#[derive(Debug, Deserialize)]
+struct PreferredVersions {
+    #[serde(rename = "normal-version", default)]
+    normal: Vec<String>,
+}
+
+/// The fields of a .cabal file shown by `info`
+#[derive(Debug, Default)]
+struct CabalFile {
+    synopsis: Option<String>,
+    homepage: Option<String>,
+    license: Option<String>,
+    maintainer: Option<String>,
+    category: Option<String>,
+    depends: Vec<String>,
+}
+
+/// `cabal` on PATH, or the one ghcup manages
+pub fn cabal_command() -> Option<PathBuf> {
+    if command_exists("cabal") {
+        return Some(PathBuf::from("cabal"));
+    }
+    ghcup_bin_dir()
+        .map(|dir| dir.join("cabal"))
+        .filter(|path| path.is_file())
+}
+
+/// Where ghcup installs ghc, cabal and friends
+pub fn ghcup_bin_dir() -> Option<PathBuf> {
+    std::env::var_os("GHCUP_INSTALL_BASE_PREFIX")
+        .map(PathBuf::from)
+        .or_else(dirs::home_dir)
+        .map(|base| base.join(".ghcup").join("bin"))
+}
+
+impl CabalBackend {
+    pub fn new() -> Result<Self> {
+        let Some(cabal) = cabal_command() else {
+            anyhow::bail!(
+                "cabal is not available on this system. Install it with ghcup to use this backend."
+            );
+        };
+
+        let client = reqwest::Client::builder()
+            .user_agent("zap/0.1.0")
+            .build()
+            .context("Failed to create HTTP client")?;
+
+        let installdir = Self::installdir_for(&cabal).context("Could not find a home directory")?;
+        Ok(Self {
+            client,
+            cabal,
+            installdir,
+        })
+    }
+
+    /// Where executables are installed: cabal's own `installdir` when it can
+    /// tell (cabal 3.10.3 and later), otherwise its XDG default
+    fn installdir_for(cabal: &Path) -> Option<PathBuf> {
+        if let Ok(output) = Command::new(cabal)
+            .args(["path", "--installdir"])
+            .stderr(Stdio::null())
+            .output()
+        {
+            let dir = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            if output.status.success() && !dir.is_empty() {
+                return Some(PathBuf::from(dir));
+            }
+        }
+
+        let home = dirs::home_dir()?;
+        let legacy = home.join(".cabal").join("bin");
+        Some(if legacy.is_dir() {
+            legacy
+        } else {
+            home.join(".local").join("bin")
+        })
+    }
+
+    pub(crate) fn installdir() -> Option<PathBuf> {
+        Self::installdir_for(&cabal_command()?)
+    }
+
+    /// Executables in the install directory that link into the cabal store,
+    /// with the package and version each belongs to
+    fn installed_executables(&self) -> Vec<(PathBuf, String, String)> {
+        let Ok(entries) = std::fs::read_dir(&self.installdir) else {
+            return vec![];
+        };
+
+        let mut executables: Vec<(PathBuf, String, String)> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.path();
+                let target = std::fs::read_link(&path).ok()?;
+                let (name, version) = parse_store_path(&target)?;
+                Some((path, name, version))
+            })
+            .collect();
+        executables.sort();
+        executables
+    }
+
+    async fn latest_version(&self, name: &str) -> Result<Option<String>> {
+        let response = self
+            .client
+            .get(format!("{}/package/{}/preferred", HACKAGE_URL, name))
+            .header("Accept", "application/json")
+            .send()
+            .await
+            .context("Failed to fetch Hackage versions")?;
+        if !response.status().is_success() {
+            return Ok(None);
+        }
+
+        let versions: PreferredVersions = response
+            .json()
+            .await
+            .context("Failed to parse Hackage versions")?;
+        Ok(versions
+            .normal
+            .into_iter()
+            .max_by(|a, b| compare_versions(a, b)))
+    }
+}
+
+#[async_trait]
+impl PackageManager for CabalBackend {
+    fn name(&self) -> &str {
+        "cabal (Hackage)"
+    }
+
+    fn id(&self) -> &str {
+        "cabal"
+    }
+
+    async fn search(&self, query: &str) -> Result<Vec<Package>> {
+        if query.len() < 2 {
+            return Ok(vec![]);
+        }
+
+        let request = SearchRequest {
+            page: 0,
+            sort_column: "default",
+            sort_direction: "ascending",
+            search_query: query,
+        };
+        let response: SearchResponse = self
+            .client
+            .post(format!("{}/packages/search", HACKAGE_URL))
+            .json(&request)
+            .send()
+            .await
+            .context("Failed to search Hackage")?
+            .json()
+            .await
+            .context("Failed to parse Hackage response")?;
+
+        let installed = self.list_installed()?;
+        let mut packages: Vec<Package> = response
+            .page_contents
+            .into_iter()
+            .map(|result| {
+                // Search results carry no version; `info` has it
+                let mut pkg =
+                    Package::new(result.name.display, "").with_description(result.description);
+                pkg.installed = installed.iter().any(|(name, _)| *name == pkg.name);
+                pkg.popularity = download_popularity(result.downloads);
+                pkg.extra.downloads = Some(result.downloads);
+                pkg.maintainer = Some(
+                    result
+                        .maintainers
+                        .into_iter()
+                        .map(|m| m.display)
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                )
+                .filter(|m| !m.is_empty());
+                pkg.url = Some(format!("{}/package/{}", HACKAGE_URL, pkg.name));
+                pkg
+            })
+            .collect();
+        packages.truncate(30);
+
+        Ok(packages)
+    }
+
+    async fn info(&self, packages: &[&str]) -> Result<Vec<Package>> {
+        let installed = self.list_installed()?;
+        let mut results = vec![];
+
+        for pkg_name in packages {
+            let Some(version) = self.latest_version(pkg_name).await? else {
+                continue;
+            };
+
+            let url = format!(
+                "{}/package/{}-{}/{}.cabal",
+                HACKAGE_URL, pkg_name, version, pkg_name
+            );
+            let content = self
+                .client
+                .get(&url)
+                .send()
+                .await
+                .context("Failed to fetch the .cabal file")?
+                .text()
+                .await
+                .context("Failed to read the .cabal file")?;
+            let cabal = parse_cabal_file(&content, pkg_name);
+
+            let mut pkg = Package::new(*pkg_name, version).with_description(cabal.synopsis);
+            pkg.url = cabal
+                .homepage
+                .or_else(|| Some(format!("{}/package/{}", HACKAGE_URL, pkg_name)));
+            pkg.maintainer = cabal.maintainer;
+            pkg.extra.license = cabal.license.into_iter().collect();
+            pkg.extra.categories = cabal.category.into_iter().collect();
+            pkg.extra.depends = cabal.depends;
+            pkg.installed = installed.iter().any(|(name, _)| name == pkg_name);
+            results.push(pkg);
+        }
+
+        Ok(results)
+    }
+
+    async fn install(&self, packages: &[Package]) -> Result<Vec<InstallResult>> {
+        if packages.is_empty() {
+            return Ok(vec![]);
+        }
+
+        println!("--> Updating the Hackage index...");
+        let _ = Command::new(&self.cabal)
+            .arg("update")
+            .stdout(Stdio::null())
+            .status();
+
+        let mut results = vec![];
+        for pkg in packages {
+            println!(
+                "--> Installing {} into {}...",
+                pkg.name,
+                self.installdir.display()
+            );
+            let status = Command::new(&self.cabal)
+                .arg("install")
+                .arg(format!("--installdir={}", self.installdir.display()))
+                .arg("--overwrite-policy=always")
+                .arg(&pkg.name)
+                .stdin(Stdio::inherit())
+                .stdout(Stdio::inherit())
+                .stderr(Stdio::inherit())
+                .status()
+                .context("Failed to run cabal install")?;
+
+            results.push(InstallResult {
+                package: pkg.name.clone(),
+                success: status.success(),
+                message: if status.success() {
+                    None
+                } else {
+                    Some("cabal install failed".to_string())
+                },
+            });
+        }
+
+        Ok(results)
+    }
+
+    async fn remove(&self, packages: &[&str]) -> Result<Vec<InstallResult>> {
+        // cabal cannot uninstall: drop the links, the store is shared with builds
+        let executables = self.installed_executables();
+        Ok(packages
+            .iter()
+            .map(|name| {
+                let links: Vec<&PathBuf> = executables
+                    .iter()
+                    .filter(|(_, package, _)| package == name)
+                    .map(|(path, _, _)| path)
+                    .collect();
+                let message = if links.is_empty() {
+                    Some(format!("{} is not installed", name))
+                } else {
+                    links
+                        .iter()
+                        .find_map(|path| std::fs::remove_file(path).err())
+                        .map(|e| format!("Failed to remove executable: {}", e))
+                };
+                InstallResult {
+                    package: name.to_string(),
+                    success: message.is_none(),
+                    message,
+                }
+            })
+            .collect())
+    }
+
+    fn is_installed(&self, package: &str) -> Result<bool> {
+        Ok(self
+            .installed_executables()
+            .iter()
+            .any(|(_, name, _)| name == package))
+    }
+
+    fn list_installed(&self) -> Result<Vec<(String, String)>> {
+        let mut installed: Vec<(String, String)> = self
+            .installed_executables()
+            .into_iter()
+            .map(|(_, name, version)| (name, version))
+            .collect();
+        installed.sort();
+        installed.dedup();
+        Ok(installed)
+    }
+
+    async fn check_updates(&self) -> Result<Vec<Package>> {
+        let installed = self.list_installed()?;
+        let latest =
+            futures::future::join_all(installed.iter().map(|(name, _)| self.latest_version(name)))
+                .await;
+
+        Ok(installed
+            .into_iter()
+            .zip(latest)
+            .filter_map(|((name, version), latest)| {
+                let latest = latest.ok().flatten()?;
+                if compare_versions(&latest, &version) != std::cmp::Ordering::Greater {
+                    return None;
+                }
+                let mut pkg = Package::new(name, latest);
+                pkg.installed = true;
+                Some(pkg)
+            })
+            .collect())
+    }
+}
+
+/// Package and version from a cabal store path such as
+/// `~/.cabal/store/ghc-9.4.8/pandoc-cli-3.1.11-e3b0c442…/bin/pandoc`
+fn parse_store_path(path: &Path) -> Option<(String, String)> {
+    let components: Vec<String> = path
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    let store = components.iter().position(|c| c == "store")?;
+    let unit = components.get(store + 2)?;
+
+    // "<name>-<version>-<hash>", and names may contain dashes
+    let (rest, _hash) = unit.rsplit_once('-')?;
+    let (name, version) = rest.rsplit_once('-')?;
+    if name.is_empty() || !version.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    Some((name.to_string(), version.to_string()))
+}
+
+/// Read the fields `info` shows from a .cabal file. Fields are "key: value",
+/// continued on more indented lines; build-depends appear in every component.
+fn parse_cabal_file(content: &str, package: &str) -> CabalFile {
+    let mut fields: Vec<(usize, String, String)> = vec![];
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with("--") {
+            continue;
+        }
+        let indent = line.len() - line.trim_start().len();
+
+        if let Some((key, value)) = trimmed.split_once(':') {
+            if !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+                fields.push((indent, key.to_ascii_lowercase(), value.trim().to_string()));
+                continue;
+            }
+        }
+        match fields.last_mut() {
+            Some((field_indent, _, value)) if indent > *field_indent => {
+                value.push(' ');
+                value.push_str(trimmed);
+            }
+            // A section header, such as "executable pandoc" or "if flag(x)"
+            _ => fields.push((indent, String::new(), String::new())),
+        }
+    }
+
+    let mut cabal = CabalFile::default();
+    for (indent, key, value) in fields {
+        let value = Some(value).filter(|v| !v.is_empty());
+        match (indent, key.as_str()) {
+            (0, "synopsis") => cabal.synopsis = value,
+            (0, "homepage") => cabal.homepage = value,
+            (0, "license") => cabal.license = value,
+            (0, "maintainer") => cabal.maintainer = value,
+            (0, "category") => cabal.category = value,
+            (_, "build-depends") => {
+                for dep in value.iter().flat_map(|v| v.split(',')) {
+                    let Some(name) = dep.split_whitespace().next() else {
+                        continue;
+                    };
+                    let name = name.split(['>', '<', '=', '^']).next().unwrap_or(name);
+                    if !name.is_empty()
+                        && name != package
+                        && !cabal.depends.iter().any(|d| d == name)
+                    {
+                        cabal.depends.push(name.to_string());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    cabal
+}
+
+fn command_exists(cmd: &str) -> bool {
+    Command::new("which")
+        .arg(cmd)
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_store_path() {
+        let path = Path::new(
+            "/home/user/.local/state/cabal/store/ghc-9.4.8/pandoc-cli-3.1.11-e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855/bin/pandoc",
+        );
+        assert_eq!(
+            parse_store_path(path),
+            Some(("pandoc-cli".to_string(), "3.1.11".to_string()))
+        );
+        assert_eq!(
+            parse_store_path(Path::new("/home/user/.ghcup/bin/ghc-9.4.8")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_cabal_file() {
+        let content = "cabal-version:   2.4\n\
+                       name:            hlint\n\
+                       version:         3.8\n\
+                       license:         BSD-3-Clause\n\
+                       maintainer:      Neil Mitchell <ndmitchell@gmail.com>\n\
+                       category:        Development\n\
+                       synopsis:        Source code suggestions\n\
+                       description:\n    HLint gives suggestions on how to improve your source code.\n\
+                       homepage:        https://github.com/ndmitchell/hlint#readme\n\
+                       \n\
+                       library\n\
+                       \x20   hs-source-dirs:   src\n\
+                       \x20   build-depends:\n\
+                       \x20       base >=4.15 && <5, process >= 1.1,\n\
+                       \x20       filepath, unordered-containers >=0.2.1.0\n\
+                       \x20   if flag(ghc-lib)\n\
+                       \x20       build-depends: ghc-lib-parser == 9.8.*\n\
+                       \n\
+                       executable hlint\n\
+                       \x20   main-is:          Main.hs\n\
+                       \x20   build-depends:    base, hlint\n";
+        let cabal = parse_cabal_file(content, "hlint");
+        assert_eq!(cabal.synopsis.as_deref(), Some("Source code suggestions"));
+        assert_eq!(cabal.license.as_deref(), Some("BSD-3-Clause"));
+        assert_eq!(cabal.category.as_deref(), Some("Development"));
+        assert_eq!(
+            cabal.homepage.as_deref(),
+            Some("https://github.com/ndmitchell/hlint#readme")
+        );
+        assert_eq!(
+            cabal.depends,
+            [
+                "base",
+                "process",
+                "filepath",
+                "unordered-containers",
+                "ghc-lib-parser"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_search_response() {
+        let json = r#"{
+            "numberOfResults": 1,
+            "pageContents": [{
+                "description": "Source code suggestions",
+                "downloads": 5046,
+                "lastUpload": "2024-06-03T21:11:28.573218Z",
+                "maintainers": [{"display": "NeilMitchell", "uri": "/user/NeilMitchell"}],
+                "name": {"display": "hlint", "uri": "/package/hlint"},
+                "tags": [{"display": "development", "uri": "/packages/tag/development"}],
+                "votes": 2.5
+            }]
+        }"#;
+        let response: SearchResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(response.page_contents[0].name.display, "hlint");
+        assert_eq!(response.page_contents[0].downloads, 5046);
+    }
+}
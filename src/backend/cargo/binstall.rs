@@ -0,0 +1,641 @@
+use anyhow::{Context, Result};
+use flate2::read::GzDecoder;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::crates2;
+
+const CRATES_DOWNLOAD_URL: &str = "https://static.crates.io/crates";
+const GITHUB_API: &str = "https://api.github.com";
+
+/// Archive formats we can unpack
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PkgFmt {
+    Tgz,
+    Bin,
+}
+
+impl PkgFmt {
+    fn parse(fmt: &str) -> Option<Self> {
+        match fmt {
+            "tgz" | "tar.gz" => Some(Self::Tgz),
+            "bin" => Some(Self::Bin),
+            _ => None,
+        }
+    }
+
+    fn suffix(self) -> &'static str {
+        match self {
+            Self::Tgz => ".tgz",
+            Self::Bin => "",
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Tgz => "tgz",
+            Self::Bin => "bin",
+        }
+    }
+}
+
+/// `[package.metadata.binstall]`, with target overrides already applied
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct BinstallMeta {
+    pkg_url: Option<String>,
+    bin_dir: Option<String>,
+    pkg_fmt: Option<String>,
+    #[serde(default)]
+    overrides: toml::Table,
+}
+
+/// What we need from the crate's published Cargo.toml
+#[derive(Debug, Default)]
+struct CrateManifest {
+    repository: Option<String>,
+    bins: Vec<String>,
+    binstall: Option<BinstallMeta>,
+}
+
+/// A downloadable prebuilt archive
+#[derive(Debug)]
+struct Artifact {
+    url: String,
+    fmt: PkgFmt,
+    /// Path template of each binary inside the archive, if known
+    bin_dir: Option<String>,
+    /// URL of a published checksum file
+    checksum_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubRelease {
+    assets: Vec<GithubAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+/// Install a prebuilt binary of `name` `version` into $CARGO_HOME/bin.
+/// Returns `Ok(None)` when no prebuilt artifact exists for this host, so the
+/// caller can fall back to building from source.
+pub async fn install(
+    client: &reqwest::Client,
+    name: &str,
+    version: &str,
+) -> Result<Option<Vec<String>>> {
+    let target = host_target();
+    let binary_ext = if target.contains("windows") {
+        ".exe"
+    } else {
+        ""
+    };
+
+    let manifest = match fetch_manifest(client, name, version).await {
+        Ok(manifest) => manifest,
+        Err(_) => return Ok(None),
+    };
+    let repo = manifest.repository.clone().unwrap_or_default();
+
+    let artifact = match &manifest.binstall {
+        Some(meta) => from_metadata(meta, name, version, &target, &repo),
+        None => None,
+    };
+    let artifact = match artifact {
+        Some(artifact) => Some(artifact),
+        None => find_github_asset(client, &repo, name, version, &target, &manifest).await,
+    };
+    let Some(artifact) = artifact else {
+        return Ok(None);
+    };
+    // A raw binary is a single executable, so it can't provide several bins
+    if artifact.fmt == PkgFmt::Bin && manifest.bins.len() > 1 {
+        return Ok(None);
+    }
+
+    println!(
+        "--> Downloading prebuilt {} {} ({})...",
+        name, version, target
+    );
+    let bytes = client
+        .get(&artifact.url)
+        .send()
+        .await
+        .with_context(|| format!("Failed to download {}", artifact.url))?
+        .error_for_status()
+        .with_context(|| format!("Failed to download {}", artifact.url))?
+        .bytes()
+        .await?;
+
+    let file_name = artifact.url.rsplit('/').next().unwrap_or_default();
+    let verified = match &artifact.checksum_url {
+        Some(checksum_url) => verify_checksum(client, checksum_url, file_name, &bytes).await?,
+        None => false,
+    };
+    if !verified {
+        eprintln!(
+            "  --> Warning: no published checksum for {}, download not verified",
+            file_name
+        );
+    }
+
+    let work_dir =
+        std::env::temp_dir().join(format!("zap-binstall-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&work_dir);
+    std::fs::create_dir_all(&work_dir)?;
+    let result = install_from_archive(
+        &bytes,
+        &artifact,
+        &manifest.bins,
+        &work_dir,
+        &[
+            ("name", name),
+            ("version", version),
+            ("target", &target),
+            ("repo", &repo),
+            ("binary-ext", binary_ext),
+        ],
+    );
+    let _ = std::fs::remove_dir_all(&work_dir);
+    let bins = result?;
+
+    crates2::record_install(name, version, &bins, &target)?;
+    Ok(Some(bins))
+}
+
+fn install_from_archive(
+    bytes: &[u8],
+    artifact: &Artifact,
+    bins: &[String],
+    work_dir: &Path,
+    vars: &[(&str, &str)],
+) -> Result<Vec<String>> {
+    let binary_ext = vars
+        .iter()
+        .find(|(k, _)| *k == "binary-ext")
+        .map(|(_, v)| *v)
+        .unwrap_or("");
+
+    let bin_dir =
+        super::bin_dir().ok_or_else(|| anyhow::anyhow!("Could not determine CARGO_HOME"))?;
+    std::fs::create_dir_all(&bin_dir)?;
+
+    let mut installed = vec![];
+    for bin in bins {
+        let file_name = format!("{}{}", bin, binary_ext);
+        let source = match artifact.fmt {
+            PkgFmt::Bin => {
+                let path = work_dir.join(&file_name);
+                std::fs::write(&path, bytes)?;
+                path
+            }
+            PkgFmt::Tgz => {
+                if installed.is_empty() {
+                    tar::Archive::new(GzDecoder::new(bytes))
+                        .unpack(work_dir)
+                        .context("Failed to unpack archive")?;
+                }
+
+                let templated = artifact.bin_dir.as_ref().map(|template| {
+                    let mut vars = vars.to_vec();
+                    vars.push(("bin", bin));
+                    work_dir.join(render(template, &vars))
+                });
+                match templated.filter(|p| p.is_file()) {
+                    Some(path) => path,
+                    None => find_file(work_dir, &file_name)
+                        .ok_or_else(|| anyhow::anyhow!("Archive does not contain {}", file_name))?,
+                }
+            }
+        };
+
+        let dest = bin_dir.join(&file_name);
+        std::fs::copy(&source, &dest)
+            .with_context(|| format!("Failed to install {}", dest.display()))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&dest, std::fs::Permissions::from_mode(0o755))?;
+        }
+        installed.push(bin.clone());
+    }
+
+    Ok(installed)
+}
+
+/// Download the .crate file and read its Cargo.toml
+async fn fetch_manifest(
+    client: &reqwest::Client,
+    name: &str,
+    version: &str,
+) -> Result<CrateManifest> {
+    let url = format!(
+        "{}/{}/{}-{}.crate",
+        CRATES_DOWNLOAD_URL, name, name, version
+    );
+    let bytes = client
+        .get(&url)
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+
+    let prefix = format!("{}-{}/", name, version);
+    let mut cargo_toml = String::new();
+    let mut has_main = false;
+    let mut extra_bins = vec![];
+
+    let mut archive = tar::Archive::new(GzDecoder::new(&bytes[..]));
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.to_string_lossy().to_string();
+        let Some(path) = path.strip_prefix(&prefix) else {
+            continue;
+        };
+
+        if path == "Cargo.toml" {
+            entry.read_to_string(&mut cargo_toml)?;
+        } else if path == "src/main.rs" {
+            has_main = true;
+        } else if let Some(bin) = path
+            .strip_prefix("src/bin/")
+            .and_then(|p| p.strip_suffix(".rs"))
+        {
+            if !bin.contains('/') {
+                extra_bins.push(bin.to_string());
+            }
+        }
+    }
+
+    let mut manifest = parse_manifest(&cargo_toml, &host_target())?;
+    if manifest.bins.is_empty() {
+        if has_main {
+            manifest.bins.push(name.to_string());
+        }
+        manifest.bins.extend(extra_bins);
+    }
+    if manifest.bins.is_empty() {
+        anyhow::bail!("{} has no binaries", name);
+    }
+    Ok(manifest)
+}
+
+fn parse_manifest(content: &str, target: &str) -> Result<CrateManifest> {
+    let table: toml::Table = toml::from_str(content).context("Failed to parse Cargo.toml")?;
+    let package = table.get("package").and_then(|p| p.as_table());
+
+    let repository = package
+        .and_then(|p| p.get("repository"))
+        .and_then(|r| r.as_str())
+        .map(|r| r.trim_end_matches('/').trim_end_matches(".git").to_string());
+
+    let bins = table
+        .get("bin")
+        .and_then(|b| b.as_array())
+        .map(|bins| {
+            bins.iter()
+                .filter_map(|b| b.get("name").and_then(|n| n.as_str()))
+                .map(|n| n.to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let binstall = package
+        .and_then(|p| p.get("metadata"))
+        .and_then(|m| m.get("binstall"))
+        .map(|b| b.clone().try_into::<BinstallMeta>())
+        .transpose()
+        .context("Invalid package.metadata.binstall")?
+        .map(|mut meta| {
+            if let Some(over) = meta.overrides.get(target).cloned() {
+                if let Ok(over) = over.try_into::<BinstallMeta>() {
+                    meta.pkg_url = over.pkg_url.or(meta.pkg_url);
+                    meta.bin_dir = over.bin_dir.or(meta.bin_dir);
+                    meta.pkg_fmt = over.pkg_fmt.or(meta.pkg_fmt);
+                }
+            }
+            meta
+        });
+
+    Ok(CrateManifest {
+        repository,
+        bins,
+        binstall,
+    })
+}
+
+fn from_metadata(
+    meta: &BinstallMeta,
+    name: &str,
+    version: &str,
+    target: &str,
+    repo: &str,
+) -> Option<Artifact> {
+    let template = meta.pkg_url.as_ref()?;
+    let fmt = PkgFmt::parse(meta.pkg_fmt.as_deref().unwrap_or("tgz"))?;
+
+    let url = render(
+        template,
+        &[
+            ("name", name),
+            ("version", version),
+            ("target", target),
+            ("repo", repo),
+            ("archive-format", fmt.name()),
+            ("format", fmt.name()),
+            ("archive-suffix", fmt.suffix()),
+            (
+                "binary-ext",
+                if target.contains("windows") {
+                    ".exe"
+                } else {
+                    ""
+                },
+            ),
+        ],
+    );
+
+    Some(Artifact {
+        checksum_url: Some(format!("{}.sha256", url)),
+        url,
+        fmt,
+        bin_dir: meta.bin_dir.clone(),
+    })
+}
+
+/// Look for a release asset built for `target` in the crate's GitHub repository
+async fn find_github_asset(
+    client: &reqwest::Client,
+    repo: &str,
+    name: &str,
+    version: &str,
+    target: &str,
+    manifest: &CrateManifest,
+) -> Option<Artifact> {
+    let slug = repo.strip_prefix("https://github.com/")?;
+
+    for tag in [
+        format!("v{}", version),
+        version.to_string(),
+        format!("{}-v{}", name, version),
+    ] {
+        let url = format!("{}/repos/{}/releases/tags/{}", GITHUB_API, slug, tag);
+        let release = match client.get(&url).send().await {
+            Ok(response) if response.status().is_success() => {
+                response.json::<GithubRelease>().await
+            }
+            _ => continue,
+        };
+        let Ok(release) = release else {
+            continue;
+        };
+
+        let Some(asset) = release.assets.iter().find(|a| {
+            a.name.contains(target) && (a.name.ends_with(".tar.gz") || a.name.ends_with(".tgz"))
+        }) else {
+            continue;
+        };
+
+        let checksum_url =
+            checksum_asset(&release.assets, &asset.name).map(|a| a.browser_download_url.clone());
+
+        return Some(Artifact {
+            url: asset.browser_download_url.clone(),
+            fmt: PkgFmt::Tgz,
+            bin_dir: manifest.binstall.as_ref().and_then(|m| m.bin_dir.clone()),
+            checksum_url,
+        });
+    }
+
+    None
+}
+
+/// The checksum file for the release asset `asset_name`: its own
+/// `<asset>.sha256`, or else a combined checksum list. Other assets' own
+/// checksum files are never picked.
+fn checksum_asset<'a>(assets: &'a [GithubAsset], asset_name: &str) -> Option<&'a GithubAsset> {
+    let own = format!("{}.sha256", asset_name);
+    assets.iter().find(|a| a.name == own).or_else(|| {
+        assets.iter().find(|a| {
+            let lower = a.name.to_lowercase();
+            let for_other_asset = lower
+                .strip_suffix(".sha256")
+                .is_some_and(|stem| assets.iter().any(|other| other.name.to_lowercase() == stem));
+            (lower.contains("sha256") || lower.contains("checksums")) && !for_other_asset
+        })
+    })
+}
+
+/// The hash a checksum file gives for `file_name`: from its "<hash>  <file>"
+/// line, or a bare hash when the file is `<file_name>.sha256` itself
+fn expected_hash(content: &str, file_name: &str, own_file: bool) -> Option<String> {
+    content.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        let hash = parts.next()?;
+        match parts.next() {
+            Some(file) if file.trim_start_matches('*') == file_name => Some(hash.to_lowercase()),
+            None if own_file => Some(hash.to_lowercase()),
+            _ => None,
+        }
+    })
+}
+
+/// Compare the SHA-256 of a download with a published checksum file. Returns
+/// whether the download was verified: a checksum file that is missing or does
+/// not list the download is tolerated; a mismatch is not.
+async fn verify_checksum(
+    client: &reqwest::Client,
+    checksum_url: &str,
+    file_name: &str,
+    bytes: &[u8],
+) -> Result<bool> {
+    let response = match client.get(checksum_url).send().await {
+        Ok(r) if r.status().is_success() => r,
+        _ => return Ok(false),
+    };
+    let content = response.text().await?;
+
+    let own_file = checksum_url.ends_with(&format!("/{}.sha256", file_name));
+    let Some(expected) = expected_hash(&content, file_name, own_file) else {
+        return Ok(false);
+    };
+
+    let actual: String = Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    if actual != expected {
+        anyhow::bail!(
+            "Checksum mismatch for {} (expected {}, got {})",
+            file_name,
+            expected,
+            actual
+        );
+    }
+
+    println!("--> Checksum verified");
+    Ok(true)
+}
+
+/// Fill in `{ var }` placeholders of a binstall template
+fn render(template: &str, vars: &[(&str, &str)]) -> String {
+    let mut out = template.to_string();
+    for (key, value) in vars {
+        out = out
+            .replace(&format!("{{ {} }}", key), value)
+            .replace(&format!("{{{}}}", key), value);
+    }
+    out
+}
+
+fn find_file(dir: &Path, file_name: &str) -> Option<PathBuf> {
+    for entry in std::fs::read_dir(dir).ok()?.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if path.is_dir() {
+            if let Some(found) = find_file(&path, file_name) {
+                return Some(found);
+            }
+        } else if entry.file_name().to_string_lossy() == file_name {
+            return Some(path);
+        }
+    }
+    None
+}
+
+/// The host target triple, from rustc or the platform zap was built for
+fn host_target() -> String {
+    let from_rustc = Command::new("rustc")
+        .arg("-vV")
+        .output()
+        .ok()
+        .and_then(|o| {
+            String::from_utf8_lossy(&o.stdout)
+                .lines()
+                .find_map(|l| l.strip_prefix("host: ").map(|h| h.to_string()))
+        });
+
+    from_rustc.unwrap_or_else(|| {
+        let arch = std::env::consts::ARCH;
+        match std::env::consts::OS {
+            "linux" => format!("{}-unknown-linux-gnu", arch),
+            "macos" => format!("{}-apple-darwin", arch),
+            "windows" => format!("{}-pc-windows-msvc", arch),
+            os => format!("{}-unknown-{}", arch, os),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"
+[package]
+name = "tool"
+version = "1.2.3"
+repository = "https://github.com/example/tool.git"
+
+[[bin]]
+name = "tool"
+
+[package.metadata.binstall]
+pkg-url = "{ repo }/releases/download/v{ version }/{ name }-{ target }{ archive-suffix }"
+bin-dir = "{ name }-{ target }/{ bin }{ binary-ext }"
+
+[package.metadata.binstall.overrides.x86_64-pc-windows-msvc]
+pkg-fmt = "bin"
+"#;
+
+    #[test]
+    fn test_binstall_metadata() {
+        let target = "x86_64-unknown-linux-gnu";
+        let manifest = parse_manifest(MANIFEST, target).unwrap();
+        assert_eq!(manifest.bins, ["tool"]);
+        assert_eq!(
+            manifest.repository.as_deref(),
+            Some("https://github.com/example/tool")
+        );
+
+        let meta = manifest.binstall.unwrap();
+        let artifact = from_metadata(
+            &meta,
+            "tool",
+            "1.2.3",
+            target,
+            "https://github.com/example/tool",
+        )
+        .unwrap();
+        assert_eq!(artifact.fmt, PkgFmt::Tgz);
+        assert_eq!(
+            artifact.url,
+            "https://github.com/example/tool/releases/download/v1.2.3/tool-x86_64-unknown-linux-gnu.tgz"
+        );
+
+        let windows = parse_manifest(MANIFEST, "x86_64-pc-windows-msvc").unwrap();
+        assert_eq!(windows.binstall.unwrap().pkg_fmt.as_deref(), Some("bin"));
+    }
+
+    #[test]
+    fn test_checksum_asset() {
+        let assets = |names: &[&str]| -> Vec<GithubAsset> {
+            names
+                .iter()
+                .map(|name| GithubAsset {
+                    name: name.to_string(),
+                    browser_download_url: format!("https://example.com/{}", name),
+                })
+                .collect()
+        };
+        let x86 = "tool-x86_64-unknown-linux-gnu.tar.gz";
+
+        let own = assets(&[
+            x86,
+            "tool-aarch64-apple-darwin.tar.gz.sha256",
+            &format!("{}.sha256", x86),
+        ]);
+        assert_eq!(
+            checksum_asset(&own, x86).map(|a| a.name.as_str()),
+            Some("tool-x86_64-unknown-linux-gnu.tar.gz.sha256")
+        );
+
+        let combined = assets(&[
+            x86,
+            "tool-aarch64-apple-darwin.tar.gz",
+            "tool-aarch64-apple-darwin.tar.gz.sha256",
+            "SHA256SUMS",
+        ]);
+        assert_eq!(
+            checksum_asset(&combined, x86).map(|a| a.name.as_str()),
+            Some("SHA256SUMS")
+        );
+
+        let others_only = assets(&[
+            x86,
+            "tool-aarch64-apple-darwin.tar.gz",
+            "tool-aarch64-apple-darwin.tar.gz.sha256",
+        ]);
+        assert!(checksum_asset(&others_only, x86).is_none());
+    }
+
+    #[test]
+    fn test_expected_hash() {
+        let file = "tool-x86_64-unknown-linux-gnu.tar.gz";
+        let combined =
+            "AAAA  tool-aarch64-apple-darwin.tar.gz\nbbbb *tool-x86_64-unknown-linux-gnu.tar.gz\n";
+        assert_eq!(
+            expected_hash(combined, file, false).as_deref(),
+            Some("bbbb")
+        );
+        assert_eq!(expected_hash("AAAA  other.tar.gz\n", file, false), None);
+        assert_eq!(expected_hash("CCCC\n", file, true).as_deref(), Some("cccc"));
+        assert_eq!(expected_hash("CCCC\n", file, false), None);
+    }
+}
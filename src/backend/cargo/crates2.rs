@@ -0,0 +1,317 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+const CRATES_IO_SOURCE: &str = "registry+https://github.com/rust-lang/crates.io-index";
+
+/// Where an installed crate came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CrateSource {
+    /// A registry index URL (crates.io unless configured otherwise)
+    Registry(String),
+    Git {
+        url: String,
+        reference: GitReference,
+        /// Commit the installed binary was built from
+        commit: String,
+    },
+    Path(PathBuf),
+}
+
+/// The git reference given to `cargo install --git`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GitReference {
+    DefaultBranch,
+    Branch(String),
+    Tag(String),
+    Rev(String),
+}
+
+/// A crate installed with `cargo install`, from `$CARGO_HOME/.crates2.json`
+#[derive(Debug, Clone)]
+pub struct InstalledCrate {
+    pub name: String,
+    pub version: String,
+    pub source: CrateSource,
+    /// Executables it installed into $CARGO_HOME/bin
+    pub bins: Vec<String>,
+    pub features: Vec<String>,
+    pub all_features: bool,
+    pub no_default_features: bool,
+}
+
+impl InstalledCrate {
+    pub fn is_crates_io(&self) -> bool {
+        matches!(&self.source, CrateSource::Registry(url)
+            if url.contains("github.com/rust-lang/crates.io-index") || url.contains("index.crates.io"))
+    }
+
+    /// Arguments for `cargo install` that reproduce this installation
+    pub fn install_args(&self, version: Option<&str>) -> Vec<String> {
+        let mut args = vec!["install".to_string()];
+
+        match &self.source {
+            CrateSource::Registry(_) => {
+                args.push(self.name.clone());
+                if let Some(version) = version {
+                    args.push("--version".to_string());
+                    args.push(version.to_string());
+                }
+            }
+            CrateSource::Git { url, reference, .. } => {
+                args.push("--git".to_string());
+                args.push(url.clone());
+                match reference {
+                    GitReference::DefaultBranch => {}
+                    GitReference::Branch(b) => args.extend(["--branch".to_string(), b.clone()]),
+                    GitReference::Tag(t) => args.extend(["--tag".to_string(), t.clone()]),
+                    GitReference::Rev(r) => args.extend(["--rev".to_string(), r.clone()]),
+                }
+                args.push(self.name.clone());
+            }
+            CrateSource::Path(path) => {
+                args.push("--path".to_string());
+                args.push(path.to_string_lossy().to_string());
+            }
+        }
+
+        if self.all_features {
+            args.push("--all-features".to_string());
+        } else if !self.features.is_empty() {
+            args.push("--features".to_string());
+            args.push(self.features.join(","));
+        }
+        if self.no_default_features {
+            args.push("--no-default-features".to_string());
+        }
+
+        args
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Crates2 {
+    #[serde(default)]
+    installs: HashMap<String, InstallInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct InstallInfo {
+    #[serde(default)]
+    bins: Vec<String>,
+    #[serde(default)]
+    features: Vec<String>,
+    #[serde(default)]
+    all_features: bool,
+    #[serde(default)]
+    no_default_features: bool,
+}
+
+/// $CARGO_HOME, or ~/.cargo
+pub fn cargo_home() -> Option<PathBuf> {
+    std::env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|h| h.join(".cargo")))
+}
+
+/// Where `cargo install` puts binaries and its .crates2.json:
+/// $CARGO_INSTALL_ROOT, or $CARGO_HOME
+pub fn install_root() -> Option<PathBuf> {
+    std::env::var_os("CARGO_INSTALL_ROOT")
+        .map(PathBuf::from)
+        .or_else(cargo_home)
+}
+
+/// Read every crate recorded in .crates2.json
+pub fn installed_crates() -> Result<Vec<InstalledCrate>> {
+    let Some(home) = install_root() else {
+        return Ok(vec![]);
+    };
+    let path = home.join(".crates2.json");
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(_) => return Ok(vec![]),
+    };
+    parse(&content).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Record a crate installed without cargo in .crates2.json and .crates.toml,
+/// so `cargo install --list` and cargo's own upgrades know about it
+pub fn record_install(name: &str, version: &str, bins: &[String], target: &str) -> Result<()> {
+    let home = install_root().ok_or_else(|| anyhow::anyhow!("Could not determine CARGO_HOME"))?;
+    let key = format!("{} {} ({})", name, version, CRATES_IO_SOURCE);
+    let old_prefix = format!("{} ", name);
+
+    let json_path = home.join(".crates2.json");
+    let mut crates2: serde_json::Value = std::fs::read_to_string(&json_path)
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_else(|| serde_json::json!({ "installs": {} }));
+    if let Some(installs) = crates2.get_mut("installs").and_then(|i| i.as_object_mut()) {
+        installs.retain(|k, _| !k.starts_with(&old_prefix));
+        installs.insert(
+            key.clone(),
+            serde_json::json!({
+                "version_req": null,
+                "bins": bins,
+                "features": [],
+                "all_features": false,
+                "no_default_features": false,
+                "profile": "release",
+                "target": target,
+                "rustc": "",
+            }),
+        );
+    }
+    std::fs::write(&json_path, serde_json::to_string(&crates2)?)
+        .with_context(|| format!("Failed to write {}", json_path.display()))?;
+
+    let toml_path = home.join(".crates.toml");
+    let mut crates_toml: toml::Table = std::fs::read_to_string(&toml_path)
+        .ok()
+        .and_then(|c| toml::from_str(&c).ok())
+        .unwrap_or_default();
+    let v1 = crates_toml
+        .entry("v1")
+        .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+    if let Some(v1) = v1.as_table_mut() {
+        v1.retain(|k, _| !k.starts_with(&old_prefix));
+        v1.insert(
+            key,
+            toml::Value::Array(bins.iter().map(|b| toml::Value::from(b.as_str())).collect()),
+        );
+    }
+    std::fs::write(&toml_path, toml::to_string(&crates_toml)?)
+        .with_context(|| format!("Failed to write {}", toml_path.display()))?;
+
+    Ok(())
+}
+
+pub fn parse(content: &str) -> Result<Vec<InstalledCrate>> {
+    let crates2: Crates2 = serde_json::from_str(content)?;
+
+    let mut crates: Vec<InstalledCrate> = crates2
+        .installs
+        .into_iter()
+        .filter_map(|(key, info)| {
+            let (name, version, source) = parse_package_id(&key)?;
+            Some(InstalledCrate {
+                name,
+                version,
+                source,
+                bins: info.bins,
+                features: info.features,
+                all_features: info.all_features,
+                no_default_features: info.no_default_features,
+            })
+        })
+        .collect();
+
+    crates.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(crates)
+}
+
+/// Parse a package id like `ripgrep 14.1.0 (registry+https://github.com/rust-lang/crates.io-index)`
+fn parse_package_id(id: &str) -> Option<(String, String, CrateSource)> {
+    let (name, rest) = id.split_once(' ')?;
+    let (version, source) = rest.split_once(' ')?;
+    let source = source.strip_prefix('(')?.strip_suffix(')')?;
+
+    let source = if let Some(url) = source.strip_prefix("registry+") {
+        CrateSource::Registry(url.to_string())
+    } else if let Some(url) = source.strip_prefix("sparse+") {
+        CrateSource::Registry(url.to_string())
+    } else if let Some(git) = source.strip_prefix("git+") {
+        let (url, commit) = git.split_once('#').unwrap_or((git, ""));
+        let (url, query) = url.split_once('?').unwrap_or((url, ""));
+        let reference = match query.split_once('=') {
+            Some(("branch", b)) => GitReference::Branch(b.to_string()),
+            Some(("tag", t)) => GitReference::Tag(t.to_string()),
+            Some(("rev", r)) => GitReference::Rev(r.to_string()),
+            _ => GitReference::DefaultBranch,
+        };
+        CrateSource::Git {
+            url: url.to_string(),
+            reference,
+            commit: commit.to_string(),
+        }
+    } else if let Some(path) = source.strip_prefix("path+file://") {
+        CrateSource::Path(PathBuf::from(path))
+    } else {
+        return None;
+    };
+
+    Some((name.to_string(), version.to_string(), source))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CRATES2: &str = r#"{"installs":{
+        "ripgrep 14.1.0 (registry+https://github.com/rust-lang/crates.io-index)":{"version_req":null,"bins":["rg"],"features":["pcre2"],"all_features":false,"no_default_features":false,"profile":"release","target":"x86_64-unknown-linux-gnu","rustc":"rustc 1.80.0"},
+        "tool 0.2.0 (git+https://github.com/example/tool?branch=dev#0123456789abcdef)":{"bins":["tool"],"features":[],"all_features":true,"no_default_features":false},
+        "local 0.1.0 (path+file:///home/user/src/local)":{"bins":["local"],"features":[],"all_features":false,"no_default_features":true}
+    }}"#;
+
+    #[test]
+    fn test_parse_crates2() {
+        let crates = parse(CRATES2).unwrap();
+        assert_eq!(crates.len(), 3);
+
+        let local = &crates[0];
+        assert_eq!(
+            local.source,
+            CrateSource::Path(PathBuf::from("/home/user/src/local"))
+        );
+        assert_eq!(
+            local.install_args(None),
+            [
+                "install",
+                "--path",
+                "/home/user/src/local",
+                "--no-default-features"
+            ]
+        );
+
+        let rg = &crates[1];
+        assert_eq!(rg.name, "ripgrep");
+        assert_eq!(rg.version, "14.1.0");
+        assert_eq!(rg.bins, ["rg"]);
+        assert!(rg.is_crates_io());
+        assert_eq!(
+            rg.install_args(Some("14.1.1")),
+            [
+                "install",
+                "ripgrep",
+                "--version",
+                "14.1.1",
+                "--features",
+                "pcre2"
+            ]
+        );
+
+        let tool = &crates[2];
+        assert_eq!(
+            tool.source,
+            CrateSource::Git {
+                url: "https://github.com/example/tool".to_string(),
+                reference: GitReference::Branch("dev".to_string()),
+                commit: "0123456789abcdef".to_string(),
+            }
+        );
+        assert_eq!(
+            tool.install_args(None),
+            [
+                "install",
+                "--git",
+                "https://github.com/example/tool",
+                "--branch",
+                "dev",
+                "tool",
+                "--all-features"
+            ]
+        );
+    }
+}
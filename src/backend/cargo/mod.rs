@@ -0,0 +1,680 @@
+mod binstall;
+mod crates2;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use super::{
+    download_popularity, InstallResult, Package, PackageExtra, PackageManager,
+    MAX_PROVIDER_CANDIDATES,
+};
+use crates2::{CrateSource, GitReference, InstalledCrate};
+
+const CRATES_IO_API: &str = "https://crates.io/api/v1";
+/// How many crates are checked for updates at the same time
+const MAX_CONCURRENT_CHECKS: usize = 8;
+
+static PRERELEASES: AtomicBool = AtomicBool::new(false);
+
+/// Also offer pre-release versions in check_updates
+pub fn set_prereleases(value: bool) {
+    PRERELEASES.store(value, Ordering::Relaxed);
+}
+
+fn prereleases() -> bool {
+    PRERELEASES.load(Ordering::Relaxed)
+}
+
+static PREBUILT: AtomicBool = AtomicBool::new(false);
+
+/// Try prebuilt release binaries before compiling crates from source
+pub fn set_prebuilt(value: bool) {
+    PREBUILT.store(value, Ordering::Relaxed);
+}
+
+fn prebuilt() -> bool {
+    PREBUILT.load(Ordering::Relaxed) || std::env::var("ZAP_CARGO_PREBUILT").is_ok()
+}
+
+/// Where `cargo install` puts executables: $CARGO_INSTALL_ROOT/bin, else $CARGO_HOME/bin
+pub fn bin_dir() -> Option<PathBuf> {
+    crates2::install_root().map(|root| root.join("bin"))
+}
+
+/// Cargo package manager backend for Rust crates
+pub struct CargoBackend {
+    client: reqwest::Client,
+}
+
+#[derive(Debug, Deserialize)]
+struct CratesSearchResponse {
+    crates: Vec<CrateInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CrateResponse {
+    #[serde(rename = "crate")]
+    krate: CrateInfo,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+struct CrateInfo {
+    name: String,
+    #[serde(default)]
+    max_version: String,
+    #[serde(default)]
+    newest_version: String,
+    description: Option<String>,
+    homepage: Option<String>,
+    repository: Option<String>,
+    downloads: Option<u64>,
+    created_at: Option<String>,
+    #[serde(default)]
+    keywords: Vec<String>,
+    #[serde(default)]
+    categories: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CrateVersionsResponse {
+    versions: Vec<CrateVersion>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CrateVersion {
+    num: String,
+    #[serde(default)]
+    yanked: bool,
+    #[serde(default)]
+    bin_names: Vec<String>,
+}
+
+impl CargoBackend {
+    pub fn new() -> Result<Self> {
+        if !command_exists("cargo") {
+            anyhow::bail!("cargo is not available on this system");
+        }
+
+        let client = reqwest::Client::builder()
+            .user_agent("zap/0.1.0 (package manager)")
+            .build()
+            .context("Failed to create HTTP client")?;
+
+        Ok(Self { client })
+    }
+
+    fn get_version(info: &CrateInfo) -> String {
+        if !info.max_version.is_empty() {
+            info.max_version.clone()
+        } else if !info.newest_version.is_empty() {
+            info.newest_version.clone()
+        } else {
+            String::new()
+        }
+    }
+
+    /// All published versions of a crate, newest first
+    async fn versions(&self, name: &str) -> Result<Vec<CrateVersion>> {
+        let url = format!("{}/crates/{}/versions", CRATES_IO_API, name);
+        let response: CrateVersionsResponse = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .context("Failed to query crates.io")?
+            .error_for_status()
+            .with_context(|| format!("{} not found on crates.io", name))?
+            .json()
+            .await
+            .context("Failed to parse crates.io response")?;
+
+        Ok(response.versions)
+    }
+
+    /// Newest non-yanked version on crates.io. Pre-releases only count when opted in.
+    async fn latest_version(&self, name: &str) -> Result<Option<semver::Version>> {
+        Ok(self
+            .versions(name)
+            .await?
+            .iter()
+            .filter(|v| !v.yanked)
+            .filter_map(|v| semver::Version::parse(&v.num).ok())
+            .filter(|v| v.pre.is_empty() || prereleases())
+            .max())
+    }
+
+    /// The update available for an installed crate, if any
+    async fn check_crate(&self, krate: &InstalledCrate) -> Result<Option<Package>> {
+        let latest = match &krate.source {
+            CrateSource::Registry(_) if krate.is_crates_io() => {
+                let installed = semver::Version::parse(&krate.version)
+                    .with_context(|| format!("Invalid version {}", krate.version))?;
+                match self.latest_version(&krate.name).await? {
+                    Some(latest) if latest > installed => latest.to_string(),
+                    _ => return Ok(None),
+                }
+            }
+            CrateSource::Git {
+                url,
+                reference,
+                commit,
+            } => {
+                let git_ref = match reference {
+                    GitReference::DefaultBranch => "HEAD".to_string(),
+                    GitReference::Branch(b) => format!("refs/heads/{}", b),
+                    // Tags and revisions are fixed
+                    GitReference::Tag(_) | GitReference::Rev(_) => return Ok(None),
+                };
+                let head = git_remote_head(url, &git_ref).await?;
+                if head.is_empty() || head == *commit {
+                    return Ok(None);
+                }
+                format!("{} ({})", krate.version, &head[..head.len().min(8)])
+            }
+            // Other registries and local paths can't be checked
+            _ => return Ok(None),
+        };
+
+        let mut pkg = Package::new(&krate.name, latest);
+        pkg.installed = true;
+        Ok(Some(pkg))
+    }
+
+    /// Install a prebuilt binary, if one is published for this host.
+    /// `Ok(false)` means the crate has to be built from source.
+    async fn try_prebuilt(&self, name: &str, version: &str) -> Result<bool> {
+        let version = match semver::Version::parse(version) {
+            Ok(v) => v,
+            Err(_) => match self.latest_version(name).await? {
+                Some(v) => v,
+                None => return Ok(false),
+            },
+        };
+
+        match binstall::install(&self.client, name, &version.to_string()).await? {
+            Some(bins) => {
+                println!("--> Installed prebuilt {}", bins.join(", "));
+                Ok(true)
+            }
+            None => {
+                println!(
+                    "--> No prebuilt binary for {} {}, building from source...",
+                    name, version
+                );
+                Ok(false)
+            }
+        }
+    }
+
+    /// Reinstall a crate from the same source and with the same features it was
+    /// installed with
+    async fn reinstall(&self, pkg: &Package) -> Result<InstallResult> {
+        let installed = crates2::installed_crates()?
+            .into_iter()
+            .find(|c| c.name == pkg.name);
+
+        println!("--> Updating {}...", pkg.name);
+
+        // Prebuilt binaries only match default-feature crates.io installs
+        let plain = installed.as_ref().is_none_or(|c| {
+            c.is_crates_io() && c.features.is_empty() && !c.all_features && !c.no_default_features
+        });
+        if prebuilt() && plain {
+            match self.try_prebuilt(&pkg.name, &pkg.version).await {
+                Ok(true) => {
+                    return Ok(InstallResult {
+                        package: pkg.name.clone(),
+                        success: true,
+                        message: None,
+                    })
+                }
+                Ok(false) => {}
+                Err(e) => {
+                    return Ok(InstallResult {
+                        package: pkg.name.clone(),
+                        success: false,
+                        message: Some(e.to_string()),
+                    })
+                }
+            }
+        }
+
+        let args = match &installed {
+            Some(krate) => {
+                let version = semver::Version::parse(&pkg.version)
+                    .ok()
+                    .map(|v| v.to_string());
+                krate.install_args(version.as_deref())
+            }
+            None => vec!["install".to_string(), pkg.name.clone()],
+        };
+
+        let status = Command::new("cargo")
+            .args(&args)
+            .stdin(Stdio::inherit())
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::inherit())
+            .status()
+            .context("Failed to run cargo install")?;
+
+        Ok(InstallResult {
+            package: pkg.name.clone(),
+            success: status.success(),
+            message: if status.success() {
+                None
+            } else {
+                Some("cargo update failed".to_string())
+            },
+        })
+    }
+}
+
+#[async_trait]
+impl PackageManager for CargoBackend {
+    fn name(&self) -> &str {
+        "Cargo (Rust)"
+    }
+
+    fn id(&self) -> &str {
+        "cargo"
+    }
+
+    async fn search(&self, query: &str) -> Result<Vec<Package>> {
+        if query.len() < 2 {
+            return Ok(vec![]);
+        }
+
+        let url = format!(
+            "{}/crates?q={}&per_page=30",
+            CRATES_IO_API,
+            urlencoded(query)
+        );
+
+        let response: CratesSearchResponse = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .context("Failed to search crates.io")?
+            .json()
+            .await
+            .context("Failed to parse crates.io response")?;
+
+        let mut packages: Vec<Package> = response
+            .crates
+            .into_iter()
+            .map(|c| {
+                let downloads = c.downloads.unwrap_or(0);
+                let version = Self::get_version(&c);
+                let mut extra = PackageExtra::default();
+                extra.keywords = c.keywords.clone();
+                extra.categories = c.categories.clone();
+                extra.downloads = c.downloads;
+                extra.created_at = parse_timestamp(c.created_at.as_deref());
+
+                Package {
+                    name: c.name,
+                    version,
+                    description: c.description,
+                    popularity: download_popularity(downloads),
+                    installed: false,
+                    maintainer: None,
+                    url: c.homepage.or(c.repository),
+                    extra,
+                }
+            })
+            .collect();
+
+        // Check which are installed
+        for pkg in &mut packages {
+            pkg.installed = self.is_installed(&pkg.name).unwrap_or(false);
+        }
+
+        // Sort by popularity
+        packages.sort_by(|a, b| {
+            b.popularity
+                .partial_cmp(&a.popularity)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        Ok(packages)
+    }
+
+    async fn info(&self, packages: &[&str]) -> Result<Vec<Package>> {
+        let mut results = vec![];
+
+        for pkg_name in packages {
+            let url = format!("{}/crates/{}", CRATES_IO_API, pkg_name);
+
+            if let Ok(response) = self.client.get(&url).send().await {
+                if response.status().is_success() {
+                    if let Ok(result) = response.json::<CrateResponse>().await {
+                        let c = result.krate;
+                        let downloads = c.downloads.unwrap_or(0);
+                        let version = Self::get_version(&c);
+
+                        let mut extra = PackageExtra::default();
+                        extra.keywords = c.keywords.clone();
+                        extra.categories = c.categories.clone();
+                        extra.downloads = c.downloads;
+                        extra.created_at = parse_timestamp(c.created_at.as_deref());
+
+                        let mut pkg = Package {
+                            name: c.name,
+                            version,
+                            description: c.description,
+                            popularity: download_popularity(downloads),
+                            installed: false,
+                            maintainer: None,
+                            url: c.homepage.or(c.repository),
+                            extra,
+                        };
+                        pkg.installed = self.is_installed(&pkg.name)?;
+                        results.push(pkg);
+                    }
+                }
+            }
+        }
+
+        Ok(results)
+    }
+
+    async fn install(&self, packages: &[Package]) -> Result<Vec<InstallResult>> {
+        let mut results = vec![];
+
+        for pkg in packages {
+            if prebuilt() {
+                match self.try_prebuilt(&pkg.name, &pkg.version).await {
+                    Ok(true) => {
+                        results.push(InstallResult {
+                            package: pkg.name.clone(),
+                            success: true,
+                            message: None,
+                        });
+                        continue;
+                    }
+                    Ok(false) => {}
+                    Err(e) => {
+                        results.push(InstallResult {
+                            package: pkg.name.clone(),
+                            success: false,
+                            message: Some(e.to_string()),
+                        });
+                        continue;
+                    }
+                }
+            }
+
+            println!("--> Installing {} with cargo...", pkg.name);
+
+            let mut cmd = Command::new("cargo");
+            cmd.args(["install", &pkg.name]);
+            if pkg.extra.pinned {
+                cmd.args(["--version", &pkg.version]);
+            }
+
+            let status = cmd
+                .stdin(Stdio::inherit())
+                .stdout(Stdio::inherit())
+                .stderr(Stdio::inherit())
+                .status()
+                .context("Failed to run cargo install")?;
+
+            results.push(InstallResult {
+                package: pkg.name.clone(),
+                success: status.success(),
+                message: if status.success() {
+                    None
+                } else {
+                    Some("cargo install failed".to_string())
+                },
+            });
+        }
+
+        Ok(results)
+    }
+
+    async fn remove(&self, packages: &[&str]) -> Result<Vec<InstallResult>> {
+        let mut results = vec![];
+
+        for package in packages {
+            println!("--> Removing {} with cargo...", package);
+
+            let status = Command::new("cargo")
+                .args(["uninstall", package])
+                .stdin(Stdio::inherit())
+                .stdout(Stdio::inherit())
+                .stderr(Stdio::inherit())
+                .status()
+                .context("Failed to run cargo uninstall")?;
+
+            results.push(InstallResult {
+                package: package.to_string(),
+                success: status.success(),
+                message: if status.success() {
+                    None
+                } else {
+                    Some("cargo uninstall failed".to_string())
+                },
+            });
+        }
+
+        Ok(results)
+    }
+
+    fn is_installed(&self, package: &str) -> Result<bool> {
+        let output = Command::new("cargo").args(["install", "--list"]).output()?;
+
+        if !output.status.success() {
+            return Ok(false);
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        // Format: "crate_name v1.0.0:"
+        Ok(stdout.lines().any(|line| {
+            line.split_whitespace()
+                .next()
+                .map(|n| n == package)
+                .unwrap_or(false)
+        }))
+    }
+
+    fn owns(&self, path: &Path) -> Result<Option<(String, String)>> {
+        let Some(bin_dir) = bin_dir() else {
+            return Ok(None);
+        };
+        if path.parent() != Some(bin_dir.as_path()) {
+            return Ok(None);
+        }
+        let Some(binary) = path.file_name().map(|n| n.to_string_lossy()) else {
+            return Ok(None);
+        };
+        let binary = binary.trim_end_matches(".exe");
+
+        Ok(crates2::installed_crates()?
+            .into_iter()
+            .find(|krate| {
+                krate
+                    .bins
+                    .iter()
+                    .any(|b| b.trim_end_matches(".exe") == binary)
+            })
+            .map(|krate| (krate.name, krate.version)))
+    }
+
+    fn list_installed(&self) -> Result<Vec<(String, String)>> {
+        let output = Command::new("cargo").args(["install", "--list"]).output()?;
+
+        if !output.status.success() {
+            return Ok(vec![]);
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let mut packages = vec![];
+
+        for line in stdout.lines() {
+            // Format: "crate_name v1.0.0:" or "    binary_name"
+            if !line.starts_with(' ') && line.contains(' ') {
+                let parts: Vec<_> = line.split_whitespace().collect();
+                if parts.len() >= 2 {
+                    let name = parts[0].to_string();
+                    let version = parts[1]
+                        .trim_start_matches('v')
+                        .trim_end_matches(':')
+                        .to_string();
+                    packages.push((name, version));
+                }
+            }
+        }
+
+        Ok(packages)
+    }
+
+    async fn check_updates(&self) -> Result<Vec<Package>> {
+        let mut installed = crates2::installed_crates()?;
+
+        // Older cargo versions only write .crates.toml; assume crates.io for those
+        if installed.is_empty() {
+            installed = self
+                .list_installed()?
+                .into_iter()
+                .map(|(name, version)| InstalledCrate {
+                    name,
+                    version,
+                    source: CrateSource::Registry(
+                        "https://github.com/rust-lang/crates.io-index".to_string(),
+                    ),
+                    bins: vec![],
+                    features: vec![],
+                    all_features: false,
+                    no_default_features: false,
+                })
+                .collect();
+        }
+
+        let checks = installed
+            .into_iter()
+            .map(|krate| async move { self.check_crate(&krate).await });
+        let results: Vec<Result<Option<Package>>> = stream::iter(checks)
+            .buffer_unordered(MAX_CONCURRENT_CHECKS)
+            .collect()
+            .await;
+
+        let mut updates = vec![];
+        for result in results {
+            match result {
+                Ok(Some(pkg)) => updates.push(pkg),
+                Ok(None) => {}
+                Err(e) => eprintln!("Warning: {}", e),
+            }
+        }
+        updates.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(updates)
+    }
+
+    async fn provides(&self, target: &str) -> Result<Vec<Package>> {
+        let Some(command) = super::command_name(target) else {
+            return Ok(vec![]);
+        };
+
+        // A crate named after the command is the likeliest provider
+        let mut candidates = vec![command.to_string()];
+        for pkg in self.search(command).await.unwrap_or_default() {
+            if candidates.len() >= MAX_PROVIDER_CANDIDATES {
+                break;
+            }
+            if !candidates.contains(&pkg.name) {
+                candidates.push(pkg.name);
+            }
+        }
+
+        // The binaries of the newest release, as recorded by crates.io
+        let providers: Vec<String> = stream::iter(candidates)
+            .map(|name| async move {
+                let versions = self.versions(&name).await.ok()?;
+                let newest = versions.into_iter().find(|v| !v.yanked)?;
+                newest
+                    .bin_names
+                    .iter()
+                    .any(|b| b == command)
+                    .then_some(name)
+            })
+            .buffered(MAX_CONCURRENT_CHECKS)
+            .filter_map(|name| async move { name })
+            .collect()
+            .await;
+
+        let names: Vec<&str> = providers.iter().map(|n| n.as_str()).collect();
+        self.info(&names).await
+    }
+
+    async fn update(&self, packages: &[Package]) -> Result<Vec<InstallResult>> {
+        let mut results = vec![];
+
+        if packages.is_empty() {
+            // Update all installed crates
+            println!("--> Checking for cargo updates...");
+            for pkg in self.check_updates().await? {
+                results.push(self.reinstall(&pkg).await?);
+            }
+        } else {
+            for pkg in packages {
+                results.push(self.reinstall(pkg).await?);
+            }
+        }
+
+        Ok(results)
+    }
+}
+
+/// Commit a remote git ref points at
+async fn git_remote_head(url: &str, git_ref: &str) -> Result<String> {
+    let output = tokio::process::Command::new("git")
+        .args(["ls-remote", url, git_ref])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .await
+        .context("Failed to run git (is it installed?)")?;
+
+    if !output.status.success() {
+        anyhow::bail!("git ls-remote {} failed", url);
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_string())
+}
+
+fn command_exists(cmd: &str) -> bool {
+    Command::new("which")
+        .arg(cmd)
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+/// RFC 3339 timestamp from the crates.io API as a unix timestamp
+fn parse_timestamp(value: Option<&str>) -> Option<i64> {
+    chrono::DateTime::parse_from_rfc3339(value?)
+        .ok()
+        .map(|d| d.timestamp())
+}
+
+fn urlencoded(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' | '.' | '~' => c.to_string(),
+            ' ' => "+".to_string(),
+            _ => format!("%{:02X}", c as u8),
+        })
+        .collect()
+}
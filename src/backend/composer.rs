@@ -0,0 +1,513 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use super::{download_popularity, InstallResult, Package, PackageManager};
+
+const PACKAGIST_URL: &str = "https://packagist.org";
+const PACKAGIST_REPO_URL: &str = "https://repo.packagist.org";
+
+/// Composer backend for PHP packages installed with `composer global`
+pub struct ComposerBackend {
+    client: reqwest::Client,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    results: Vec<SearchResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResult {
+    name: String,
+    description: Option<String>,
+    url: Option<String>,
+    #[serde(default)]
+    downloads: u64,
+}
+
+/// `/p2/<vendor>/<package>.json`: tagged versions, newest first, minified
+#[derive(Debug, Deserialize)]
+struct MetadataResponse {
+    packages: HashMap<String, Vec<serde_json::Map<String, serde_json::Value>>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Version {
+    version: String,
+    version_normalized: Option<String>,
+    description: Option<String>,
+    homepage: Option<String>,
+    #[serde(default)]
+    license: Vec<String>,
+    #[serde(default)]
+    authors: Vec<Author>,
+    #[serde(default)]
+    require: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct Author {
+    name: Option<String>,
+}
+
+/// `/packages/<vendor>/<package>.json`, for the download counts
+#[derive(Debug, Deserialize)]
+struct PackageResponse {
+    package: PackageStats,
+}
+
+#[derive(Debug, Deserialize)]
+struct PackageStats {
+    downloads: Downloads,
+}
+
+#[derive(Debug, Deserialize)]
+struct Downloads {
+    total: u64,
+}
+
+/// `composer global show/outdated --format=json`
+#[derive(Debug, Deserialize)]
+struct ShowResponse {
+    #[serde(default)]
+    installed: Vec<ShowEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ShowEntry {
+    name: String,
+    version: String,
+    latest: Option<String>,
+    description: Option<String>,
+}
+
+impl ComposerBackend {
+    pub fn new() -> Result<Self> {
+        if !command_exists("composer") {
+            anyhow::bail!("composer is not available on this system");
+        }
+
+        let client = reqwest::Client::builder()
+            .user_agent("zap/0.1.0")
+            .build()
+            .context("Failed to create HTTP client")?;
+
+        Ok(Self { client })
+    }
+
+    /// Where `composer global require` links package binaries
+    pub(crate) fn bin_dir() -> Option<PathBuf> {
+        let output = Command::new("composer")
+            .args(["global", "config", "bin-dir", "--absolute"])
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+        let dir = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        (output.status.success() && !dir.is_empty()).then(|| PathBuf::from(dir))
+    }
+
+    /// Packages required by the global composer.json
+    fn show(&self, command: &str) -> Result<Vec<ShowEntry>> {
+        let output = Command::new("composer")
+            .args([
+                "global",
+                command,
+                "--direct",
+                "--format=json",
+                "--no-interaction",
+            ])
+            .stdin(Stdio::null())
+            .output()
+            .with_context(|| format!("Failed to run composer global {}", command))?;
+
+        // Fails when nothing was ever installed globally
+        if !output.status.success() {
+            return Ok(vec![]);
+        }
+
+        let response: ShowResponse = serde_json::from_slice(&output.stdout)
+            .with_context(|| format!("Failed to parse composer global {} output", command))?;
+        Ok(response.installed)
+    }
+
+    fn installed_names(&self) -> HashSet<String> {
+        self.show("show")
+            .map(|entries| entries.into_iter().map(|e| e.name).collect())
+            .unwrap_or_default()
+    }
+
+    async fn fetch_package(&self, name: &str) -> Result<Option<Package>> {
+        let url = format!("{}/p2/{}.json", PACKAGIST_REPO_URL, name);
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .context("Failed to fetch Packagist metadata")?;
+        if !response.status().is_success() {
+            return Ok(None);
+        }
+        let metadata: MetadataResponse = response
+            .json()
+            .await
+            .context("Failed to parse Packagist metadata")?;
+        let Some(mut pkg) = parse_metadata(metadata, name) else {
+            return Ok(None);
+        };
+
+        let url = format!("{}/packages/{}.json", PACKAGIST_URL, name);
+        if let Ok(response) = self.client.get(&url).send().await {
+            if let Ok(stats) = response.json::<PackageResponse>().await {
+                pkg.popularity = download_popularity(stats.package.downloads.total);
+                pkg.extra.downloads = Some(stats.package.downloads.total);
+            }
+        }
+        pkg.url = pkg
+            .url
+            .or_else(|| Some(format!("{}/packages/{}", PACKAGIST_URL, name)));
+
+        Ok(Some(pkg))
+    }
+
+    fn run_global(&self, args: &[&str], packages: &[&str]) -> Result<Vec<InstallResult>> {
+        if packages.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let status = Command::new("composer")
+            .arg("global")
+            .args(args)
+            .args(packages)
+            .stdin(Stdio::inherit())
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::inherit())
+            .status()
+            .with_context(|| format!("Failed to run composer global {}", args[0]))?;
+
+        let success = status.success();
+        Ok(packages
+            .iter()
+            .map(|name| InstallResult {
+                package: name.to_string(),
+                success,
+                message: if success {
+                    None
+                } else {
+                    Some(format!("composer global {} failed", args[0]))
+                },
+            })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl PackageManager for ComposerBackend {
+    fn name(&self) -> &str {
+        "Composer (PHP)"
+    }
+
+    fn id(&self) -> &str {
+        "composer"
+    }
+
+    async fn search(&self, query: &str) -> Result<Vec<Package>> {
+        if query.len() < 2 {
+            return Ok(vec![]);
+        }
+
+        let response: SearchResponse = self
+            .client
+            .get(format!("{}/search.json", PACKAGIST_URL))
+            .query(&[("q", query), ("per_page", "30")])
+            .send()
+            .await
+            .context("Failed to search Packagist")?
+            .json()
+            .await
+            .context("Failed to parse Packagist response")?;
+
+        let installed = self.installed_names();
+        let mut packages: Vec<Package> = response
+            .results
+            .into_iter()
+            .map(|result| {
+                // Search results carry no version; `info` has it
+                let mut pkg = Package::new(result.name, "").with_description(result.description);
+                pkg.popularity = download_popularity(result.downloads);
+                pkg.url = result.url;
+                pkg.extra.downloads = Some(result.downloads);
+                pkg.installed = installed.contains(&pkg.name);
+                pkg
+            })
+            .collect();
+        packages.truncate(30);
+
+        Ok(packages)
+    }
+
+    async fn info(&self, packages: &[&str]) -> Result<Vec<Package>> {
+        let installed = self.installed_names();
+        let mut results = vec![];
+
+        for pkg_name in packages {
+            if let Some(mut pkg) = self.fetch_package(pkg_name).await? {
+                pkg.installed = installed.contains(&pkg.name);
+                results.push(pkg);
+            }
+        }
+
+        Ok(results)
+    }
+
+    async fn install(&self, packages: &[Package]) -> Result<Vec<InstallResult>> {
+        let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+        if !names.is_empty() {
+            println!("--> Installing packages with composer global require...");
+        }
+        // Also used for updates: requiring again moves the constraint to the
+        // newest version, where `composer global update` would stay within it
+        self.run_global(&["require"], &names)
+    }
+
+    async fn remove(&self, packages: &[&str]) -> Result<Vec<InstallResult>> {
+        if !packages.is_empty() {
+            println!("--> Removing packages with composer global remove...");
+        }
+        self.run_global(&["remove"], packages)
+    }
+
+    fn is_installed(&self, package: &str) -> Result<bool> {
+        Ok(self.installed_names().contains(package))
+    }
+
+    fn list_installed(&self) -> Result<Vec<(String, String)>> {
+        Ok(self
+            .show("show")?
+            .into_iter()
+            .map(|entry| (entry.name, entry.version))
+            .collect())
+    }
+
+    async fn check_updates(&self) -> Result<Vec<Package>> {
+        Ok(outdated_packages(self.show("outdated")?))
+    }
+}
+
+/// The newest stable version in `/p2/` metadata. Composer 2 minifies it: each
+/// entry only lists the fields that changed from the one before, and "__unset"
+/// removes a field, so entries are expanded in order until a stable one.
+fn parse_metadata(mut metadata: MetadataResponse, name: &str) -> Option<Package> {
+    let mut expanded = serde_json::Map::new();
+    let version = metadata
+        .packages
+        .remove(name)?
+        .into_iter()
+        .find_map(|entry| {
+            for (key, value) in entry {
+                if value == "__unset" {
+                    expanded.remove(&key);
+                } else {
+                    expanded.insert(key, value);
+                }
+            }
+            serde_json::from_value::<Version>(serde_json::Value::Object(expanded.clone()))
+                .ok()
+                .filter(|v| is_stable(v.version_normalized.as_deref().unwrap_or(&v.version)))
+        })?;
+
+    let mut pkg = Package::new(name, version.version).with_description(version.description);
+    pkg.url = version.homepage.filter(|h| !h.is_empty());
+    pkg.maintainer = Some(
+        version
+            .authors
+            .into_iter()
+            .filter_map(|a| a.name)
+            .collect::<Vec<_>>()
+            .join(", "),
+    )
+    .filter(|m| !m.is_empty());
+    pkg.extra.license = version.license;
+    if let Some(require) = version.require.as_object() {
+        pkg.extra.depends = require
+            .iter()
+            .map(|(dep, constraint)| format!("{} {}", dep, constraint.as_str().unwrap_or("*")))
+            .collect();
+    }
+    Some(pkg)
+}
+
+/// Composer's stability of a normalized version: "1.2.0.0" and
+/// "1.2.0.0-patch1" are stable, "1.2.0.0-RC1" and "dev-main" are not
+fn is_stable(version: &str) -> bool {
+    if version.starts_with("dev-") {
+        return false;
+    }
+    match version.split_once('-') {
+        Some((_, suffix)) => suffix.to_lowercase().starts_with("patch"),
+        None => true,
+    }
+}
+
+fn outdated_packages(entries: Vec<ShowEntry>) -> Vec<Package> {
+    entries
+        .into_iter()
+        .filter_map(|entry| {
+            let latest = entry.latest.filter(|latest| *latest != entry.version)?;
+            let mut pkg = Package::new(entry.name, latest).with_description(entry.description);
+            pkg.installed = true;
+            Some(pkg)
+        })
+        .collect()
+}
+
+/// Whether a name has Composer's "vendor/package" form, with the lowercase
+/// letters, digits and `.`, `_`, `-` separators Packagist accepts
+pub fn is_package_name(name: &str) -> bool {
+    let Some((vendor, package)) = name.split_once('/') else {
+        return false;
+    };
+    let valid = |part: &str| {
+        let bytes = part.as_bytes();
+        let alnum = |b: u8| b.is_ascii_lowercase() || b.is_ascii_digit();
+        !bytes.is_empty()
+            && alnum(bytes[0])
+            && alnum(bytes[bytes.len() - 1])
+            && bytes
+                .iter()
+                .all(|&b| alnum(b) || matches!(b, b'.' | b'_' | b'-'))
+            && !part.contains("..")
+            && !part.contains("__")
+            && !part.contains("---")
+    };
+    valid(vendor) && valid(package)
+}
+
+fn command_exists(cmd: &str) -> bool {
+    Command::new("which")
+        .arg(cmd)
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_metadata() {
+        let json = r#"{
+            "packages": {
+                "phpstan/phpstan": [
+                    {
+                        "name": "phpstan/phpstan",
+                        "description": "PHPStan - PHP Static Analysis Tool",
+                        "keywords": ["dev", "static analysis"],
+                        "homepage": "",
+                        "version": "1.11.10",
+                        "version_normalized": "1.11.10.0",
+                        "license": ["MIT"],
+                        "authors": [],
+                        "require": {"php": "^7.2|^8.0"},
+                        "type": "library"
+                    },
+                    {
+                        "version": "1.11.9",
+                        "version_normalized": "1.11.9.0"
+                    }
+                ]
+            },
+            "minified": "composer/2.0"
+        }"#;
+        let metadata: MetadataResponse = serde_json::from_str(json).unwrap();
+        let pkg = parse_metadata(metadata, "phpstan/phpstan").unwrap();
+        assert_eq!(pkg.version, "1.11.10");
+        assert_eq!(
+            pkg.description.as_deref(),
+            Some("PHPStan - PHP Static Analysis Tool")
+        );
+        assert_eq!(pkg.extra.license, ["MIT"]);
+        assert_eq!(pkg.extra.depends, ["php ^7.2|^8.0"]);
+        assert!(pkg.url.is_none());
+        assert!(pkg.maintainer.is_none());
+    }
+
+    #[test]
+    fn test_parse_metadata_skips_prereleases() {
+        let json = r#"{
+            "packages": {
+                "laravel/installer": [
+                    {
+                        "name": "laravel/installer",
+                        "description": "Laravel application installer.",
+                        "version": "v6.0.0-beta1",
+                        "version_normalized": "6.0.0.0-beta1",
+                        "license": ["MIT"],
+                        "require": {"php": "^8.2"}
+                    },
+                    {
+                        "version": "v5.8.3",
+                        "version_normalized": "5.8.3.0",
+                        "require": "__unset"
+                    }
+                ]
+            },
+            "minified": "composer/2.0"
+        }"#;
+        let metadata: MetadataResponse = serde_json::from_str(json).unwrap();
+        let pkg = parse_metadata(metadata, "laravel/installer").unwrap();
+        assert_eq!(pkg.version, "v5.8.3");
+        assert_eq!(
+            pkg.description.as_deref(),
+            Some("Laravel application installer.")
+        );
+        assert_eq!(pkg.extra.license, ["MIT"]);
+        assert!(pkg.extra.depends.is_empty());
+    }
+
+    #[test]
+    fn test_is_stable() {
+        assert!(is_stable("1.11.10.0"));
+        assert!(is_stable("2.0.0.0-patch1"));
+        assert!(!is_stable("6.0.0.0-beta1"));
+        assert!(!is_stable("3.0.0.0-RC2"));
+        assert!(!is_stable("dev-main"));
+    }
+
+    #[test]
+    fn test_outdated_packages() {
+        let json = r#"{
+            "installed": [
+                {"name": "friendsofphp/php-cs-fixer", "direct-dependency": true, "homepage": null,
+                 "source": "https://github.com/PHP-CS-Fixer/PHP-CS-Fixer/tree/v3.59.3",
+                 "version": "v3.59.3", "latest": "v3.62.0", "latest-status": "semver-safe-update",
+                 "description": "A tool to automatically fix PHP code style", "abandoned": false},
+                {"name": "laravel/installer", "direct-dependency": true,
+                 "version": "v5.8.3", "latest": "v5.8.3", "latest-status": "up-to-date",
+                 "description": "Laravel application installer.", "abandoned": false}
+            ]
+        }"#;
+        let response: ShowResponse = serde_json::from_str(json).unwrap();
+        let updates = outdated_packages(response.installed);
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].name, "friendsofphp/php-cs-fixer");
+        assert_eq!(updates[0].version, "v3.62.0");
+    }
+
+    #[test]
+    fn test_is_package_name() {
+        assert!(is_package_name("laravel/installer"));
+        assert!(is_package_name("friendsofphp/php-cs-fixer"));
+        assert!(is_package_name("symfony/polyfill-php80"));
+        assert!(!is_package_name("@angular/cli"));
+        assert!(!is_package_name("Vendor/Package"));
+        assert!(!is_package_name("github.com/cli/cli"));
+        assert!(!is_package_name("ripgrep"));
+        assert!(!is_package_name("a/b/c"));
+    }
+}
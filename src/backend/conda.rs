@@ -0,0 +1,598 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+
+use super::{InstallResult, Package, PackageManager};
+use crate::audit::compare_versions;
+
+static ENV: OnceLock<String> = OnceLock::new();
+
+/// Use the named conda environment instead of the active one
+pub fn set_env(name: Option<String>) {
+    if let Some(name) = name {
+        let _ = ENV.set(name);
+    }
+}
+
+/// conda backend for the active (or `--env`) environment, driven by
+/// micromamba, mamba or conda, whichever is found first
+pub struct CondaBackend {
+    /// Command or path of the tool
+    tool: String,
+    /// "micromamba", "mamba" or "conda"
+    flavor: String,
+}
+
+/// A package build in a channel, as printed by `search --json`
+#[derive(Debug, Deserialize)]
+struct Record {
+    name: String,
+    version: String,
+    #[serde(default)]
+    channel: String,
+    #[serde(default)]
+    depends: Vec<String>,
+    license: Option<String>,
+}
+
+/// A package in an environment, as printed by `list --json`
+#[derive(Debug, Deserialize)]
+struct ListEntry {
+    name: String,
+    version: String,
+}
+
+/// The `conda-meta/<name>-<version>-<build>.json` record of an installed package
+#[derive(Debug, Deserialize)]
+struct PrefixRecord {
+    name: String,
+    version: String,
+    #[serde(default)]
+    files: Vec<String>,
+}
+
+impl CondaBackend {
+    pub fn new() -> Result<Self> {
+        let tool = ["micromamba", "mamba", "conda"]
+            .into_iter()
+            .find(|cmd| command_exists(cmd))
+            .map(str::to_string)
+            // `conda activate` wraps the tool in a shell function, which
+            // leaves the real binary off PATH
+            .or_else(|| {
+                ["MAMBA_EXE", "CONDA_EXE"]
+                    .into_iter()
+                    .filter_map(std::env::var_os)
+                    .map(PathBuf::from)
+                    .find(|path| path.is_file())
+                    .map(|path| path.to_string_lossy().into_owned())
+            })
+            .context("conda is not available on this system (tried micromamba, mamba and conda)")?;
+
+        let flavor = Path::new(&tool)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "conda".to_string());
+        Ok(Self { tool, flavor })
+    }
+
+    fn command(&self) -> Command {
+        Command::new(&self.tool)
+    }
+
+    /// `-n <env>` for `--env`, otherwise `-p` for the active environment.
+    /// Without either the tool falls back to its base environment.
+    fn target_args(&self) -> Vec<String> {
+        if let Some(name) = ENV.get() {
+            return vec!["-n".to_string(), name.clone()];
+        }
+        match std::env::var("CONDA_PREFIX") {
+            Ok(prefix) if !prefix.is_empty() => vec!["-p".to_string(), prefix],
+            _ => vec![],
+        }
+    }
+
+    fn target_name(&self) -> String {
+        if let Some(name) = ENV.get() {
+            return name.clone();
+        }
+        std::env::var("CONDA_DEFAULT_ENV")
+            .ok()
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| "base".to_string())
+    }
+
+    /// Directory of the target environment
+    fn prefix(&self) -> Option<PathBuf> {
+        let Some(name) = ENV.get() else {
+            return std::env::var_os("CONDA_PREFIX").map(PathBuf::from);
+        };
+
+        let json = |args: &[&str]| -> Option<Value> {
+            let output = self.command().args(args).output().ok()?;
+            serde_json::from_slice(&output.stdout).ok()
+        };
+        // The base environment is the installation root, whatever its directory is called
+        let info = json(&["info", "--json"]).unwrap_or_default();
+        let root = ["root_prefix", "base environment"]
+            .into_iter()
+            .find_map(|key| info.get(key)?.as_str())
+            .map(PathBuf::from);
+
+        let envs: Vec<PathBuf> = json(&["env", "list", "--json"])?
+            .get("envs")?
+            .as_array()?
+            .iter()
+            .filter_map(Value::as_str)
+            .map(PathBuf::from)
+            .collect();
+        find_env(name, &envs, root.as_deref())
+    }
+
+    fn search_records(&self, spec: &str) -> Result<Vec<Record>> {
+        let output = self
+            .command()
+            .args(["search", spec, "--json"])
+            .output()
+            .with_context(|| format!("Failed to run {} search", self.flavor))?;
+
+        // A failed search still prints JSON, with an "error" instead of results
+        let json: Value = serde_json::from_slice(&output.stdout)
+            .with_context(|| format!("Failed to parse {} search output", self.flavor))?;
+        Ok(parse_search(json))
+    }
+
+    /// Run an install/remove/update transaction in the target environment
+    fn transact(&self, action: &str, packages: &[&str]) -> Result<Vec<InstallResult>> {
+        if packages.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let status = self
+            .command()
+            .args([action, "-y"])
+            .args(self.target_args())
+            .args(packages)
+            .stdin(Stdio::inherit())
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::inherit())
+            .status()
+            .with_context(|| format!("Failed to run {} {}", self.flavor, action))?;
+
+        let success = status.success();
+        Ok(packages
+            .iter()
+            .map(|name| InstallResult {
+                package: name.to_string(),
+                success,
+                message: if success {
+                    None
+                } else {
+                    Some(format!("{} {} failed", self.flavor, action))
+                },
+            })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl PackageManager for CondaBackend {
+    fn name(&self) -> &str {
+        &self.flavor
+    }
+
+    fn id(&self) -> &str {
+        "conda"
+    }
+
+    async fn search(&self, query: &str) -> Result<Vec<Package>> {
+        if query.len() < 2 {
+            return Ok(vec![]);
+        }
+
+        let records = self.search_records(&format!("*{}*", query))?;
+        let installed: HashMap<String, String> = self.list_installed()?.into_iter().collect();
+
+        let mut packages: Vec<Package> = newest_builds(records)
+            .into_iter()
+            .map(|record| {
+                let mut pkg = record_to_package(record);
+                pkg.installed = installed.contains_key(&pkg.name);
+                pkg
+            })
+            .collect();
+
+        // The glob matches anywhere in the name, so put exact and prefix
+        // matches first
+        let query = query.to_lowercase();
+        packages.sort_by_key(|pkg| {
+            let name = pkg.name.to_lowercase();
+            (name != query, !name.starts_with(&query), name.len())
+        });
+        packages.truncate(30);
+
+        Ok(packages)
+    }
+
+    async fn info(&self, packages: &[&str]) -> Result<Vec<Package>> {
+        let installed: HashMap<String, String> = self.list_installed()?.into_iter().collect();
+        let mut results = vec![];
+
+        for pkg_name in packages {
+            let records = self.search_records(pkg_name)?;
+            if let Some(record) = newest_builds(records)
+                .into_iter()
+                .find(|r| r.name == *pkg_name)
+            {
+                let mut pkg = record_to_package(record);
+                pkg.installed = installed.contains_key(&pkg.name);
+                results.push(pkg);
+            }
+        }
+
+        Ok(results)
+    }
+
+    async fn install(&self, packages: &[Package]) -> Result<Vec<InstallResult>> {
+        let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+        if !names.is_empty() {
+            println!(
+                "--> Installing packages with {} into {}...",
+                self.flavor,
+                self.target_name()
+            );
+        }
+        self.transact("install", &names)
+    }
+
+    async fn remove(&self, packages: &[&str]) -> Result<Vec<InstallResult>> {
+        if !packages.is_empty() {
+            println!(
+                "--> Removing packages with {} from {}...",
+                self.flavor,
+                self.target_name()
+            );
+        }
+        self.transact("remove", packages)
+    }
+
+    async fn update(&self, packages: &[Package]) -> Result<Vec<InstallResult>> {
+        let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+        self.transact("update", &names)
+    }
+
+    fn is_installed(&self, package: &str) -> Result<bool> {
+        Ok(self
+            .list_installed()?
+            .iter()
+            .any(|(name, _)| name == package))
+    }
+
+    fn list_installed(&self) -> Result<Vec<(String, String)>> {
+        let output = self
+            .command()
+            .args(["list", "--json"])
+            .args(self.target_args())
+            .output()
+            .with_context(|| format!("Failed to run {} list", self.flavor))?;
+
+        if !output.status.success() {
+            return Ok(vec![]);
+        }
+
+        let entries: Vec<ListEntry> = serde_json::from_slice(&output.stdout)
+            .with_context(|| format!("Failed to parse {} list output", self.flavor))?;
+        Ok(entries.into_iter().map(|e| (e.name, e.version)).collect())
+    }
+
+    async fn check_updates(&self) -> Result<Vec<Package>> {
+        // A dry-run solve lists what `update --all` would unlink and link
+        // again at another version
+        let output = self
+            .command()
+            .args(["update", "--all", "--dry-run", "--json"])
+            .args(self.target_args())
+            .output()
+            .context("Failed to check for updates")?;
+
+        let Ok(json) = serde_json::from_slice::<Value>(&output.stdout) else {
+            return Ok(vec![]);
+        };
+        Ok(parse_dry_run(&json))
+    }
+
+    fn owns(&self, path: &Path) -> Result<Option<(String, String)>> {
+        let Some(prefix) = self.prefix() else {
+            return Ok(None);
+        };
+        let Ok(relative) = path.strip_prefix(&prefix) else {
+            return Ok(None);
+        };
+        let relative = relative.to_string_lossy().replace('\\', "/");
+
+        let Ok(entries) = std::fs::read_dir(prefix.join("conda-meta")) else {
+            return Ok(None);
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let Ok(content) = std::fs::read(&path) else {
+                continue;
+            };
+            if let Ok(record) = serde_json::from_slice::<PrefixRecord>(&content) {
+                if record.files.contains(&relative) {
+                    return Ok(Some((record.name, record.version)));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+/// Parse `search --json`. conda and mamba 1 map each name to its builds,
+/// micromamba and mamba 2 return `{"result": {"pkgs": [...]}}`.
+fn parse_search(json: Value) -> Vec<Record> {
+    if json.get("error").is_some() {
+        return vec![];
+    }
+
+    let builds: Vec<Value> = match json {
+        Value::Object(mut map) => match map.remove("result") {
+            Some(mut result) => match result.get_mut("pkgs").map(Value::take) {
+                Some(Value::Array(pkgs)) => pkgs,
+                _ => vec![],
+            },
+            None => map
+                .into_iter()
+                .filter_map(|(_, builds)| match builds {
+                    Value::Array(builds) => Some(builds),
+                    _ => None,
+                })
+                .flatten()
+                .collect(),
+        },
+        _ => vec![],
+    };
+
+    builds
+        .into_iter()
+        .filter_map(|build| serde_json::from_value(build).ok())
+        .collect()
+}
+
+/// The newest build of every package, in the order the names first appear
+fn newest_builds(records: Vec<Record>) -> Vec<Record> {
+    let mut newest: Vec<Record> = vec![];
+    for record in records {
+        match newest.iter_mut().find(|r| r.name == record.name) {
+            Some(existing) => {
+                if compare_versions(&record.version, &existing.version) == Ordering::Greater {
+                    *existing = record;
+                }
+            }
+            None => newest.push(record),
+        }
+    }
+    newest
+}
+
+fn record_to_package(record: Record) -> Package {
+    let channel = channel_name(&record.channel);
+    let description = Some(channel)
+        .filter(|c| !c.is_empty())
+        .map(|c| format!("{} channel", c));
+    let mut pkg = Package::new(record.name, record.version).with_description(description);
+    pkg.extra.depends = record.depends;
+    pkg.extra.license = record.license.into_iter().collect();
+    pkg
+}
+
+/// "https://conda.anaconda.org/conda-forge/linux-64" -> "conda-forge"
+fn channel_name(channel: &str) -> &str {
+    let channel = channel.trim_end_matches('/');
+    let Some(rest) = channel.split_once("://").map(|(_, rest)| rest) else {
+        return channel;
+    };
+    let mut parts: Vec<&str> = rest.split('/').skip(1).collect();
+    let is_subdir = |part: &str| {
+        part == "noarch"
+            || part.starts_with("linux-")
+            || part.starts_with("osx-")
+            || part.starts_with("win-")
+    };
+    if parts.last().is_some_and(|p| is_subdir(p)) {
+        parts.pop();
+    }
+    match parts.last() {
+        Some(name) if !name.is_empty() => name,
+        _ => channel,
+    }
+}
+
+/// Packages the dry-run solve would link at a different version than the
+/// one it unlinks
+fn parse_dry_run(json: &Value) -> Vec<Package> {
+    let actions = |kind: &str| -> Vec<(String, String)> {
+        json.get("actions")
+            .and_then(|a| a.get(kind))
+            .and_then(Value::as_array)
+            .map(|entries| {
+                entries
+                    .iter()
+                    .filter_map(|e| {
+                        Some((
+                            e.get("name")?.as_str()?.to_string(),
+                            e.get("version")?.as_str()?.to_string(),
+                        ))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let unlinked: HashMap<String, String> = actions("UNLINK").into_iter().collect();
+    actions("LINK")
+        .into_iter()
+        .filter(|(name, version)| unlinked.get(name).is_some_and(|old| old != version))
+        .map(|(name, version)| {
+            let mut pkg = Package::new(name, version);
+            pkg.installed = true;
+            pkg
+        })
+        .collect()
+}
+
+fn command_exists(cmd: &str) -> bool {
+    Command::new("which")
+        .arg(cmd)
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+/// The prefix of the environment called `name`: the root for "base", otherwise
+/// the listed environment with that directory name
+fn find_env(name: &str, envs: &[PathBuf], root: Option<&Path>) -> Option<PathBuf> {
+    if name == "base" {
+        return root.map(Path::to_path_buf);
+    }
+    envs.iter()
+        .filter(|path| Some(path.as_path()) != root)
+        .find(|path| path.file_name().is_some_and(|n| n == name))
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_find_env() {
+        let root = PathBuf::from("/home/me/miniforge3");
+        let envs = [
+            root.clone(),
+            PathBuf::from("/home/me/miniforge3/envs/ml"),
+            PathBuf::from("/home/me/projects/app/.conda"),
+        ];
+        assert_eq!(find_env("base", &envs, Some(&root)), Some(root.clone()));
+        assert_eq!(
+            find_env("ml", &envs, Some(&root)),
+            Some(PathBuf::from("/home/me/miniforge3/envs/ml"))
+        );
+        assert_eq!(find_env("miniforge3", &envs, Some(&root)), None);
+        assert_eq!(find_env("missing", &envs, Some(&root)), None);
+    }
+
+    #[test]
+    fn test_parse_search_conda() {
+        let output = json!({
+            "numpy": [
+                {"name": "numpy", "version": "1.26.4", "build": "py312h8753938_0",
+                 "channel": "conda-forge", "license": "BSD-3-Clause",
+                 "depends": ["libblas >=3.9.0,<4.0a0", "python >=3.12,<3.13.0a0"]},
+                {"name": "numpy", "version": "2.0.1", "build": "py312h1103770_0",
+                 "channel": "conda-forge", "license": "BSD-3-Clause",
+                 "depends": ["libblas >=3.9.0,<4.0a0", "python >=3.12,<3.13.0a0", "python_abi 3.12.* *_cp312"]}
+            ],
+            "numpy-base": [
+                {"name": "numpy-base", "version": "1.26.4", "build": "py312he1a6c75_0",
+                 "channel": "pkgs/main", "depends": []}
+            ]
+        });
+        let packages = newest_builds(parse_search(output));
+        assert_eq!(packages.len(), 2);
+        let numpy = packages.iter().find(|p| p.name == "numpy").unwrap();
+        assert_eq!(numpy.version, "2.0.1");
+        assert_eq!(numpy.depends.len(), 3);
+
+        let pkg = record_to_package(packages.into_iter().find(|p| p.name == "numpy").unwrap());
+        assert_eq!(pkg.extra.license, ["BSD-3-Clause"]);
+        assert_eq!(pkg.description.as_deref(), Some("conda-forge channel"));
+    }
+
+    #[test]
+    fn test_parse_search_micromamba() {
+        let output = json!({
+            "query": {"query": "*ripgrep*", "type": "search"},
+            "result": {
+                "msg": "",
+                "pkgs": [
+                    {"name": "ripgrep", "version": "14.1.0", "build": "he8a937b_0",
+                     "channel": "https://conda.anaconda.org/conda-forge/linux-64",
+                     "depends": ["__glibc >=2.17,<3.0.a0"], "license": "MIT"},
+                    {"name": "ripgrep", "version": "14.1.1", "build": "h8fae777_0",
+                     "channel": "https://conda.anaconda.org/conda-forge/linux-64",
+                     "depends": ["__glibc >=2.17,<3.0.a0"], "license": "MIT"}
+                ],
+                "status": "OK"
+            }
+        });
+        let packages = newest_builds(parse_search(output));
+        assert_eq!(packages.len(), 1);
+        assert_eq!(packages[0].version, "14.1.1");
+        assert_eq!(channel_name(&packages[0].channel), "conda-forge");
+    }
+
+    #[test]
+    fn test_parse_search_not_found() {
+        let output = json!({
+            "error": "PackagesNotFoundError: The following packages are not available from current channels",
+            "exception_name": "PackagesNotFoundError"
+        });
+        assert!(parse_search(output).is_empty());
+    }
+
+    #[test]
+    fn test_channel_name() {
+        assert_eq!(channel_name("conda-forge"), "conda-forge");
+        assert_eq!(channel_name("pkgs/main"), "pkgs/main");
+        assert_eq!(
+            channel_name("https://conda.anaconda.org/bioconda/noarch"),
+            "bioconda"
+        );
+        assert_eq!(
+            channel_name("https://repo.anaconda.com/pkgs/main/osx-arm64/"),
+            "main"
+        );
+    }
+
+    #[test]
+    fn test_parse_dry_run() {
+        let output = json!({
+            "actions": {
+                "FETCH": [],
+                "LINK": [
+                    {"name": "openssl", "version": "3.3.2", "channel": "conda-forge"},
+                    {"name": "ca-certificates", "version": "2024.8.30", "channel": "conda-forge"},
+                    {"name": "libzlib", "version": "1.3.1", "channel": "conda-forge"}
+                ],
+                "UNLINK": [
+                    {"name": "openssl", "version": "3.3.1", "channel": "conda-forge"},
+                    {"name": "ca-certificates", "version": "2024.7.4", "channel": "conda-forge"}
+                ],
+                "PREFIX": "/home/user/miniforge3/envs/data"
+            },
+            "dry_run": true,
+            "success": true
+        });
+        let updates = parse_dry_run(&output);
+        assert_eq!(updates.len(), 2);
+        assert_eq!(updates[0].name, "openssl");
+        assert_eq!(updates[0].version, "3.3.2");
+        assert_eq!(updates[1].name, "ca-certificates");
+
+        let up_to_date =
+            json!({"message": "All requested packages already installed.", "success": true});
+        assert!(parse_dry_run(&up_to_date).is_empty());
+    }
+}
@@ -13,6 +13,14 @@ pub enum System {
     Fedora,
     /// openSUSE (Leap, Tumbleweed)
     OpenSUSE,
+    /// Alpine Linux
+    Alpine,
+    /// Void Linux
+    Void,
+    /// NixOS
+    NixOS,
+    /// Gentoo and derivatives (Funtoo, Calculate, etc.)
+    Gentoo,
     /// FreeBSD
     FreeBSD,
     /// macOS
@@ -30,6 +38,10 @@ impl System {
             System::Debian | System::Ubuntu => "APT",
             System::Fedora => "DNF",
             System::OpenSUSE => "zypper",
+            System::Alpine => "apk",
+            System::Void => "XBPS",
+            System::NixOS => "Nix",
+            System::Gentoo => "Portage",
             System::FreeBSD => "pkg",
             System::MacOS => "Homebrew",
             System::Windows => {
@@ -50,7 +62,15 @@ impl System {
     pub fn is_linux(&self) -> bool {
         matches!(
             self,
-            System::Arch | System::Debian | System::Ubuntu | System::Fedora | System::OpenSUSE
+            System::Arch
+                | System::Debian
+                | System::Ubuntu
+                | System::Fedora
+                | System::OpenSUSE
+                | System::Alpine
+                | System::Void
+                | System::NixOS
+                | System::Gentoo
         )
     }
 
@@ -94,6 +114,11 @@ pub fn detect_system() -> System {
         }
     }
 
+    // Gentoo ships /etc/gentoo-release, also on derivatives with their own os-release
+    if std::path::Path::new("/etc/gentoo-release").exists() {
+        return System::Gentoo;
+    }
+
     // On Linux, check /etc/os-release
     if let Ok(os_release) = std::fs::read_to_string("/etc/os-release") {
         let os_release_lower = os_release.to_lowercase();
@@ -111,6 +136,18 @@ pub fn detect_system() -> System {
             return System::Arch;
         }
 
+        if os_release_lower.contains("id=alpine") {
+            return System::Alpine;
+        }
+
+        if os_release_lower.contains("id=void") || os_release_lower.contains("id=\"void") {
+            return System::Void;
+        }
+
+        if os_release_lower.contains("id=nixos") {
+            return System::NixOS;
+        }
+
         if os_release_lower.contains("id=opensuse")
             || os_release_lower.contains("id=suse")
             || os_release_lower.contains("id_like=opensuse")
@@ -160,6 +197,18 @@ pub fn detect_system() -> System {
         return System::OpenSUSE;
     }
 
+    if command_exists("apk") {
+        return System::Alpine;
+    }
+
+    if command_exists("xbps-install") {
+        return System::Void;
+    }
+
+    if std::path::Path::new("/etc/NIXOS").exists() {
+        return System::NixOS;
+    }
+
     if command_exists("apt") || command_exists("apt-get") {
         // Try to distinguish Ubuntu from Debian
         if let Ok(output) = Command::new("lsb_release").arg("-i").output() {
@@ -225,6 +274,15 @@ pub fn detect_available_package_managers() -> Vec<&'static str> {
     if command_exists("zypper") {
         managers.push("zypper");
     }
+    if command_exists("apk") {
+        managers.push("apk");
+    }
+    if command_exists("xbps-install") {
+        managers.push("xbps");
+    }
+    if command_exists("emerge") {
+        managers.push("portage");
+    }
     if command_exists("pkg") {
         managers.push("pkg");
     }
@@ -248,18 +306,46 @@ pub fn detect_available_package_managers() -> Vec<&'static str> {
     if command_exists("snap") {
         managers.push("snap");
     }
+    if command_exists("nix") {
+        managers.push("nix");
+    }
 
     // Language package managers
     if command_exists("pip") || command_exists("pip3") {
         managers.push("pip");
     }
+    if std::env::var_os("CONDA_PREFIX").is_some()
+        || ["micromamba", "mamba", "conda"]
+            .iter()
+            .any(|cmd| command_exists(cmd))
+    {
+        managers.push("conda");
+    }
+    if command_exists("gem") {
+        managers.push("gem");
+    }
+    if command_exists("composer") {
+        managers.push("composer");
+    }
+    if super::cabal::cabal_command().is_some() {
+        managers.push("cabal");
+    }
+    if command_exists("opam") {
+        managers.push("opam");
+    }
+    if command_exists("dotnet") {
+        managers.push("dotnet");
+    }
     if command_exists("cargo") {
         managers.push("cargo");
     }
     if command_exists("go") {
         managers.push("go");
     }
-    if command_exists("npm") {
+    if ["npm", "pnpm", "yarn", "bun"]
+        .iter()
+        .any(|cmd| command_exists(cmd))
+    {
         managers.push("npm");
     }
     if command_exists("deno") {
@@ -1,5 +1,6 @@
 use anyhow::{Context, Result};
 use async_trait::async_trait;
+use std::path::Path;
 use std::process::{Command, Stdio};
 
 use super::sudo;
@@ -284,6 +285,33 @@ impl PackageManager for DnfBackend {
         Ok(results)
     }
 
+    async fn remove(&self, packages: &[&str]) -> Result<Vec<InstallResult>> {
+        if packages.is_empty() {
+            return Ok(vec![]);
+        }
+
+        println!("--> Removing packages with dnf...");
+
+        let mut args = vec!["dnf", "remove", "-y"];
+        args.extend(packages.iter().copied());
+
+        let status = sudo::run_sudo(&args).context("Failed to run dnf remove")?;
+
+        let success = status.success();
+        Ok(packages
+            .iter()
+            .map(|name| InstallResult {
+                package: name.to_string(),
+                success,
+                message: if success {
+                    None
+                } else {
+                    Some("dnf remove failed".to_string())
+                },
+            })
+            .collect())
+    }
+
     fn is_installed(&self, package: &str) -> Result<bool> {
         let output = Command::new("rpm")
             .args(["-q", package])
@@ -351,6 +379,77 @@ impl PackageManager for DnfBackend {
 
         Ok(updates)
     }
+
+    fn owns(&self, path: &Path) -> Result<Option<(String, String)>> {
+        rpm_owner(path)
+    }
+
+    async fn provides(&self, target: &str) -> Result<Vec<Package>> {
+        // "*bin/rg" covers /usr/bin, /usr/sbin and /usr/local/bin
+        let pattern = if target.contains('/') {
+            target.to_string()
+        } else {
+            format!("*bin/{}", target)
+        };
+
+        let output = Command::new("dnf")
+            .args(["-q", "provides", &pattern])
+            .output()
+            .context("Failed to run dnf provides")?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let names = parse_provides(&stdout);
+        let names: Vec<&str> = names.iter().map(|n| n.as_str()).collect();
+        self.info(&names).await
+    }
+}
+
+/// The rpm package owning `path`, shared with the zypper backend
+pub(crate) fn rpm_owner(path: &Path) -> Result<Option<(String, String)>> {
+    let output = Command::new("rpm")
+        .args(["-qf", "--queryformat", "%{NAME} %{VERSION}-%{RELEASE}\n"])
+        .arg(path)
+        .output()
+        .context("Failed to run rpm -qf")?;
+
+    if !output.status.success() {
+        return Ok(None);
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout
+        .lines()
+        .next()
+        .and_then(|line| line.split_once(' '))
+        .map(|(name, version)| (name.to_string(), version.to_string())))
+}
+
+/// Package names from `dnf provides`, whose matches start with an unindented
+/// "name-version-release.arch : summary" line
+fn parse_provides(output: &str) -> Vec<String> {
+    let mut names: Vec<String> = vec![];
+    for line in output.lines() {
+        let Some((nevra, _)) = line.split_once(" : ") else {
+            continue;
+        };
+        // "Repo        : fedora" and friends have no version part
+        if nevra.starts_with(char::is_whitespace) || nevra.contains(' ') {
+            continue;
+        }
+        let Some((without_arch, _)) = nevra.rsplit_once('.') else {
+            continue;
+        };
+        let mut parts = without_arch.rsplitn(3, '-');
+        let (Some(_release), Some(_version), Some(name)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            continue;
+        };
+        if !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+    }
+    names
 }
 
 fn command_exists(cmd: &str) -> bool {
@@ -360,3 +459,22 @@ fn command_exists(cmd: &str) -> bool {
         .map(|o| o.status.success())
         .unwrap_or(false)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_provides() {
+        let output = "ripgrep-14.1.0-1.fc40.x86_64 : Line-oriented search tool\n\
+                      Repo        : fedora\n\
+                      Matched from:\n\
+                      Filename    : /usr/bin/rg\n\
+                      \n\
+                      ripgrep-14.1.0-2.fc40.x86_64 : Line-oriented search tool\n\
+                      Repo        : updates\n\
+                      \n\
+                      python3-devel-3.12.4-1.fc40.x86_64 : Libraries and header files\n";
+        assert_eq!(parse_provides(output), ["ripgrep", "python3-devel"]);
+    }
+}
@@ -0,0 +1,392 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::cmp::Ordering;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use tokio::sync::OnceCell;
+
+use super::{download_popularity, InstallResult, Package, PackageManager};
+use crate::audit::compare_versions;
+
+const NUGET_INDEX_URL: &str = "https://api.nuget.org/v3/index.json";
+
+/// .NET global tools from NuGet (`dotnet tool -g`)
+pub struct DotnetToolBackend {
+    client: reqwest::Client,
+    index: OnceCell<ServiceIndex>,
+}
+
+/// The NuGet service index, listing the URL of each API
+#[derive(Debug, Deserialize)]
+struct ServiceIndex {
+    resources: Vec<Resource>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Resource {
+    #[serde(rename = "@id")]
+    id: String,
+    #[serde(rename = "@type")]
+    kind: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    data: Vec<SearchResult>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchResult {
+    id: String,
+    version: String,
+    description: Option<String>,
+    #[serde(default)]
+    authors: Vec<String>,
+    #[serde(default)]
+    total_downloads: u64,
+    project_url: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+/// `<PackageBaseAddress>/<id>/index.json`
+#[derive(Debug, Deserialize)]
+struct VersionList {
+    versions: Vec<String>,
+}
+
+impl DotnetToolBackend {
+    pub fn new() -> Result<Self> {
+        if !command_exists("dotnet") {
+            anyhow::bail!("dotnet is not available on this system");
+        }
+
+        let client = reqwest::Client::builder()
+            .user_agent("zap/0.1.0")
+            .build()
+            .context("Failed to create HTTP client")?;
+
+        Ok(Self {
+            client,
+            index: OnceCell::new(),
+        })
+    }
+
+    /// Where `dotnet tool install -g` puts tool commands
+    pub(crate) fn tools_dir() -> Option<PathBuf> {
+        dirs::home_dir().map(|home| home.join(".dotnet").join("tools"))
+    }
+
+    /// URL of a NuGet API, looked up in the service index as NuGet asks
+    /// clients to, rather than hardcoded
+    async fn resource(&self, kind: &str) -> Result<String> {
+        let index = self
+            .index
+            .get_or_try_init(|| async {
+                self.client
+                    .get(NUGET_INDEX_URL)
+                    .send()
+                    .await
+                    .context("Failed to fetch the NuGet service index")?
+                    .json::<ServiceIndex>()
+                    .await
+                    .context("Failed to parse the NuGet service index")
+            })
+            .await?;
+
+        index
+            .resources
+            .iter()
+            .find(|r| r.kind == kind)
+            .map(|r| r.id.trim_end_matches('/').to_string())
+            .with_context(|| format!("NuGet has no {} resource", kind))
+    }
+
+    async fn search_tools(&self, query: &str) -> Result<Vec<Package>> {
+        let url = self.resource("SearchQueryService").await?;
+        let response: SearchResponse = self
+            .client
+            .get(&url)
+            .query(&[
+                ("q", query),
+                ("packageType", "DotnetTool"),
+                ("take", "30"),
+                ("semVerLevel", "2.0.0"),
+            ])
+            .send()
+            .await
+            .context("Failed to search NuGet")?
+            .json()
+            .await
+            .context("Failed to parse NuGet response")?;
+
+        let installed = self.installed_ids();
+        Ok(response
+            .data
+            .into_iter()
+            .map(|result| {
+                let mut pkg = search_result_to_package(result);
+                pkg.installed = installed.contains(&pkg.name.to_lowercase());
+                pkg
+            })
+            .collect())
+    }
+
+    async fn latest_version(&self, id: &str) -> Result<Option<String>> {
+        let base = self.resource("PackageBaseAddress/3.0.0").await?;
+        let response = self
+            .client
+            .get(format!("{}/{}/index.json", base, id.to_lowercase()))
+            .send()
+            .await
+            .context("Failed to fetch NuGet versions")?;
+        if !response.status().is_success() {
+            return Ok(None);
+        }
+
+        let list: VersionList = response
+            .json()
+            .await
+            .context("Failed to parse NuGet versions")?;
+        Ok(newest_stable(list.versions))
+    }
+
+    fn installed_ids(&self) -> Vec<String> {
+        self.list_installed()
+            .map(|installed| installed.into_iter().map(|(id, _)| id).collect())
+            .unwrap_or_default()
+    }
+
+    fn run_tool(&self, action: &str, packages: &[&str]) -> Result<Vec<InstallResult>> {
+        let mut results = vec![];
+
+        // `dotnet tool` takes one package at a time
+        for package in packages {
+            let status = Command::new("dotnet")
+                .args(["tool", action, "-g", package])
+                .stdin(Stdio::inherit())
+                .stdout(Stdio::inherit())
+                .stderr(Stdio::inherit())
+                .status()
+                .with_context(|| format!("Failed to run dotnet tool {}", action))?;
+
+            results.push(InstallResult {
+                package: package.to_string(),
+                success: status.success(),
+                message: if status.success() {
+                    None
+                } else {
+                    Some(format!("dotnet tool {} failed", action))
+                },
+            });
+        }
+
+        Ok(results)
+    }
+}
+
+#[async_trait]
+impl PackageManager for DotnetToolBackend {
+    fn name(&self) -> &str {
+        ".NET tools"
+    }
+
+    fn id(&self) -> &str {
+        "dotnet"
+    }
+
+    async fn search(&self, query: &str) -> Result<Vec<Package>> {
+        if query.len() < 2 {
+            return Ok(vec![]);
+        }
+
+        let mut packages = self.search_tools(query).await?;
+        packages.truncate(30);
+        Ok(packages)
+    }
+
+    async fn info(&self, packages: &[&str]) -> Result<Vec<Package>> {
+        let mut results = vec![];
+
+        for pkg_name in packages {
+            let found = self
+                .search_tools(&format!("packageid:{}", pkg_name))
+                .await?;
+            results.extend(
+                found
+                    .into_iter()
+                    .filter(|pkg| pkg.name.eq_ignore_ascii_case(pkg_name)),
+            );
+        }
+
+        Ok(results)
+    }
+
+    async fn install(&self, packages: &[Package]) -> Result<Vec<InstallResult>> {
+        let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+        if !names.is_empty() {
+            println!("--> Installing packages with dotnet tool install -g...");
+        }
+        self.run_tool("install", &names)
+    }
+
+    async fn remove(&self, packages: &[&str]) -> Result<Vec<InstallResult>> {
+        if !packages.is_empty() {
+            println!("--> Removing packages with dotnet tool uninstall -g...");
+        }
+        self.run_tool("uninstall", packages)
+    }
+
+    async fn update(&self, packages: &[Package]) -> Result<Vec<InstallResult>> {
+        let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+        self.run_tool("update", &names)
+    }
+
+    fn is_installed(&self, package: &str) -> Result<bool> {
+        Ok(self.installed_ids().contains(&package.to_lowercase()))
+    }
+
+    fn list_installed(&self) -> Result<Vec<(String, String)>> {
+        let output = Command::new("dotnet")
+            .args(["tool", "list", "-g"])
+            .output()
+            .context("Failed to run dotnet tool list")?;
+
+        if !output.status.success() {
+            return Ok(vec![]);
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(parse_tool_list(&stdout))
+    }
+
+    async fn check_updates(&self) -> Result<Vec<Package>> {
+        let installed = self.list_installed()?;
+        let latest =
+            futures::future::join_all(installed.iter().map(|(id, _)| self.latest_version(id)))
+                .await;
+
+        Ok(installed
+            .into_iter()
+            .zip(latest)
+            .filter_map(|((id, version), latest)| {
+                let latest = latest.ok().flatten()?;
+                if compare_versions(&latest, &version) != Ordering::Greater {
+                    return None;
+                }
+                let mut pkg = Package::new(id, latest);
+                pkg.installed = true;
+                Some(pkg)
+            })
+            .collect())
+    }
+}
+
+fn search_result_to_package(result: SearchResult) -> Package {
+    let mut pkg = Package::new(result.id, result.version).with_description(result.description);
+    pkg.popularity = download_popularity(result.total_downloads);
+    pkg.maintainer = Some(result.authors.join(", ")).filter(|a| !a.is_empty());
+    pkg.url = result
+        .project_url
+        .filter(|u| !u.is_empty())
+        .or_else(|| Some(format!("https://www.nuget.org/packages/{}", pkg.name)));
+    pkg.extra.downloads = Some(result.total_downloads);
+    pkg.extra.keywords = result.tags;
+    pkg
+}
+
+/// The highest version without a pre-release suffix
+fn newest_stable(versions: Vec<String>) -> Option<String> {
+    versions
+        .into_iter()
+        .filter(|v| !v.contains('-'))
+        .max_by(|a, b| compare_versions(a, b))
+}
+
+/// Parse `dotnet tool list -g`: a "Package Id  Version  Commands" table under
+/// a dashed line. Ids are printed in lowercase.
+fn parse_tool_list(output: &str) -> Vec<(String, String)> {
+    output
+        .lines()
+        .skip_while(|line| !line.starts_with("---"))
+        .skip(1)
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            Some((fields.next()?.to_string(), fields.next()?.to_string()))
+        })
+        .collect()
+}
+
+fn command_exists(cmd: &str) -> bool {
+    Command::new("which")
+        .arg(cmd)
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tool_list() {
+        let output = "Package Id      Version      Commands\n\
+                      -------------------------------------\n\
+                      csharpier       0.28.2       dotnet-csharpier\n\
+                      dotnet-ef       8.0.8        dotnet-ef\n";
+        assert_eq!(
+            parse_tool_list(output),
+            [
+                ("csharpier".to_string(), "0.28.2".to_string()),
+                ("dotnet-ef".to_string(), "8.0.8".to_string()),
+            ]
+        );
+        assert!(parse_tool_list("Package Id      Version      Commands\n---------\n").is_empty());
+    }
+
+    #[test]
+    fn test_newest_stable() {
+        let versions = ["8.0.7", "8.0.8", "9.0.0-rc.1.24452.1", "10.0.0-preview.1"]
+            .map(str::to_string)
+            .to_vec();
+        assert_eq!(newest_stable(versions).as_deref(), Some("8.0.8"));
+    }
+
+    #[test]
+    fn test_search_result_to_package() {
+        let json = r#"{
+            "totalHits": 1,
+            "data": [{
+                "@id": "https://api.nuget.org/v3/registration5-semver1/dotnet-ef/index.json",
+                "@type": "Package",
+                "registration": "https://api.nuget.org/v3/registration5-semver1/dotnet-ef/index.json",
+                "id": "dotnet-ef",
+                "version": "8.0.8",
+                "description": "Entity Framework Core Tools for the .NET Command-Line Interface.",
+                "summary": "",
+                "title": "dotnet-ef",
+                "projectUrl": "https://docs.microsoft.com/ef/core/",
+                "tags": ["Entity", "Framework", "Core", "entity-framework-core", "EF"],
+                "authors": ["Microsoft"],
+                "owners": ["aspnet", "EntityFramework", "Microsoft"],
+                "totalDownloads": 74212315,
+                "verified": true,
+                "packageTypes": [{"name": "DotnetTool"}]
+            }]
+        }"#;
+        let response: SearchResponse = serde_json::from_str(json).unwrap();
+        let pkg = search_result_to_package(response.data.into_iter().next().unwrap());
+        assert_eq!(pkg.name, "dotnet-ef");
+        assert_eq!(pkg.version, "8.0.8");
+        assert_eq!(pkg.maintainer.as_deref(), Some("Microsoft"));
+        assert_eq!(
+            pkg.url.as_deref(),
+            Some("https://docs.microsoft.com/ef/core/")
+        );
+        assert_eq!(pkg.extra.downloads, Some(74212315));
+        assert!(pkg.popularity > 70.0);
+    }
+}
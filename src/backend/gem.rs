@@ -0,0 +1,463 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use super::{download_popularity, InstallResult, Package, PackageManager};
+
+const DEFAULT_HOST: &str = "https://rubygems.org";
+
+/// RubyGems API host, e.g. a mirror or a private gem server
+fn api_host() -> String {
+    std::env::var("ZAP_GEM_HOST")
+        .ok()
+        .filter(|host| !host.is_empty())
+        .map(|host| host.trim_end_matches('/').to_string())
+        .unwrap_or_else(|| DEFAULT_HOST.to_string())
+}
+
+/// Where `gem` comes from, which decides how gems are installed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RubyManager {
+    /// The system Ruby: gems go to the user's gem directory
+    System,
+    Rbenv,
+    Asdf,
+    Mise,
+    /// rvm and chruby switch GEM_HOME in the shell, so plain `gem` is right
+    Shell,
+}
+
+impl RubyManager {
+    fn detect() -> Self {
+        let gem = which("gem").unwrap_or_default();
+        let gem = gem.to_string_lossy();
+
+        if gem.contains("/.rbenv/") || std::env::var_os("RBENV_VERSION").is_some() {
+            Self::Rbenv
+        } else if gem.contains("/.asdf/") {
+            Self::Asdf
+        } else if gem.contains("/mise/") {
+            Self::Mise
+        } else if gem.contains("/.rvm/")
+            || gem.contains("/.rubies/")
+            || std::env::var_os("RUBY_ROOT").is_some()
+        {
+            Self::Shell
+        } else {
+            Self::System
+        }
+    }
+
+    /// `gem`, run through the version manager so it picks the selected Ruby
+    fn gem(self) -> Command {
+        match self {
+            Self::Rbenv => {
+                let mut command = Command::new("rbenv");
+                command.args(["exec", "gem"]);
+                command
+            }
+            Self::Asdf => {
+                let mut command = Command::new("asdf");
+                command.args(["exec", "gem"]);
+                command
+            }
+            Self::Mise => {
+                let mut command = Command::new("mise");
+                command.args(["exec", "--", "gem"]);
+                command
+            }
+            Self::System | Self::Shell => Command::new("gem"),
+        }
+    }
+
+    /// Regenerate the shims of managers that need it after new executables appear
+    fn rehash(self) {
+        let command = match self {
+            Self::Rbenv => Some(("rbenv", vec!["rehash"])),
+            Self::Asdf => Some(("asdf", vec!["reshim", "ruby"])),
+            Self::Mise => Some(("mise", vec!["reshim"])),
+            Self::System | Self::Shell => None,
+        };
+        if let Some((program, args)) = command {
+            let _ = Command::new(program)
+                .args(args)
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status();
+        }
+    }
+}
+
+/// RubyGems backend
+pub struct GemBackend {
+    client: reqwest::Client,
+    host: String,
+    manager: RubyManager,
+}
+
+/// A gem as returned by `/api/v1/search.json` and `/api/v1/gems/<name>.json`
+#[derive(Debug, Deserialize)]
+struct GemInfo {
+    name: String,
+    version: String,
+    info: Option<String>,
+    authors: Option<String>,
+    #[serde(default)]
+    downloads: u64,
+    #[serde(default)]
+    licenses: Option<Vec<String>>,
+    homepage_uri: Option<String>,
+    project_uri: Option<String>,
+    #[serde(default)]
+    dependencies: Dependencies,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Dependencies {
+    #[serde(default)]
+    runtime: Vec<Dependency>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Dependency {
+    name: String,
+    requirements: String,
+}
+
+impl GemBackend {
+    pub fn new() -> Result<Self> {
+        if which("gem").is_none() {
+            anyhow::bail!("gem is not available on this system. Install Ruby to use this backend.");
+        }
+
+        let client = reqwest::Client::builder()
+            .user_agent("zap/0.1.0")
+            .build()
+            .context("Failed to create HTTP client")?;
+
+        Ok(Self {
+            client,
+            host: api_host(),
+            manager: RubyManager::detect(),
+        })
+    }
+
+    /// Gem executables of the system Ruby go to the user gem directory
+    pub(crate) fn user_bin_dir() -> Option<PathBuf> {
+        let output = Command::new("ruby")
+            .args(["-e", "print Gem.user_dir"])
+            .output()
+            .ok()?;
+        let dir = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        (output.status.success() && !dir.is_empty()).then(|| PathBuf::from(dir).join("bin"))
+    }
+
+    fn installed_versions(&self) -> HashMap<String, String> {
+        self.list_installed()
+            .map(|installed| installed.into_iter().collect())
+            .unwrap_or_default()
+    }
+
+    /// Run a gem command with one result per package
+    fn run_gem(&self, args: &[&str], packages: &[&str]) -> Result<Vec<InstallResult>> {
+        if packages.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let status = self
+            .manager
+            .gem()
+            .args(args)
+            .args(packages)
+            .stdin(Stdio::inherit())
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::inherit())
+            .status()
+            .with_context(|| format!("Failed to run gem {}", args[0]))?;
+        self.manager.rehash();
+
+        let success = status.success();
+        Ok(packages
+            .iter()
+            .map(|name| InstallResult {
+                package: name.to_string(),
+                success,
+                message: if success {
+                    None
+                } else {
+                    Some(format!("gem {} failed", args[0]))
+                },
+            })
+            .collect())
+    }
+
+    /// `install` or `update` arguments, into the user gem directory for the system Ruby
+    fn install_args<'a>(&self, action: &'a str) -> Vec<&'a str> {
+        let mut args = vec![action, "--no-document"];
+        if self.manager == RubyManager::System {
+            args.push("--user-install");
+        }
+        args
+    }
+}
+
+#[async_trait]
+impl PackageManager for GemBackend {
+    fn name(&self) -> &str {
+        "RubyGems"
+    }
+
+    fn id(&self) -> &str {
+        "gem"
+    }
+
+    async fn search(&self, query: &str) -> Result<Vec<Package>> {
+        if query.len() < 2 {
+            return Ok(vec![]);
+        }
+
+        let gems: Vec<GemInfo> = self
+            .client
+            .get(format!("{}/api/v1/search.json", self.host))
+            .query(&[("query", query)])
+            .send()
+            .await
+            .context("Failed to search RubyGems")?
+            .error_for_status()
+            .context("Failed to search RubyGems")?
+            .json()
+            .await
+            .context("Failed to parse RubyGems response")?;
+
+        let installed = self.installed_versions();
+        let mut packages: Vec<Package> = gems
+            .into_iter()
+            .map(|gem| {
+                let mut pkg = gem_to_package(gem);
+                pkg.installed = installed.contains_key(&pkg.name);
+                pkg
+            })
+            .collect();
+        packages.truncate(30);
+
+        Ok(packages)
+    }
+
+    async fn info(&self, packages: &[&str]) -> Result<Vec<Package>> {
+        let installed = self.installed_versions();
+        let mut results = vec![];
+
+        for pkg_name in packages {
+            let response = self
+                .client
+                .get(format!("{}/api/v1/gems/{}.json", self.host, pkg_name))
+                .send()
+                .await
+                .context("Failed to fetch gem info")?;
+
+            if !response.status().is_success() {
+                continue;
+            }
+            let gem: GemInfo = response
+                .json()
+                .await
+                .context("Failed to parse RubyGems response")?;
+            let mut pkg = gem_to_package(gem);
+            pkg.installed = installed.contains_key(&pkg.name);
+            results.push(pkg);
+        }
+
+        Ok(results)
+    }
+
+    async fn install(&self, packages: &[Package]) -> Result<Vec<InstallResult>> {
+        let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+        if !names.is_empty() {
+            println!("--> Installing packages with gem...");
+        }
+        self.run_gem(&self.install_args("install"), &names)
+    }
+
+    async fn remove(&self, packages: &[&str]) -> Result<Vec<InstallResult>> {
+        if !packages.is_empty() {
+            println!("--> Removing packages with gem...");
+        }
+        // All versions, and their executables, without asking about each
+        self.run_gem(&["uninstall", "--all", "--executables"], packages)
+    }
+
+    async fn update(&self, packages: &[Package]) -> Result<Vec<InstallResult>> {
+        let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+        self.run_gem(&self.install_args("update"), &names)
+    }
+
+    fn is_installed(&self, package: &str) -> Result<bool> {
+        let status = self
+            .manager
+            .gem()
+            .args(["list", "--local", "--exact", "--installed", package])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()?;
+
+        Ok(status.success())
+    }
+
+    fn list_installed(&self) -> Result<Vec<(String, String)>> {
+        let output = self.manager.gem().args(["list", "--local"]).output()?;
+
+        if !output.status.success() {
+            return Ok(vec![]);
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(parse_gem_list(&stdout))
+    }
+
+    async fn check_updates(&self) -> Result<Vec<Package>> {
+        let output = self
+            .manager
+            .gem()
+            .arg("outdated")
+            .output()
+            .context("Failed to check for updates")?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(parse_outdated(&stdout))
+    }
+}
+
+fn gem_to_package(gem: GemInfo) -> Package {
+    let mut pkg = Package::new(gem.name, gem.version).with_description(gem.info);
+    pkg.popularity = download_popularity(gem.downloads);
+    pkg.maintainer = gem.authors;
+    pkg.url = gem
+        .homepage_uri
+        .filter(|u| !u.is_empty())
+        .or(gem.project_uri);
+    pkg.extra.downloads = Some(gem.downloads);
+    pkg.extra.license = gem.licenses.unwrap_or_default();
+    pkg.extra.depends = gem
+        .dependencies
+        .runtime
+        .into_iter()
+        .map(|dep| format!("{} {}", dep.name, dep.requirements))
+        .collect();
+    pkg
+}
+
+/// Parse `gem list --local`: "rake (13.2.1, 13.0.6)", newest version first,
+/// with bundled gems shown as "bundler (default: 2.5.11)"
+fn parse_gem_list(output: &str) -> Vec<(String, String)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (name, versions) = line.trim().split_once(" (")?;
+            let newest = versions.trim_end_matches(')').split(", ").next()?;
+            let newest = newest.trim_start_matches("default: ");
+            Some((name.to_string(), newest.to_string()))
+        })
+        .collect()
+}
+
+/// Parse `gem outdated`: "rubocop (1.60.0 < 1.65.1)"
+fn parse_outdated(output: &str) -> Vec<Package> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (name, versions) = line.trim().split_once(" (")?;
+            let (_, latest) = versions.trim_end_matches(')').split_once(" < ")?;
+            let mut pkg = Package::new(name, latest);
+            pkg.installed = true;
+            Some(pkg)
+        })
+        .collect()
+}
+
+fn which(cmd: &str) -> Option<PathBuf> {
+    let output = Command::new("which").arg(cmd).output().ok()?;
+    let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !path.is_empty()).then(|| PathBuf::from(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_gem_list() {
+        let output = "\n*** LOCAL GEMS ***\n\n\
+                      bundler (default: 2.5.11)\n\
+                      rake (13.2.1, 13.0.6)\n\
+                      rubocop (1.65.1)\n";
+        let gems = parse_gem_list(output);
+        assert_eq!(
+            gems,
+            [
+                ("bundler".to_string(), "2.5.11".to_string()),
+                ("rake".to_string(), "13.2.1".to_string()),
+                ("rubocop".to_string(), "1.65.1".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_outdated() {
+        let output = "fpm (1.15.0 < 1.15.1)\nrubocop (1.60.0 < 1.65.1)\n";
+        let updates = parse_outdated(output);
+        assert_eq!(updates.len(), 2);
+        assert_eq!(updates[1].name, "rubocop");
+        assert_eq!(updates[1].version, "1.65.1");
+        assert!(updates[1].installed);
+    }
+
+    #[test]
+    fn test_gem_to_package() {
+        let json = r#"{
+            "name": "fpm",
+            "downloads": 25481634,
+            "version": "1.15.1",
+            "authors": "Jordan Sissel",
+            "info": "Convert directories, rpms, python eggs, rubygems, and more to rpms, debs, solaris packages and more. Win at package management without wasting pointless hours debugging bad rpm specs!",
+            "licenses": ["MIT"],
+            "project_uri": "https://rubygems.org/gems/fpm",
+            "homepage_uri": "https://github.com/jordansissel/fpm",
+            "dependencies": {
+                "development": [{"name": "rspec", "requirements": "~> 3.0.0"}],
+                "runtime": [
+                    {"name": "arr-pm", "requirements": "~> 0.0.11"},
+                    {"name": "clamp", "requirements": "~> 1.0.0"}
+                ]
+            }
+        }"#;
+        let pkg = gem_to_package(serde_json::from_str(json).unwrap());
+        assert_eq!(pkg.name, "fpm");
+        assert_eq!(pkg.version, "1.15.1");
+        assert_eq!(pkg.extra.license, ["MIT"]);
+        assert_eq!(pkg.extra.depends, ["arr-pm ~> 0.0.11", "clamp ~> 1.0.0"]);
+        assert_eq!(pkg.maintainer.as_deref(), Some("Jordan Sissel"));
+        assert_eq!(
+            pkg.url.as_deref(),
+            Some("https://github.com/jordansissel/fpm")
+        );
+        assert!(pkg.popularity > 70.0 && pkg.popularity <= 100.0);
+    }
+
+    #[test]
+    fn test_search_result_without_licenses() {
+        // Search results carry no dependencies, and licenses may be null
+        let json = r#"[{"name": "bundler", "downloads": 1, "version": "2.5.16",
+                        "info": "Bundler manages an application's dependencies", "licenses": null,
+                        "project_uri": "https://rubygems.org/gems/bundler", "homepage_uri": ""}]"#;
+        let gems: Vec<GemInfo> = serde_json::from_str(json).unwrap();
+        let pkg = gem_to_package(gems.into_iter().next().unwrap());
+        assert!(pkg.extra.license.is_empty());
+        assert_eq!(
+            pkg.url.as_deref(),
+            Some("https://rubygems.org/gems/bundler")
+        );
+    }
+}
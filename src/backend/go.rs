@@ -1,6 +1,7 @@
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use serde::Deserialize;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 
 use super::{InstallResult, Package, PackageExtra, PackageManager};
@@ -8,6 +9,16 @@ use super::{InstallResult, Package, PackageExtra, PackageManager};
 /// Go package manager backend (go install)
 pub struct GoBackend {
     client: reqwest::Client,
+    proxy: ModuleProxy,
+}
+
+/// Where module versions are looked up, from GOPROXY and GONOPROXY/GOPRIVATE
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ModuleProxy {
+    /// The proxy URL, or `None` for GOPROXY=off or direct
+    url: Option<String>,
+    /// Module path patterns that must not go through the proxy
+    private: Vec<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -25,6 +36,19 @@ struct PkgGoDevResult {
     synopsis: Option<String>,
 }
 
+/// A binary built by `go install`, as reported by `go version -m`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GoBinary {
+    pub binary: String,
+    /// Package path of the main package, e.g. golang.org/x/tools/gopls
+    pub path: String,
+    /// Module containing it, e.g. golang.org/x/tools/gopls
+    pub module: String,
+    pub version: String,
+}
+
+const DEFAULT_PROXY: &str = "https://proxy.golang.org";
+
 impl GoBackend {
     pub fn new() -> Result<Self> {
         if !command_exists("go") {
@@ -36,7 +60,10 @@ impl GoBackend {
             .build()
             .context("Failed to create HTTP client")?;
 
-        Ok(Self { client })
+        Ok(Self {
+            client,
+            proxy: ModuleProxy::from_go_env(),
+        })
     }
 
     async fn search_pkg_go_dev(&self, query: &str) -> Result<Vec<Package>> {
@@ -44,75 +71,94 @@ impl GoBackend {
         // For now, we'll assume the query is a module path or search for it
 
         // Try to get module info directly
-        let url = format!("https://proxy.golang.org/{}/@latest", query);
-
-        if let Ok(response) = self.client.get(&url).send().await {
-            if response.status().is_success() {
-                if let Ok(text) = response.text().await {
-                    // Parse module info (JSON format)
-                    if let Ok(info) = serde_json::from_str::<serde_json::Value>(&text) {
-                        if let Some(version) = info.get("Version").and_then(|v| v.as_str()) {
-                            return Ok(vec![Package {
-                                name: query.to_string(),
-                                version: version.to_string(),
-                                description: None,
-                                popularity: 0.0,
-                                installed: self.is_installed(query).unwrap_or(false),
-                                maintainer: None,
-                                url: Some(format!("https://pkg.go.dev/{}", query)),
-                                extra: PackageExtra::default(),
-                            }]);
-                        }
-                    }
-                }
-            }
+        if let Ok(version) = self.latest_version(query).await {
+            return Ok(vec![Package {
+                name: query.to_string(),
+                version,
+                description: None,
+                popularity: 0.0,
+                installed: self.is_installed(query).unwrap_or(false),
+                maintainer: None,
+                url: Some(format!("https://pkg.go.dev/{}", query)),
+                extra: PackageExtra::default(),
+            }]);
         }
 
         // If direct lookup fails, return empty (no public search API)
         Ok(vec![])
     }
 
-    fn find_installed_binaries() -> Vec<(String, String)> {
-        let mut binaries = vec![];
+    /// Directories go install writes to: GOBIN, GOPATH/bin and ~/go/bin
+    pub(crate) fn bin_dirs() -> Vec<PathBuf> {
+        let mut dirs = vec![];
 
-        // Check GOBIN first, then GOPATH/bin
         if let Ok(gobin) = std::env::var("GOBIN") {
-            if let Ok(entries) = std::fs::read_dir(&gobin) {
-                for entry in entries.filter_map(|e| e.ok()) {
-                    let name = entry.file_name().to_string_lossy().to_string();
-                    binaries.push((name, "installed".to_string()));
-                }
-            }
+            dirs.push(PathBuf::from(gobin));
         }
 
-        // Check GOPATH/bin
+        // Binaries go to the first GOPATH entry
         if let Ok(gopath) = std::env::var("GOPATH") {
-            let bin_path = std::path::Path::new(&gopath).join("bin");
-            if let Ok(entries) = std::fs::read_dir(&bin_path) {
-                for entry in entries.filter_map(|e| e.ok()) {
-                    let name = entry.file_name().to_string_lossy().to_string();
-                    if !binaries.iter().any(|(n, _)| n == &name) {
-                        binaries.push((name, "installed".to_string()));
-                    }
-                }
+            if let Some(first) = std::env::split_paths(&gopath).next() {
+                dirs.push(first.join("bin"));
             }
         }
 
-        // Fallback: check ~/go/bin
         if let Ok(home) = std::env::var("HOME") {
-            let bin_path = std::path::Path::new(&home).join("go").join("bin");
-            if let Ok(entries) = std::fs::read_dir(&bin_path) {
-                for entry in entries.filter_map(|e| e.ok()) {
-                    let name = entry.file_name().to_string_lossy().to_string();
-                    if !binaries.iter().any(|(n, _)| n == &name) {
-                        binaries.push((name, "installed".to_string()));
-                    }
+            dirs.push(PathBuf::from(home).join("go").join("bin"));
+        }
+
+        dirs.dedup();
+        dirs.into_iter().filter(|d| d.is_dir()).collect()
+    }
+
+    /// Read the module path and version Go embeds in each installed binary
+    pub fn find_installed_binaries() -> Vec<GoBinary> {
+        let mut binaries: Vec<GoBinary> = vec![];
+
+        for dir in Self::bin_dirs() {
+            let output = match Command::new("go")
+                .arg("version")
+                .arg("-m")
+                .arg(&dir)
+                .output()
+            {
+                Ok(output) => output,
+                Err(_) => continue,
+            };
+
+            for binary in parse_version_m(&String::from_utf8_lossy(&output.stdout)) {
+                if !binaries.iter().any(|b| b.binary == binary.binary) {
+                    binaries.push(binary);
                 }
             }
         }
 
         binaries
     }
+
+    /// Latest version of a module according to the module proxy
+    async fn latest_version(&self, module: &str) -> Result<String> {
+        let Some(proxy) = self.proxy.url_for(module) else {
+            anyhow::bail!("{} is not looked up through a module proxy", module);
+        };
+        let url = format!("{}/{}/@latest", proxy, escape_module_path(module));
+        let info: serde_json::Value = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .with_context(|| format!("Failed to query {}", url))?
+            .error_for_status()
+            .with_context(|| format!("Module proxy has no {}", module))?
+            .json()
+            .await
+            .context("Failed to parse module proxy response")?;
+
+        info.get("Version")
+            .and_then(|v| v.as_str())
+            .map(|v| v.to_string())
+            .ok_or_else(|| anyhow::anyhow!("Module proxy returned no version for {}", module))
+    }
 }
 
 #[async_trait]
@@ -138,30 +184,28 @@ impl PackageManager for GoBackend {
         let mut results = vec![];
 
         for pkg_path in packages {
-            // Try to get module info from Go proxy
-            let url = format!("https://proxy.golang.org/{}/@latest", pkg_path);
-
-            if let Ok(response) = self.client.get(&url).send().await {
-                if response.status().is_success() {
-                    if let Ok(text) = response.text().await {
-                        if let Ok(info) = serde_json::from_str::<serde_json::Value>(&text) {
-                            if let Some(version) = info.get("Version").and_then(|v| v.as_str()) {
-                                let pkg = Package {
-                                    name: pkg_path.to_string(),
-                                    version: version.to_string(),
-                                    description: None,
-                                    popularity: 0.0,
-                                    installed: self.is_installed(pkg_path)?,
-                                    maintainer: None,
-                                    url: Some(format!("https://pkg.go.dev/{}", pkg_path)),
-                                    extra: PackageExtra::default(),
-                                };
-                                results.push(pkg);
-                            }
-                        }
-                    }
+            // The proxy only knows modules, so for a package inside one
+            // (golang.org/x/tools/cmd/stringer) its parent paths are tried in turn
+            let mut version = None;
+            for module in module_candidates(pkg_path) {
+                if let Ok(latest) = self.latest_version(module).await {
+                    version = Some(latest);
+                    break;
                 }
             }
+            let Some(version) = version else {
+                continue;
+            };
+            results.push(Package {
+                name: pkg_path.to_string(),
+                version,
+                description: None,
+                popularity: 0.0,
+                installed: self.is_installed(pkg_path)?,
+                maintainer: None,
+                url: Some(format!("https://pkg.go.dev/{}", pkg_path)),
+                extra: PackageExtra::default(),
+            });
         }
 
         Ok(results)
@@ -205,23 +249,79 @@ impl PackageManager for GoBackend {
     }
 
     fn is_installed(&self, package: &str) -> Result<bool> {
-        // Extract binary name from package path
+        let package = package.split('@').next().unwrap_or(package);
         // e.g., github.com/user/tool -> tool
         let binary_name = package.rsplit('/').next().unwrap_or(package);
 
-        // Check if binary exists in GOBIN or GOPATH/bin
         let binaries = Self::find_installed_binaries();
-        Ok(binaries.iter().any(|(name, _)| name == binary_name))
+        Ok(binaries
+            .iter()
+            .any(|b| b.path == package || b.binary == package || b.binary == binary_name))
+    }
+
+    fn owns(&self, path: &Path) -> Result<Option<(String, String)>> {
+        // Other Go programs on the system (from distro packages, say) carry module info too
+        let in_bin_dir = path
+            .parent()
+            .and_then(|dir| dir.canonicalize().ok())
+            .is_some_and(|dir| {
+                Self::bin_dirs()
+                    .iter()
+                    .any(|d| d.canonicalize().is_ok_and(|d| d == dir))
+            });
+        if !in_bin_dir {
+            return Ok(None);
+        }
+
+        let output = Command::new("go")
+            .args(["version", "-m"])
+            .arg(path)
+            .output()
+            .context("Failed to run go version -m")?;
+
+        Ok(parse_version_m(&String::from_utf8_lossy(&output.stdout))
+            .into_iter()
+            .next()
+            .map(|binary| (binary.path, binary.version)))
     }
 
     fn list_installed(&self) -> Result<Vec<(String, String)>> {
-        Ok(Self::find_installed_binaries())
+        Ok(Self::find_installed_binaries()
+            .into_iter()
+            .map(|b| (b.path, b.version))
+            .collect())
     }
 
     async fn check_updates(&self) -> Result<Vec<Package>> {
-        // Go doesn't have a built-in way to check for updates
-        // Would need to track installed versions separately
-        Ok(vec![])
+        // Binaries built from a local checkout report "(devel)" and can't be compared
+        let binaries: Vec<GoBinary> = Self::find_installed_binaries()
+            .into_iter()
+            .filter(|b| b.version.starts_with('v'))
+            .collect();
+
+        let latest =
+            futures::future::join_all(binaries.iter().map(|b| self.latest_version(&b.module)))
+                .await;
+
+        let mut updates = vec![];
+        for (binary, latest) in binaries.iter().zip(latest) {
+            let latest = match latest {
+                Ok(v) => v,
+                Err(e) => {
+                    eprintln!("Warning: Could not check {}: {}", binary.path, e);
+                    continue;
+                }
+            };
+
+            if is_newer(&latest, &binary.version) {
+                let mut pkg = Package::new(&binary.path, latest);
+                pkg.installed = true;
+                pkg.url = Some(format!("https://pkg.go.dev/{}", binary.path));
+                updates.push(pkg);
+            }
+        }
+
+        Ok(updates)
     }
 
     async fn update(&self, packages: &[Package]) -> Result<Vec<InstallResult>> {
@@ -263,3 +363,285 @@ fn command_exists(cmd: &str) -> bool {
         .map(|o| o.status.success())
         .unwrap_or(false)
 }
+
+/// Parse `go version -m` output for one or more binaries:
+///
+/// ```text
+/// /home/user/go/bin/gopls: go1.22.0
+///         path    golang.org/x/tools/gopls
+///         mod     golang.org/x/tools/gopls        v0.15.0 h1:...
+/// ```
+fn parse_version_m(output: &str) -> Vec<GoBinary> {
+    let mut binaries = vec![];
+    let mut current: Option<GoBinary> = None;
+
+    for line in output.lines() {
+        if !line.starts_with(char::is_whitespace) {
+            binaries.extend(current.take());
+            // "<file>: go1.22.0"
+            if let Some((file, _)) = line.rsplit_once(": ") {
+                let binary = std::path::Path::new(file)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| file.to_string());
+                current = Some(GoBinary {
+                    binary,
+                    path: String::new(),
+                    module: String::new(),
+                    version: String::new(),
+                });
+            }
+            continue;
+        }
+
+        let Some(bin) = current.as_mut() else {
+            continue;
+        };
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        match fields.as_slice() {
+            ["path", path, ..] => bin.path = path.to_string(),
+            ["mod", module, version, ..] => {
+                bin.module = module.to_string();
+                bin.version = version.to_string();
+            }
+            _ => {}
+        }
+    }
+    binaries.extend(current);
+
+    // Skip files that aren't Go binaries or were built outside a module
+    binaries
+        .into_iter()
+        .filter(|b| !b.path.is_empty() && !b.module.is_empty())
+        .collect()
+}
+
+/// First proxy in GOPROXY that can be queried over HTTP(S), else proxy.golang.org
+impl ModuleProxy {
+    /// Read GOPROXY and GONOPROXY (which defaults to GOPRIVATE) once, from
+    /// `go env` so go's own defaults and config file apply
+    fn from_go_env() -> Self {
+        let output = Command::new("go")
+            .args(["env", "GOPROXY", "GONOPROXY"])
+            .output()
+            .ok()
+            .filter(|o| o.status.success());
+        match output {
+            Some(o) => {
+                let stdout = String::from_utf8_lossy(&o.stdout);
+                let mut lines = stdout.lines();
+                Self::parse(lines.next().unwrap_or(""), lines.next().unwrap_or(""))
+            }
+            None => {
+                let var = |name: &str| std::env::var(name).unwrap_or_default();
+                let noproxy = std::env::var("GONOPROXY").unwrap_or_else(|_| var("GOPRIVATE"));
+                Self::parse(&var("GOPROXY"), &noproxy)
+            }
+        }
+    }
+
+    fn parse(goproxy: &str, noproxy: &str) -> Self {
+        // The first entry is used; "off" and "direct" mean no proxy lookups
+        let url = match goproxy.split([',', '|']).map(|p| p.trim()).next() {
+            None | Some("") => Some(DEFAULT_PROXY.to_string()),
+            Some(p) if p.starts_with("http://") || p.starts_with("https://") => {
+                Some(p.trim_end_matches('/').to_string())
+            }
+            Some(_) => None,
+        };
+        let private = noproxy
+            .split(',')
+            .map(|p| p.trim())
+            .filter(|p| !p.is_empty())
+            .map(|p| p.to_string())
+            .collect();
+        Self { url, private }
+    }
+
+    /// The proxy to ask about `module`, unless it is private or proxies are off
+    fn url_for(&self, module: &str) -> Option<&str> {
+        if self
+            .private
+            .iter()
+            .any(|p| matches_module_pattern(p, module))
+        {
+            return None;
+        }
+        self.url.as_deref()
+    }
+}
+
+/// GONOPROXY/GOPRIVATE matching: a glob pattern matches a module path when it
+/// matches the same number of leading path elements
+fn matches_module_pattern(pattern: &str, module: &str) -> bool {
+    let pattern: Vec<&str> = pattern.trim_end_matches('/').split('/').collect();
+    let module: Vec<&str> = module.split('/').collect();
+    module.len() >= pattern.len()
+        && pattern
+            .iter()
+            .zip(&module)
+            .all(|(p, m)| glob_match(p.as_bytes(), m.as_bytes()))
+}
+
+/// `*` and `?` wildcards within one path element
+fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some((b'*', rest)) => (0..=text.len()).any(|i| glob_match(rest, &text[i..])),
+        Some((b'?', rest)) => !text.is_empty() && glob_match(rest, &text[1..]),
+        Some((c, rest)) => text.first() == Some(c) && glob_match(rest, &text[1..]),
+    }
+}
+
+/// Module proxy paths encode uppercase letters as '!' + lowercase
+fn escape_module_path(path: &str) -> String {
+    let mut escaped = String::with_capacity(path.len());
+    for c in path.chars() {
+        if c.is_ascii_uppercase() {
+            escaped.push('!');
+            escaped.push(c.to_ascii_lowercase());
+        } else {
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+/// `path` and its parents down to the host and first element, longest first
+fn module_candidates(path: &str) -> Vec<&str> {
+    let mut candidates = vec![path];
+    let mut current = path;
+    while let Some((parent, _)) = current.rsplit_once('/') {
+        if !parent.contains('/') {
+            break;
+        }
+        candidates.push(parent);
+        current = parent;
+    }
+    candidates
+}
+
+/// Compare Go module versions (vX.Y.Z, including pseudo-versions)
+fn is_newer(latest: &str, installed: &str) -> bool {
+    let parse = |v: &str| {
+        semver::Version::parse(
+            v.trim_start_matches('v')
+                .split("+incompatible")
+                .next()
+                .unwrap_or(v),
+        )
+        .ok()
+    };
+    match (parse(latest), parse(installed)) {
+        (Some(latest), Some(installed)) => latest > installed,
+        _ => latest != installed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version_m() {
+        let output = "/home/user/go/bin/gopls: go1.22.0\n\
+                      \tpath\tgolang.org/x/tools/gopls\n\
+                      \tmod\tgolang.org/x/tools/gopls\tv0.15.0\th1:4p2kqyA0iDMs0hXkvjNQAxY8pAnDTk1nkI7Ye9xnvwE=\n\
+                      \tdep\tgithub.com/BurntSushi/toml\tv1.3.2\th1:o7IhLm0Msx3BaB+n3Ag7L8EVlByGKpq14C8yWiu0O78=\n\
+                      \tbuild\t-buildmode=exe\n\
+                      \tbuild\tGOOS=linux\n\
+                      /home/user/go/bin/stringer: go1.21.6\n\
+                      \tpath\tgolang.org/x/tools/cmd/stringer\n\
+                      \tmod\tgolang.org/x/tools\tv0.17.0\th1:FvmRgNOcs3kOa+T20R1uhfP9F6HgG2mfxDv1vrx1Htc=\n\
+                      /home/user/go/bin/legacy: go1.10\n\
+                      \tpath\tlegacy\n";
+        let binaries = parse_version_m(output);
+        assert_eq!(binaries.len(), 2);
+
+        assert_eq!(binaries[0].binary, "gopls");
+        assert_eq!(binaries[0].path, "golang.org/x/tools/gopls");
+        assert_eq!(binaries[0].module, "golang.org/x/tools/gopls");
+        assert_eq!(binaries[0].version, "v0.15.0");
+
+        // A command in a subdirectory of its module
+        assert_eq!(binaries[1].binary, "stringer");
+        assert_eq!(binaries[1].path, "golang.org/x/tools/cmd/stringer");
+        assert_eq!(binaries[1].module, "golang.org/x/tools");
+        assert_eq!(binaries[1].version, "v0.17.0");
+    }
+
+    #[test]
+    fn test_escape_module_path() {
+        assert_eq!(
+            escape_module_path("github.com/BurntSushi/toml"),
+            "github.com/!burnt!sushi/toml"
+        );
+        assert_eq!(
+            escape_module_path("golang.org/x/tools/gopls"),
+            "golang.org/x/tools/gopls"
+        );
+    }
+
+    #[test]
+    fn test_module_proxy() {
+        let proxy = ModuleProxy::parse(
+            "https://goproxy.example.com/,direct",
+            "*.corp.example.com,github.com/acme",
+        );
+        assert_eq!(
+            proxy.url_for("golang.org/x/tools"),
+            Some("https://goproxy.example.com")
+        );
+        assert_eq!(proxy.url_for("git.corp.example.com/team/tool"), None);
+        assert_eq!(proxy.url_for("github.com/acme/secret"), None);
+        assert_eq!(
+            proxy.url_for("github.com/acmeco/tool"),
+            Some("https://goproxy.example.com")
+        );
+
+        assert_eq!(
+            ModuleProxy::parse("off", "").url_for("golang.org/x/tools"),
+            None
+        );
+        assert_eq!(
+            ModuleProxy::parse("direct", "").url_for("golang.org/x/tools"),
+            None
+        );
+        assert_eq!(
+            ModuleProxy::parse("", "").url_for("golang.org/x/tools"),
+            Some(DEFAULT_PROXY)
+        );
+    }
+
+    #[test]
+    fn test_module_candidates() {
+        assert_eq!(
+            module_candidates("golang.org/x/tools/cmd/stringer"),
+            [
+                "golang.org/x/tools/cmd/stringer",
+                "golang.org/x/tools/cmd",
+                "golang.org/x/tools",
+                "golang.org/x",
+            ]
+        );
+        assert_eq!(module_candidates("github.com/a"), ["github.com/a"]);
+    }
+
+    #[test]
+    fn test_is_newer() {
+        assert!(is_newer("v0.15.1", "v0.15.0"));
+        assert!(is_newer("v1.10.0", "v1.9.3"));
+        assert!(!is_newer("v0.15.0", "v0.15.0"));
+        assert!(!is_newer("v0.14.2", "v0.15.0"));
+        // A release is newer than the pre-release pseudo-version it follows
+        assert!(is_newer("v0.15.0", "v0.15.0-pre.1"));
+        assert!(is_newer("v0.15.0", "v0.14.3-0.20240102180000-abcdef123456"));
+        assert!(!is_newer(
+            "v0.14.2",
+            "v0.14.3-0.20240102180000-abcdef123456"
+        ));
+        assert!(is_newer("v2.0.1+incompatible", "v2.0.0+incompatible"));
+        // Versions that are not semver are compared for equality
+        assert!(is_newer("v0.15.0", "(devel)"));
+    }
+}
@@ -1,25 +1,35 @@
+pub mod apk;
 pub mod apt;
 pub mod aur;
 pub mod bootstrap;
 pub mod brew;
+pub mod cabal;
 pub mod cargo;
 pub mod choco;
+pub mod composer;
+pub mod conda;
 mod detect;
 pub mod deno;
 pub mod dnf;
 pub mod dockerhub;
+pub mod dotnet;
 pub mod flatpak;
+pub mod gem;
 pub mod go;
 pub mod multi;
+pub mod nix;
 pub mod npm;
+pub mod opam;
 pub mod pacman;
 pub mod pip;
 pub mod pkg;
+pub mod portage;
 pub mod r#pub;
 pub mod scoop;
 pub mod snap;
 pub mod sudo;
 pub mod winget;
+pub mod xbps;
 pub mod zsh;
 pub mod zypper;
 
@@ -27,6 +37,7 @@ use anyhow::Result;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::path::Path;
 
 pub use detect::{detect_available_package_managers, detect_system, System};
 
@@ -53,6 +64,14 @@ pub struct PackageExtra {
     pub aur_id: Option<u64>,
     pub aur_votes: Option<u32>,
     pub aur_url_path: Option<String>,
+    #[serde(default)]
+    pub aur_package_base: Option<String>,
+    #[serde(default)]
+    pub aur_first_submitted: Option<i64>,
+    #[serde(default)]
+    pub aur_last_modified: Option<i64>,
+    #[serde(default)]
+    pub aur_replaces: Vec<String>,
     pub out_of_date: Option<u64>,
 
     // APT specific
@@ -63,6 +82,11 @@ pub struct PackageExtra {
     pub brew_tap: Option<String>,
     pub brew_cask: Option<bool>,
 
+    // Portage specific
+    /// USE flags, disabled ones prefixed with "-"
+    #[serde(default)]
+    pub use_flags: Vec<String>,
+
     // Common
     #[serde(default)]
     pub depends: Vec<String>,
@@ -72,6 +96,21 @@ pub struct PackageExtra {
     pub keywords: Vec<String>,
     #[serde(default)]
     pub categories: Vec<String>,
+    /// `version` was asked for (a manifest pin) rather than just the latest
+    #[serde(default)]
+    pub pinned: bool,
+    /// A manifest requirement to hand the installer as written, e.g.
+    /// `requests[socks]>=2.28,<3`
+    #[serde(default)]
+    pub requirement: Option<String>,
+
+    // Registry statistics
+    /// When the package was first published (unix timestamp)
+    #[serde(default)]
+    pub created_at: Option<i64>,
+    /// Downloads reported by the registry (all time on crates.io, last year on npm)
+    #[serde(default)]
+    pub downloads: Option<u64>,
 }
 
 impl Package {
@@ -97,6 +136,19 @@ impl Package {
         self.popularity = pop;
         self
     }
+
+    /// What to hand an installer: the manifest requirement if there is one,
+    /// "name{separator}version" when the version is pinned, e.g.
+    /// "black==24.1.0", and the bare name otherwise
+    pub fn spec(&self, separator: &str) -> String {
+        if let Some(requirement) = &self.extra.requirement {
+            requirement.clone()
+        } else if self.extra.pinned {
+            format!("{}{}{}", self.name, separator, self.version)
+        } else {
+            self.name.clone()
+        }
+    }
 }
 
 /// Result of a package installation
@@ -125,6 +177,11 @@ pub trait PackageManager: Send + Sync {
     /// Install packages
     async fn install(&self, packages: &[Package]) -> Result<Vec<InstallResult>>;
 
+    /// Uninstall packages by name
+    async fn remove(&self, _packages: &[&str]) -> Result<Vec<InstallResult>> {
+        anyhow::bail!("Removing packages is not supported by {}", self.name())
+    }
+
     /// Check if a package is installed
     fn is_installed(&self, package: &str) -> Result<bool>;
 
@@ -139,6 +196,71 @@ pub trait PackageManager: Send + Sync {
 
     /// Check which installed packages have updates available
     async fn check_updates(&self) -> Result<Vec<Package>>;
+
+    /// Find packages that provide a command (e.g. "rg") or a file path
+    /// (e.g. "/usr/bin/rg"). Backends without a file index find nothing.
+    async fn provides(&self, _target: &str) -> Result<Vec<Package>> {
+        Ok(vec![])
+    }
+
+    /// The installed package that put the file at `path` there, as (name, version)
+    fn owns(&self, _path: &Path) -> Result<Option<(String, String)>> {
+        Ok(None)
+    }
+
+    /// Download count for registries that report it apart from `info`
+    async fn downloads(&self, _package: &str) -> Option<u64> {
+        None
+    }
+}
+
+/// How many search results a registry backend checks for a binary in `provides`
+pub(crate) const MAX_PROVIDER_CANDIDATES: usize = 10;
+
+/// A registry download count as a popularity score (0-100), on a log scale
+pub(crate) fn download_popularity(downloads: u64) -> f64 {
+    ((downloads.max(1) as f64).log10() * 10.0).clamp(0.0, 100.0)
+}
+
+/// The command a `provides` lookup is for: the target itself, or the file
+/// name of a path inside a bin directory
+pub(crate) fn command_name(target: &str) -> Option<&str> {
+    match target.rsplit_once('/') {
+        None => Some(target),
+        Some((dir, name)) if dir.ends_with("bin") && !name.is_empty() => Some(name),
+        Some(_) => None,
+    }
+}
+
+/// Whether a file listed by a package index satisfies a `provides` lookup:
+/// the same path, or an executable of that name in a bin directory
+pub(crate) fn provides_match(path: &str, target: &str) -> bool {
+    let path = path.trim_start_matches('/');
+    if target.contains('/') {
+        return path == target.trim_start_matches('/');
+    }
+    match path.rsplit_once('/') {
+        Some((dir, name)) => name == target && dir.ends_with("bin"),
+        None => false,
+    }
+}
+
+/// Regular expression narrowing a file index search (apt-file, pkg provides)
+/// to candidates for `provides_match`
+pub(crate) fn provides_pattern(target: &str) -> String {
+    let escaped: String = target
+        .trim_start_matches('/')
+        .chars()
+        .flat_map(|c| {
+            let special = "\\.+*?()|[]{}^$".contains(c);
+            special.then_some('\\').into_iter().chain([c])
+        })
+        .collect();
+    if target.contains('/') {
+        format!("{}$", escaped)
+    } else {
+        format!("bin/{}$", escaped)
+    }
 }
 
 impl fmt::Display for Package {
@@ -151,6 +273,7 @@ impl fmt::Display for Package {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PackageManagerType {
     // Native system package managers
+    Apk,
     Apt,
     Aur,
     Brew,
@@ -161,19 +284,29 @@ pub enum PackageManagerType {
     Pacman,
     Pkg,
     Zypper,
+    Xbps,
+    Portage,
     // Universal package managers
     Flatpak,
     Snap,
+    Nix,
     // Language package managers
     Cargo,
     Go,
     Pip,
+    Conda,
+    Gem,
+    Composer,
+    Cabal,
+    Opam,
+    Dotnet,
     Npm,
 }
 
 impl PackageManagerType {
     pub fn name(&self) -> &'static str {
         match self {
+            Self::Apk => "apk",
             Self::Apt => "APT",
             Self::Aur => "AUR",
             Self::Brew => "Homebrew",
@@ -184,17 +317,27 @@ impl PackageManagerType {
             Self::Pacman => "pacman",
             Self::Pkg => "pkg",
             Self::Zypper => "zypper",
+            Self::Xbps => "XBPS",
+            Self::Portage => "Portage",
             Self::Flatpak => "Flatpak",
             Self::Snap => "Snap",
+            Self::Nix => "Nix",
             Self::Cargo => "Cargo",
             Self::Go => "Go",
             Self::Pip => "pip",
+            Self::Conda => "conda",
+            Self::Gem => "gem",
+            Self::Composer => "composer",
+            Self::Cabal => "cabal",
+            Self::Opam => "opam",
+            Self::Dotnet => ".NET tools",
             Self::Npm => "npm",
         }
     }
 
     pub fn id(&self) -> &'static str {
         match self {
+            Self::Apk => "apk",
             Self::Apt => "apt",
             Self::Aur => "aur",
             Self::Brew => "brew",
@@ -205,11 +348,20 @@ impl PackageManagerType {
             Self::Pacman => "pacman",
             Self::Pkg => "pkg",
             Self::Zypper => "zypper",
+            Self::Xbps => "xbps",
+            Self::Portage => "portage",
             Self::Flatpak => "flatpak",
             Self::Snap => "snap",
+            Self::Nix => "nix",
             Self::Cargo => "cargo",
             Self::Go => "go",
             Self::Pip => "pip",
+            Self::Conda => "conda",
+            Self::Gem => "gem",
+            Self::Composer => "composer",
+            Self::Cabal => "cabal",
+            Self::Opam => "opam",
+            Self::Dotnet => "dotnet",
             Self::Npm => "npm",
         }
     }
@@ -217,6 +369,7 @@ impl PackageManagerType {
     /// Get all system package manager types
     pub fn system_managers() -> &'static [Self] {
         &[
+            Self::Apk,
             Self::Apt,
             Self::Aur,
             Self::Brew,
@@ -227,16 +380,29 @@ impl PackageManagerType {
             Self::Pacman,
             Self::Pkg,
             Self::Zypper,
+            Self::Xbps,
+            Self::Portage,
         ]
     }
 
     /// Get all universal package manager types
     pub fn universal_managers() -> &'static [Self] {
-        &[Self::Flatpak, Self::Snap]
+        &[Self::Flatpak, Self::Snap, Self::Nix]
     }
 
     /// Get all language package manager types
     pub fn language_managers() -> &'static [Self] {
-        &[Self::Cargo, Self::Go, Self::Pip, Self::Npm]
+        &[
+            Self::Cargo,
+            Self::Go,
+            Self::Pip,
+            Self::Conda,
+            Self::Gem,
+            Self::Composer,
+            Self::Cabal,
+            Self::Opam,
+            Self::Dotnet,
+            Self::Npm,
+        ]
     }
 }
@@ -5,6 +5,11 @@ use std::sync::Arc;
 
 use super::{detect_available_package_managers, Package, PackageManager, InstallResult};
 
+/// Ids of the language package managers, tried after the system ones
+pub(crate) const LANGUAGE_BACKENDS: &[&str] = &[
+    "npm", "pip", "cargo", "go", "deno", "pub", "conda", "gem", "composer", "cabal", "opam", "dotnet",
+];
+
 /// Detected package type for a given package name
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PackageType {
@@ -18,6 +23,8 @@ pub enum PackageType {
     Cargo,
     /// Go package
     Go,
+    /// Composer package
+    Composer,
     /// Unknown - will try all backends
     Unknown,
 }
@@ -31,6 +38,10 @@ pub fn detect_package_type(name: &str) -> PackageType {
 
     // Check for npm packages with slashes (but not Go packages)
     if name.contains("/") && !name.starts_with("github.com/") && !name.starts_with("golang.org/") && !name.starts_with("gopkg.in/") && !name.starts_with("deno.land/") {
+        // Composer packages are always "vendor/package"
+        if super::composer::is_package_name(name) {
+            return PackageType::Composer;
+        }
         // Could be npm scoped package without @
         // But also could be other things, so we'll try npm first
         return PackageType::Npm;
@@ -59,12 +70,23 @@ pub struct MultiBackend {
 impl MultiBackend {
     /// Create a new multi-backend manager with all available package managers
     pub fn new() -> Result<Self> {
+        Self::init(true)
+    }
+
+    /// Like `new`, without progress output (for the command-not-found hook)
+    pub fn quiet() -> Result<Self> {
+        Self::init(false)
+    }
+
+    fn init(verbose: bool) -> Result<Self> {
         let available = detect_available_package_managers();
         let mut backends = Vec::new();
 
-        println!("--> Detected {} available package managers: {}", 
-                 available.len(), 
-                 available.join(", "));
+        if verbose {
+            println!("--> Detected {} available package managers: {}", 
+                     available.len(), 
+                     available.join(", "));
+        }
 
         // Try to create each available backend
         for backend_id in available {
@@ -72,9 +94,10 @@ impl MultiBackend {
                 Ok(backend) => {
                     backends.push((backend_id.to_string(), backend));
                 }
-                Err(e) => {
+                Err(e) if verbose => {
                     eprintln!("  --> Warning: Failed to initialize {} backend: {}", backend_id, e);
                 }
+                Err(_) => {}
             }
         }
 
@@ -82,7 +105,9 @@ impl MultiBackend {
             anyhow::bail!("No package managers could be initialized");
         }
 
-        println!("--> Initialized {} package managers", backends.len());
+        if verbose {
+            println!("--> Initialized {} package managers", backends.len());
+        }
         Ok(Self { backends })
     }
 
@@ -154,6 +179,143 @@ impl MultiBackend {
         Ok(results)
     }
 
+    /// Find the packages that provide a command or file, best matches first:
+    /// backends in install priority order, and within each, packages named
+    /// after the command. `system_only` skips the registry lookups of language
+    /// backends, which are too slow for a command-not-found hook.
+    pub async fn provides_all(&self, target: &str, system_only: bool) -> Vec<(String, Vec<Package>)> {
+        let order: Vec<&str> = self
+            .backends_for(&PackageType::Unknown)
+            .into_iter()
+            .filter(|id| !system_only || !LANGUAGE_BACKENDS.contains(id))
+            .collect();
+
+        let mut lookups = Vec::new();
+        for id in order {
+            if let Some(backend) = self.get_backend(id) {
+                lookups.push(async move { (id, backend.provides(target).await) });
+            }
+        }
+
+        let mut results = Vec::new();
+        for (id, result) in futures::future::join_all(lookups).await {
+            match result {
+                Ok(mut packages) if !packages.is_empty() => {
+                    let command = super::command_name(target).unwrap_or(target);
+                    packages.sort_by_key(|pkg| pkg.name != command);
+                    results.push((id.to_string(), packages));
+                }
+                Ok(_) => {}
+                Err(e) if !system_only => {
+                    eprintln!("  --> Warning: {} error: {}", id, e);
+                }
+                Err(_) => {}
+            }
+        }
+
+        results
+    }
+
+    /// Every backend that claims the file at `path`, as (backend id, package, version)
+    pub fn owns_all(&self, path: &std::path::Path) -> Vec<(String, String, String)> {
+        let mut owners = Vec::new();
+        for (id, backend) in &self.backends {
+            if let Ok(Some((name, version))) = backend.owns(path) {
+                owners.push((id.clone(), name, version));
+            }
+        }
+        owners
+    }
+
+    /// Backend IDs to try for a package type, in priority order
+    fn backends_for(&self, pkg_type: &PackageType) -> Vec<&str> {
+        match pkg_type {
+            PackageType::Npm => vec!["npm", "deno"], // Try npm first, then deno
+            PackageType::Pip => vec!["pip"],
+            PackageType::Cargo => vec!["cargo"],
+            PackageType::Go => vec!["go"],
+            PackageType::Composer => vec!["composer"],
+            PackageType::System => {
+                // For system packages, try all system backends
+                // Order matters: try native package managers first, then AUR/universal
+                // CRITICAL: pacman must come before AUR to avoid installing main repo packages via AUR
+                let priority_order = ["pacman", "apt", "dnf", "zypper", "apk", "xbps", "portage", "pkg", "brew", "winget", "scoop", "choco"];
+                let mut system_backends: Vec<&str> = Vec::new();
+            
+                // Add backends in priority order
+                for priority_id in &priority_order {
+                    if self.backends.iter().any(|(id, _)| id == *priority_id) {
+                        system_backends.push(priority_id);
+                    }
+                }
+            
+                // Add any other system backends not in priority list
+                for (id, _) in &self.backends {
+                    if matches!(
+                        id.as_str(),
+                        "pacman" | "apt" | "dnf" | "zypper" | "apk" | "xbps" | "portage" | "pkg" | "brew"
+                            | "winget" | "scoop" | "choco"
+                    ) && !system_backends.contains(&id.as_str()) {
+                        system_backends.push(id.as_str());
+                    }
+                }
+            
+                // Then add AUR and universal package managers (AUR should be last for system packages)
+                let mut aur_universal: Vec<&str> = self
+                    .backends
+                    .iter()
+                    .filter(|(id, _)| {
+                        matches!(id.as_str(), "flatpak" | "snap" | "nix")
+                    })
+                    .map(|(id, _)| id.as_str())
+                    .collect();
+            
+                // Add AUR last (after flatpak/snap/nix)
+                if self.backends.iter().any(|(id, _)| id == "aur") {
+                    aur_universal.push("aur");
+                }
+            
+                system_backends.append(&mut aur_universal);
+                system_backends
+            }
+            PackageType::Unknown => {
+                // For unknown packages, try system backends first, then language backends
+                // CRITICAL: pacman must come before AUR
+                let priority_order = ["pacman", "apt", "dnf", "zypper", "apk", "xbps", "portage", "pkg", "brew", "winget", "scoop", "choco", "flatpak", "snap", "nix", "aur"];
+                let mut backends: Vec<&str> = Vec::new();
+            
+                // Add backends in priority order
+                for priority_id in &priority_order {
+                    if self.backends.iter().any(|(id, _)| id == *priority_id) {
+                        backends.push(priority_id);
+                    }
+                }
+            
+                // Add any other system backends not in priority list
+                for (id, _) in &self.backends {
+                    if matches!(
+                        id.as_str(),
+                        "pacman" | "apt" | "dnf" | "zypper" | "apk" | "xbps" | "portage" | "pkg" | "brew"
+                            | "winget" | "scoop" | "choco" | "aur" | "flatpak" | "snap" | "nix"
+                    ) && !backends.contains(&id.as_str()) {
+                        backends.push(id.as_str());
+                    }
+                }
+            
+                // Then add language package managers
+                let mut lang_backends: Vec<&str> = self
+                    .backends
+                    .iter()
+                    .filter(|(id, _)| LANGUAGE_BACKENDS.contains(&id.as_str()))
+                    .map(|(id, _)| id.as_str())
+                    .collect();
+            
+                backends.append(&mut lang_backends);
+                backends
+            }
+        }
+    }
+
     /// Install packages, automatically detecting which backend to use for each
     pub async fn install_auto(&self, package_names: Vec<String>) -> Result<Vec<InstallResult>> {
         let mut all_results = Vec::new();
@@ -167,92 +329,7 @@ impl MultiBackend {
             let mut found = false;
 
             // Try backends based on detected type
-            let backends_to_try: Vec<&str> = match pkg_type {
-                PackageType::Npm => vec!["npm", "deno"], // Try npm first, then deno
-                PackageType::Pip => vec!["pip"],
-                PackageType::Cargo => vec!["cargo"],
-                PackageType::Go => vec!["go"],
-                PackageType::System => {
-                    // For system packages, try all system backends
-                    // Order matters: try native package managers first, then AUR/universal
-                    // CRITICAL: pacman must come before AUR to avoid installing main repo packages via AUR
-                    let priority_order = ["pacman", "apt", "dnf", "zypper", "pkg", "brew", "winget", "scoop", "choco"];
-                    let mut system_backends: Vec<&str> = Vec::new();
-                    
-                    // Add backends in priority order
-                    for priority_id in &priority_order {
-                        if self.backends.iter().any(|(id, _)| id == *priority_id) {
-                            system_backends.push(priority_id);
-                        }
-                    }
-                    
-                    // Add any other system backends not in priority list
-                    for (id, _) in &self.backends {
-                        if matches!(
-                            id.as_str(),
-                            "pacman" | "apt" | "dnf" | "zypper" | "pkg" | "brew"
-                                | "winget" | "scoop" | "choco"
-                        ) && !system_backends.contains(&id.as_str()) {
-                            system_backends.push(id.as_str());
-                        }
-                    }
-                    
-                    // Then add AUR and universal package managers (AUR should be last for system packages)
-                    let mut aur_universal: Vec<&str> = self
-                        .backends
-                        .iter()
-                        .filter(|(id, _)| {
-                            matches!(id.as_str(), "flatpak" | "snap")
-                        })
-                        .map(|(id, _)| id.as_str())
-                        .collect();
-                    
-                    // Add AUR last (after flatpak/snap)
-                    if self.backends.iter().any(|(id, _)| id == "aur") {
-                        aur_universal.push("aur");
-                    }
-                    
-                    system_backends.append(&mut aur_universal);
-                    system_backends
-                }
-                PackageType::Unknown => {
-                    // For unknown packages, try system backends first, then language backends
-                    // CRITICAL: pacman must come before AUR
-                    let priority_order = ["pacman", "apt", "dnf", "zypper", "pkg", "brew", "winget", "scoop", "choco", "flatpak", "snap", "aur"];
-                    let mut backends: Vec<&str> = Vec::new();
-                    
-                    // Add backends in priority order
-                    for priority_id in &priority_order {
-                        if self.backends.iter().any(|(id, _)| id == *priority_id) {
-                            backends.push(priority_id);
-                        }
-                    }
-                    
-                    // Add any other system backends not in priority list
-                    for (id, _) in &self.backends {
-                        if matches!(
-                            id.as_str(),
-                            "pacman" | "apt" | "dnf" | "zypper" | "pkg" | "brew"
-                                | "winget" | "scoop" | "choco" | "aur" | "flatpak" | "snap"
-                        ) && !backends.contains(&id.as_str()) {
-                            backends.push(id.as_str());
-                        }
-                    }
-                    
-                    // Then add language package managers
-                    let mut lang_backends: Vec<&str> = self
-                        .backends
-                        .iter()
-                        .filter(|(id, _)| {
-                            matches!(id.as_str(), "npm" | "pip" | "cargo" | "go" | "deno" | "pub")
-                        })
-                        .map(|(id, _)| id.as_str())
-                        .collect();
-                    
-                    backends.append(&mut lang_backends);
-                    backends
-                }
-            };
+            let backends_to_try = self.backends_for(&pkg_type);
 
             // Try each backend in order
             for backend_id in backends_to_try {
@@ -308,11 +385,12 @@ impl MultiBackend {
                         PackageType::Pip => backend_id == "pip",
                         PackageType::Cargo => backend_id == "cargo",
                         PackageType::Go => backend_id == "go",
+                        PackageType::Composer => backend_id == "composer",
                         PackageType::System => {
                             matches!(
                                 backend_id.as_str(),
-                                "pacman" | "aur" | "apt" | "dnf" | "zypper" | "pkg" | "brew"
-                                    | "winget" | "scoop" | "choco" | "flatpak" | "snap"
+                                "pacman" | "aur" | "apt" | "dnf" | "zypper" | "apk" | "xbps" | "portage" | "pkg" | "brew"
+                                    | "winget" | "scoop" | "choco" | "flatpak" | "snap" | "nix"
                             )
                         }
                         PackageType::Unknown => false, // Already tried all
@@ -345,6 +423,11 @@ impl MultiBackend {
         // Install packages grouped by backend
         for (backend_id, packages) in packages_by_backend {
             if let Some(backend) = self.get_backend(&backend_id) {
+                let packages = crate::typosquat::confirm(backend, packages).await?;
+                if packages.is_empty() {
+                    continue;
+                }
+
                 println!();
                 println!("--> Installing {} packages via {}:", 
                          packages.len(), 
@@ -447,7 +530,7 @@ impl MultiBackend {
 }
 
 /// Create a backend by its ID string
-fn create_backend_by_id(id: &str) -> Result<Arc<dyn PackageManager>> {
+pub(crate) fn create_backend_by_id(id: &str) -> Result<Arc<dyn PackageManager>> {
     match id {
         "apt" => Ok(Arc::new(super::apt::AptBackend::new()?)),
         "aur" => Ok(Arc::new(super::aur::AurBackend::new()?)),
@@ -459,11 +542,21 @@ fn create_backend_by_id(id: &str) -> Result<Arc<dyn PackageManager>> {
         "pacman" => Ok(Arc::new(super::pacman::PacmanBackend::new()?)),
         "pkg" => Ok(Arc::new(super::pkg::PkgBackend::new()?)),
         "zypper" => Ok(Arc::new(super::zypper::ZypperBackend::new()?)),
+        "apk" => Ok(Arc::new(super::apk::ApkBackend::new()?)),
+        "xbps" => Ok(Arc::new(super::xbps::XbpsBackend::new()?)),
+        "portage" => Ok(Arc::new(super::portage::PortageBackend::new()?)),
         "flatpak" => Ok(Arc::new(super::flatpak::FlatpakBackend::new()?)),
         "snap" => Ok(Arc::new(super::snap::SnapBackend::new()?)),
+        "nix" => Ok(Arc::new(super::nix::NixBackend::new()?)),
         "cargo" => Ok(Arc::new(super::cargo::CargoBackend::new()?)),
         "go" => Ok(Arc::new(super::go::GoBackend::new()?)),
         "pip" => Ok(Arc::new(super::pip::PipBackend::new()?)),
+        "conda" => Ok(Arc::new(super::conda::CondaBackend::new()?)),
+        "gem" => Ok(Arc::new(super::gem::GemBackend::new()?)),
+        "composer" => Ok(Arc::new(super::composer::ComposerBackend::new()?)),
+        "cabal" => Ok(Arc::new(super::cabal::CabalBackend::new()?)),
+        "opam" => Ok(Arc::new(super::opam::OpamBackend::new()?)),
+        "dotnet" => Ok(Arc::new(super::dotnet::DotnetToolBackend::new()?)),
         "npm" => Ok(Arc::new(super::npm::NpmBackend::new()?)),
         "deno" => Ok(Arc::new(super::deno::DenoBackend::new()?)),
         "pub" => Ok(Arc::new(super::r#pub::PubBackend::new()?)),
@@ -473,3 +566,16 @@ fn create_backend_by_id(id: &str) -> Result<Arc<dyn PackageManager>> {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_package_type() {
+        assert!(matches!(detect_package_type("@angular/cli"), PackageType::Npm));
+        assert!(matches!(detect_package_type("laravel/installer"), PackageType::Composer));
+        assert!(matches!(detect_package_type("github.com/cli/cli"), PackageType::Go));
+        assert!(matches!(detect_package_type("ripgrep"), PackageType::Unknown));
+    }
+}
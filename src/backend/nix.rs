@@ -0,0 +1,748 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use super::{InstallResult, Package, PackageManager};
+
+/// Nix backend installing nixpkgs attributes into the user profile
+pub struct NixBackend {
+    /// Whether the profile is managed by `nix-env` rather than `nix profile`.
+    /// The two cannot be mixed: each refuses a profile created by the other.
+    legacy: bool,
+}
+
+/// A package in the user profile
+#[derive(Debug, PartialEq)]
+struct ProfileEntry {
+    /// What `nix profile remove/upgrade` or `nix-env -e` call it
+    id: String,
+    /// nixpkgs attribute path, e.g. "python3Packages.black"
+    attr: String,
+    version: String,
+    store_paths: Vec<String>,
+    from_nixpkgs: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResult {
+    #[serde(default)]
+    version: String,
+    #[serde(default)]
+    description: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Meta {
+    #[serde(default)]
+    version: String,
+    description: Option<String>,
+    homepage: Option<String>,
+    #[serde(default)]
+    licenses: Vec<String>,
+}
+
+/// Turns a derivation into the fields of `Meta`; homepage and license may be
+/// a single value or a list
+const META_EXPR: &str = r#"p: let
+  first = x: if builtins.isList x then (if x == [] then null else builtins.head x) else x;
+  license = p.meta.license or [];
+in {
+  version = p.version or "";
+  description = p.meta.description or null;
+  homepage = first (p.meta.homepage or null);
+  licenses = map (l: if builtins.isAttrs l then l.spdxId or l.shortName or "unknown" else toString l)
+    (if builtins.isList license then license else [ license ]);
+}"#;
+
+impl NixBackend {
+    pub fn new() -> Result<Self> {
+        if !command_exists("nix") {
+            anyhow::bail!("nix is not available on this system");
+        }
+        let legacy = profile_dir()
+            .map(|dir| dir.join("manifest.nix").exists() && !dir.join("manifest.json").exists())
+            .unwrap_or(false);
+        Ok(Self { legacy })
+    }
+
+    /// The nixpkgs package set, as a Nix expression
+    fn nixpkgs_expr(&self) -> &'static str {
+        if self.legacy {
+            "import <nixpkgs> {}"
+        } else {
+            "(builtins.getFlake \"nixpkgs\").legacyPackages.${builtins.currentSystem}"
+        }
+    }
+
+    fn profile_entries(&self) -> Result<Vec<ProfileEntry>> {
+        let output = if self.legacy {
+            Command::new("nix-env")
+                .args(["-q", "--json", "--out-path"])
+                .output()
+                .context("Failed to run nix-env -q")?
+        } else {
+            nix()
+                .args(["profile", "list", "--json"])
+                .output()
+                .context("Failed to run nix profile list")?
+        };
+
+        if !output.status.success() {
+            return Ok(vec![]);
+        }
+
+        let json: Value = serde_json::from_slice(&output.stdout)
+            .context("Failed to parse the profile listing")?;
+        Ok(if self.legacy {
+            let mut entries = parse_env_query(&json);
+            resolve_env_attrs(&mut entries);
+            entries
+        } else {
+            parse_profile_list(&json)
+        })
+    }
+
+    fn run_nix(&self, command: &mut Command, description: &str) -> bool {
+        command
+            .stdin(Stdio::inherit())
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::inherit())
+            .status()
+            .map(|status| status.success())
+            .unwrap_or_else(|e| {
+                eprintln!("  --> Failed to run {}: {}", description, e);
+                false
+            })
+    }
+}
+
+#[async_trait]
+impl PackageManager for NixBackend {
+    fn name(&self) -> &str {
+        "Nix (nixpkgs)"
+    }
+
+    fn id(&self) -> &str {
+        "nix"
+    }
+
+    async fn search(&self, query: &str) -> Result<Vec<Package>> {
+        if query.len() < 2 {
+            return Ok(vec![]);
+        }
+
+        let output = nix()
+            .args(["search", "nixpkgs", query, "--json"])
+            .output()
+            .context("Failed to run nix search")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if stderr.contains("no results") {
+                return Ok(vec![]);
+            }
+            anyhow::bail!("nix search failed: {}", stderr.trim());
+        }
+
+        let results: HashMap<String, SearchResult> =
+            serde_json::from_slice(&output.stdout).context("Failed to parse nix search output")?;
+        let installed: Vec<String> = self
+            .profile_entries()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|e| e.attr)
+            .collect();
+
+        let mut packages: Vec<Package> = results
+            .into_iter()
+            .map(|(key, result)| {
+                let attr = attr_path(&key).to_string();
+                let description = Some(result.description).filter(|d| !d.is_empty());
+                let mut pkg = Package::new(attr, result.version).with_description(description);
+                pkg.installed = installed.contains(&pkg.name);
+                pkg
+            })
+            .collect();
+
+        // Exact and top-level attributes first: "ripgrep" before "vimPlugins.ripgrep"
+        packages.sort_by_key(|p| (p.name != query, p.name.contains('.'), p.name.len()));
+        packages.truncate(30);
+
+        Ok(packages)
+    }
+
+    async fn info(&self, packages: &[&str]) -> Result<Vec<Package>> {
+        let installed = self.profile_entries().unwrap_or_default();
+        let mut results = vec![];
+
+        for attr in packages {
+            let output = nix()
+                .args(["eval", "--impure", "--json", "--expr"])
+                .arg(format!(
+                    "({}) ({}).{}",
+                    META_EXPR,
+                    self.nixpkgs_expr(),
+                    attr
+                ))
+                .output()
+                .context("Failed to run nix eval")?;
+
+            if !output.status.success() {
+                continue;
+            }
+
+            let meta: Meta = serde_json::from_slice(&output.stdout).unwrap_or_default();
+            let mut pkg = Package::new(*attr, meta.version).with_description(meta.description);
+            pkg.url = meta.homepage;
+            pkg.extra.license = meta.licenses;
+            pkg.installed = installed.iter().any(|e| e.attr == *attr);
+            results.push(pkg);
+        }
+
+        Ok(results)
+    }
+
+    async fn install(&self, packages: &[Package]) -> Result<Vec<InstallResult>> {
+        let mut results = vec![];
+
+        for pkg in packages {
+            println!("--> Installing {} into the Nix profile...", pkg.name);
+            let success = if self.legacy {
+                self.run_nix(
+                    Command::new("nix-env").args(["-f", "<nixpkgs>", "-iA", &pkg.name]),
+                    "nix-env -iA",
+                )
+            } else {
+                self.run_nix(
+                    nix().args(["profile", "install", &format!("nixpkgs#{}", pkg.name)]),
+                    "nix profile install",
+                )
+            };
+
+            results.push(InstallResult {
+                package: pkg.name.clone(),
+                success,
+                message: if success {
+                    None
+                } else {
+                    Some("nix install failed".to_string())
+                },
+            });
+        }
+
+        Ok(results)
+    }
+
+    async fn update(&self, packages: &[Package]) -> Result<Vec<InstallResult>> {
+        // nix-env upgrades by installing the attribute again
+        if self.legacy {
+            return self.install(packages).await;
+        }
+
+        let entries = self.profile_entries()?;
+        let mut results = vec![];
+
+        for pkg in packages {
+            let id = entries
+                .iter()
+                .find(|e| e.attr == pkg.name)
+                .map(|e| e.id.as_str())
+                .unwrap_or(&pkg.name);
+            println!("--> Upgrading {}...", pkg.name);
+            let success = self.run_nix(
+                nix().args(["profile", "upgrade", id]),
+                "nix profile upgrade",
+            );
+
+            results.push(InstallResult {
+                package: pkg.name.clone(),
+                success,
+                message: if success {
+                    None
+                } else {
+                    Some("nix profile upgrade failed".to_string())
+                },
+            });
+        }
+
+        Ok(results)
+    }
+
+    async fn remove(&self, packages: &[&str]) -> Result<Vec<InstallResult>> {
+        let entries = self.profile_entries()?;
+        let mut results = vec![];
+
+        for name in packages {
+            let Some(entry) = entries.iter().find(|e| e.attr == *name || e.id == *name) else {
+                results.push(InstallResult {
+                    package: name.to_string(),
+                    success: false,
+                    message: Some("not installed in the Nix profile".to_string()),
+                });
+                continue;
+            };
+
+            println!("--> Removing {} from the Nix profile...", name);
+            let success = if self.legacy {
+                self.run_nix(
+                    Command::new("nix-env").args(["-e", &entry.id]),
+                    "nix-env -e",
+                )
+            } else {
+                self.run_nix(
+                    nix().args(["profile", "remove", &entry.id]),
+                    "nix profile remove",
+                )
+            };
+
+            results.push(InstallResult {
+                package: name.to_string(),
+                success,
+                message: if success {
+                    None
+                } else {
+                    Some("nix remove failed".to_string())
+                },
+            });
+        }
+
+        Ok(results)
+    }
+
+    fn is_installed(&self, package: &str) -> Result<bool> {
+        Ok(self.profile_entries()?.iter().any(|e| e.attr == package))
+    }
+
+    fn list_installed(&self) -> Result<Vec<(String, String)>> {
+        Ok(self
+            .profile_entries()?
+            .into_iter()
+            .map(|e| (e.attr, e.version))
+            .collect())
+    }
+
+    async fn check_updates(&self) -> Result<Vec<Package>> {
+        let entries: Vec<ProfileEntry> = self
+            .profile_entries()?
+            .into_iter()
+            .filter(|e| e.from_nixpkgs)
+            .collect();
+        if entries.is_empty() {
+            return Ok(vec![]);
+        }
+
+        println!("--> Evaluating nixpkgs...");
+        // One evaluation for all entries; attributes nixpkgs no longer has give ""
+        let attrs: Vec<String> = entries
+            .iter()
+            .map(|e| serde_json::to_string(&e.attr).unwrap_or_default())
+            .collect();
+        let expr = format!(
+            "let pkgs = {}; in map (a: (builtins.foldl' (set: key: set.${{key}} or {{}}) pkgs \
+             (builtins.filter builtins.isString (builtins.split \"\\\\.\" a))).version or \"\") [ {} ]",
+            self.nixpkgs_expr(),
+            attrs.join(" ")
+        );
+        let output = nix()
+            .args(["eval", "--impure", "--json", "--expr", &expr])
+            .output()
+            .context("Failed to run nix eval")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("nix eval failed: {}", stderr.trim());
+        }
+
+        let latest: Vec<String> =
+            serde_json::from_slice(&output.stdout).context("Failed to parse nix eval output")?;
+        Ok(entries
+            .into_iter()
+            .zip(latest)
+            .filter(|(entry, latest)| !latest.is_empty() && *latest != entry.version)
+            .map(|(entry, latest)| {
+                let mut pkg = Package::new(entry.attr, latest);
+                pkg.installed = true;
+                pkg
+            })
+            .collect())
+    }
+
+    fn owns(&self, path: &Path) -> Result<Option<(String, String)>> {
+        // Profile binaries are symlinks into /nix/store/<hash>-<name>-<version>/
+        let Ok(real) = path.canonicalize() else {
+            return Ok(None);
+        };
+        let Some(store_path) = store_path_of(&real) else {
+            return Ok(None);
+        };
+
+        Ok(self
+            .profile_entries()?
+            .into_iter()
+            .find(|e| e.store_paths.contains(&store_path))
+            .map(|e| (e.attr, e.version)))
+    }
+
+    async fn provides(&self, target: &str) -> Result<Vec<Package>> {
+        // nix-locate comes with nix-index and needs its database built first
+        if !command_exists("nix-locate") {
+            return Ok(vec![]);
+        }
+        let pattern = match super::command_name(target) {
+            Some(cmd) if !target.contains('/') => format!("/bin/{}", cmd),
+            _ => target.to_string(),
+        };
+
+        let output = Command::new("nix-locate")
+            .args([
+                "--minimal",
+                "--top-level",
+                "--whole-name",
+                "--at-root",
+                &pattern,
+            ])
+            .output()
+            .context("Failed to run nix-locate")?;
+
+        // One "attr.output" per line, e.g. "ripgrep.out"
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let mut attrs: Vec<&str> = vec![];
+        for line in stdout.lines() {
+            let attr = line.trim().strip_suffix(".out").unwrap_or(line.trim());
+            if !attr.is_empty() && !attrs.contains(&attr) {
+                attrs.push(attr);
+            }
+        }
+
+        self.info(&attrs).await
+    }
+}
+
+/// `nix-env -q` only knows derivation names, so the attribute path of each
+/// entry is looked up in `<nixpkgs>`: the attribute that builds the installed
+/// store path. Store paths never change, so the answers are cached.
+fn resolve_env_attrs(entries: &mut [ProfileEntry]) {
+    let cache_path = attr_cache_path();
+    let mut cache: HashMap<String, String> = cache_path
+        .as_ref()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    let mut changed = false;
+
+    for entry in entries.iter_mut() {
+        let cached = entry.store_paths.iter().find_map(|p| cache.get(p));
+        if let Some(attr) = cached {
+            entry.attr = attr.clone();
+            continue;
+        }
+
+        // One query per name: nix-env fails outright on a name it cannot find
+        let output = Command::new("nix-env")
+            .args(["-f", "<nixpkgs>", "-qaP", "--json", "--out-path", &entry.id])
+            .stderr(Stdio::null())
+            .output();
+        let Some(available) = output
+            .ok()
+            .filter(|o| o.status.success())
+            .and_then(|o| serde_json::from_slice::<Value>(&o.stdout).ok())
+        else {
+            continue;
+        };
+        if let Some(attr) = find_attr_path(&available, &entry.store_paths) {
+            for path in &entry.store_paths {
+                cache.insert(path.clone(), attr.clone());
+            }
+            entry.attr = attr;
+            changed = true;
+        }
+    }
+
+    if let (true, Some(path)) = (changed, cache_path) {
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        if let Ok(content) = serde_json::to_string(&cache) {
+            let _ = std::fs::write(path, content);
+        }
+    }
+}
+
+fn attr_cache_path() -> Option<PathBuf> {
+    directories::ProjectDirs::from("com", "zap", "zap")
+        .map(|dirs| dirs.cache_dir().join("nix-attrs.json"))
+}
+
+/// The attribute in `nix-env -qaP --json --out-path` output, keyed by
+/// attribute path, whose outputs include one of `store_paths`
+fn find_attr_path(available: &Value, store_paths: &[String]) -> Option<String> {
+    available.as_object()?.iter().find_map(|(attr, element)| {
+        let outputs = element["outputs"].as_object()?;
+        outputs
+            .values()
+            .filter_map(|p| p.as_str())
+            .any(|p| store_paths.iter().any(|s| s == p))
+            .then(|| attr.clone())
+    })
+}
+
+/// `nix` with the commands and flakes it needs enabled, for installs that
+/// have not turned them on in nix.conf
+fn nix() -> Command {
+    let mut command = Command::new("nix");
+    command.args(["--extra-experimental-features", "nix-command flakes"]);
+    command
+}
+
+fn profile_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".nix-profile"))
+}
+
+/// "legacyPackages.x86_64-linux.python3Packages.black" -> "python3Packages.black"
+fn attr_path(key: &str) -> &str {
+    match key.split_once('.') {
+        Some(("legacyPackages" | "packages", rest)) => {
+            rest.split_once('.').map(|(_, attr)| attr).unwrap_or(rest)
+        }
+        _ => key,
+    }
+}
+
+/// The store directory a file lives in: /nix/store/<hash>-<name>
+fn store_path_of(path: &Path) -> Option<String> {
+    let rest = path.strip_prefix("/nix/store").ok()?;
+    let first = rest.components().next()?;
+    Some(Path::new("/nix/store").join(first).display().to_string())
+}
+
+/// Split a store path or derivation name the way `builtins.parseDrvName`
+/// does: the version starts at the first dash followed by a digit
+fn parse_drv_name(store_path: &str) -> (&str, &str) {
+    let base = store_path.rsplit('/').next().unwrap_or(store_path);
+    let name = if store_path.starts_with("/nix/store/") {
+        base.split_once('-').map(|(_, n)| n).unwrap_or(base)
+    } else {
+        base
+    };
+    let split = name
+        .char_indices()
+        .find(|&(i, c)| {
+            c == '-'
+                && name[i + 1..]
+                    .chars()
+                    .next()
+                    .is_some_and(|c| c.is_ascii_digit())
+        })
+        .map(|(i, _)| i);
+    match split {
+        Some(i) => (&name[..i], &name[i + 1..]),
+        None => (name, ""),
+    }
+}
+
+/// Drop the output name store paths of split packages end in ("1.7.1-bin")
+fn strip_output(version: &str) -> &str {
+    ["-bin", "-out", "-lib", "-dev", "-man", "-doc"]
+        .iter()
+        .find_map(|output| version.strip_suffix(output))
+        .unwrap_or(version)
+}
+
+/// Parse `nix profile list --json`. Since Nix 2.20 elements are keyed by
+/// name; older versions list them in an array and address them by attribute path.
+fn parse_profile_list(json: &Value) -> Vec<ProfileEntry> {
+    let elements: Vec<(Option<&str>, &Value)> = match &json["elements"] {
+        Value::Object(map) => map.iter().map(|(k, v)| (Some(k.as_str()), v)).collect(),
+        Value::Array(list) => list.iter().map(|v| (None, v)).collect(),
+        _ => vec![],
+    };
+
+    elements
+        .into_iter()
+        .filter(|(_, element)| element["active"].as_bool().unwrap_or(true))
+        .filter_map(|(name, element)| {
+            let full_attr = element["attrPath"].as_str()?;
+            let store_paths: Vec<String> = element["storePaths"]
+                .as_array()
+                .map(|paths| {
+                    paths
+                        .iter()
+                        .filter_map(|p| p.as_str().map(str::to_string))
+                        .collect()
+                })
+                .unwrap_or_default();
+            let version = store_paths
+                .first()
+                .map(|p| strip_output(parse_drv_name(p).1).to_string())
+                .unwrap_or_default();
+            let url = element["originalUrl"].as_str().unwrap_or_default();
+
+            Some(ProfileEntry {
+                id: name.unwrap_or(full_attr).to_string(),
+                attr: attr_path(full_attr).to_string(),
+                version,
+                store_paths,
+                from_nixpkgs: url.contains("nixpkgs"),
+            })
+        })
+        .collect()
+}
+
+/// Parse `nix-env -q --json --out-path`. Only the derivation names are
+/// known, so `attr` starts out as the package name.
+fn parse_env_query(json: &Value) -> Vec<ProfileEntry> {
+    let Some(map) = json.as_object() else {
+        return vec![];
+    };
+
+    map.iter()
+        .map(|(key, element)| {
+            let drv_name = element["name"].as_str().unwrap_or(key);
+            let (parsed_name, parsed_version) = parse_drv_name(drv_name);
+            let pname = element["pname"].as_str().unwrap_or(parsed_name);
+            let version = element["version"].as_str().unwrap_or(parsed_version);
+            let store_paths = element["outputs"]
+                .as_object()
+                .map(|outputs| {
+                    outputs
+                        .values()
+                        .filter_map(|p| p.as_str().map(str::to_string))
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            ProfileEntry {
+                id: pname.to_string(),
+                attr: pname.to_string(),
+                version: version.to_string(),
+                store_paths,
+                from_nixpkgs: true,
+            }
+        })
+        .collect()
+}
+
+fn command_exists(cmd: &str) -> bool {
+    Command::new("which")
+        .arg(cmd)
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_drv_name() {
+        assert_eq!(
+            parse_drv_name("/nix/store/0c8bmxsd2yxqdn2vjb4n5pp9bfnqs6k8-ripgrep-14.1.0"),
+            ("ripgrep", "14.1.0")
+        );
+        assert_eq!(
+            parse_drv_name("python3.12-black-24.4.2"),
+            ("python3.12-black", "24.4.2")
+        );
+        assert_eq!(parse_drv_name("hello"), ("hello", ""));
+    }
+
+    #[test]
+    fn test_attr_path() {
+        assert_eq!(attr_path("legacyPackages.x86_64-linux.ripgrep"), "ripgrep");
+        assert_eq!(
+            attr_path("legacyPackages.aarch64-darwin.python3Packages.black"),
+            "python3Packages.black"
+        );
+        assert_eq!(attr_path("ripgrep"), "ripgrep");
+    }
+
+    #[test]
+    fn test_parse_profile_list() {
+        let json: Value = serde_json::from_str(
+            r#"{
+              "elements": {
+                "ripgrep": {
+                  "active": true,
+                  "attrPath": "legacyPackages.x86_64-linux.ripgrep",
+                  "originalUrl": "flake:nixpkgs",
+                  "outputs": null,
+                  "priority": 5,
+                  "storePaths": ["/nix/store/0c8bmxsd2yxqdn2vjb4n5pp9bfnqs6k8-ripgrep-14.1.0"],
+                  "url": "path:/nix/store/abc-source?narHash=sha256-xyz"
+                },
+                "helix": {
+                  "active": true,
+                  "attrPath": "packages.x86_64-linux.default",
+                  "originalUrl": "github:helix-editor/helix",
+                  "storePaths": ["/nix/store/1d9cmxsd2yxqdn2vjb4n5pp9bfnqs6k8-helix-24.07"]
+                }
+              },
+              "version": 3
+            }"#,
+        )
+        .unwrap();
+        let mut entries = parse_profile_list(&json);
+        entries.sort_by(|a, b| a.id.cmp(&b.id));
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].id, "helix");
+        assert!(!entries[0].from_nixpkgs);
+        assert_eq!(entries[1].attr, "ripgrep");
+        assert_eq!(entries[1].version, "14.1.0");
+        assert!(entries[1].from_nixpkgs);
+
+        // Nix before 2.20: an array, addressed by attribute path
+        let json: Value = serde_json::from_str(
+            r#"{"elements": [{"active": true, "attrPath": "legacyPackages.x86_64-linux.jq",
+                "originalUrl": "flake:nixpkgs", "storePaths": ["/nix/store/2e0c-jq-1.7.1-bin"]}],
+                "version": 2}"#,
+        )
+        .unwrap();
+        let entries = parse_profile_list(&json);
+        assert_eq!(entries[0].id, "legacyPackages.x86_64-linux.jq");
+        assert_eq!(entries[0].attr, "jq");
+        assert_eq!(entries[0].version, "1.7.1");
+    }
+
+    #[test]
+    fn test_parse_env_query() {
+        let json: Value = serde_json::from_str(
+            r#"{"hello-2.12.1": {"name": "hello-2.12.1", "pname": "hello", "version": "2.12.1",
+                "system": "x86_64-linux", "outputName": "out",
+                "outputs": {"out": "/nix/store/63l345l7dgcfz789w1y93j1540czafqh-hello-2.12.1"}}}"#,
+        )
+        .unwrap();
+        let entries = parse_env_query(&json);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].attr, "hello");
+        assert_eq!(entries[0].version, "2.12.1");
+        assert_eq!(
+            entries[0].store_paths,
+            ["/nix/store/63l345l7dgcfz789w1y93j1540czafqh-hello-2.12.1"]
+        );
+    }
+
+    #[test]
+    fn test_find_attr_path() {
+        let available: Value = serde_json::from_str(
+            r#"{"black": {"name": "black-24.4.2", "pname": "black", "version": "24.4.2",
+                 "outputs": {"out": "/nix/store/5lh1yc3q3l3ng8ybl7kv8kw0vjcq1lwk-black-24.4.2"}},
+                "python311Packages.black": {"name": "python3.11-black-24.4.2", "pname": "black",
+                 "version": "24.4.2",
+                 "outputs": {"out": "/nix/store/qgshm6m2mjm1xvdbn3rlxy5hvmk3bjyz-python3.11-black-24.4.2"}}}"#,
+        )
+        .unwrap();
+        let installed =
+            ["/nix/store/qgshm6m2mjm1xvdbn3rlxy5hvmk3bjyz-python3.11-black-24.4.2".to_string()];
+        assert_eq!(
+            find_attr_path(&available, &installed).as_deref(),
+            Some("python311Packages.black")
+        );
+        let other = ["/nix/store/0000000000000000000000000000000-black-23.1.0".to_string()];
+        assert_eq!(find_attr_path(&available, &other), None);
+    }
+}
@@ -0,0 +1,344 @@
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Lockfiles that identify the client a project uses, checked in order
+const LOCKFILES: &[(&str, NodeClient)] = &[
+    ("pnpm-lock.yaml", NodeClient::Pnpm),
+    ("yarn.lock", NodeClient::Yarn),
+    ("bun.lock", NodeClient::Bun),
+    ("bun.lockb", NodeClient::Bun),
+    ("package-lock.json", NodeClient::Npm),
+];
+
+/// The command-line client that installs packages from the npm registry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeClient {
+    Npm,
+    Pnpm,
+    Yarn,
+    Bun,
+}
+
+impl NodeClient {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "npm" => Some(Self::Npm),
+            "pnpm" => Some(Self::Pnpm),
+            "yarn" => Some(Self::Yarn),
+            "bun" => Some(Self::Bun),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Npm => "npm",
+            Self::Pnpm => "pnpm",
+            Self::Yarn => "yarn",
+            Self::Bun => "bun",
+        }
+    }
+
+    /// Executable name; the Node.js clients are .cmd shims on Windows
+    pub fn command(self) -> String {
+        if cfg!(target_os = "windows") && self != Self::Bun {
+            format!("{}.cmd", self.name())
+        } else {
+            self.name().to_string()
+        }
+    }
+
+    pub fn is_available(self) -> bool {
+        super::command_exists(&self.command())
+    }
+
+    /// `$ZAP_NODE_PM`, then the client the project in `dir` uses, then the
+    /// first installed one
+    pub fn select(dir: &Path) -> Result<Self> {
+        if let Ok(name) = std::env::var("ZAP_NODE_PM") {
+            return Self::from_name(&name).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Unknown ZAP_NODE_PM value '{}' (expected npm, pnpm, yarn or bun)",
+                    name
+                )
+            });
+        }
+
+        if let Some(node) = Self::detect(dir) {
+            if node.is_available() {
+                return Ok(node);
+            }
+            eprintln!(
+                "Warning: this project uses {} but it is not installed",
+                node.name()
+            );
+        }
+
+        [Self::Npm, Self::Pnpm, Self::Bun, Self::Yarn]
+            .into_iter()
+            .find(|node| node.is_available())
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "npm is not available on this system. Install Node.js to use this backend."
+                )
+            })
+    }
+
+    /// The client a project uses, from the `packageManager` field of its
+    /// package.json or its lockfile, looking in `dir` and its parents
+    pub fn detect(dir: &Path) -> Option<Self> {
+        for dir in dir.ancestors() {
+            if let Ok(content) = std::fs::read_to_string(dir.join("package.json")) {
+                if let Some(client) = parse_package_manager_field(&content) {
+                    return Some(client);
+                }
+            }
+            for (lockfile, client) in LOCKFILES {
+                if dir.join(lockfile).is_file() {
+                    return Some(*client);
+                }
+            }
+        }
+        None
+    }
+
+    /// Arguments for adding packages, globally or to the current project
+    pub fn add_args(self, global: bool) -> Vec<&'static str> {
+        match (self, global) {
+            (Self::Npm, true) => vec!["install", "-g"],
+            (Self::Npm, false) => vec!["install", "--save"],
+            (Self::Yarn, true) => vec!["global", "add"],
+            (Self::Pnpm | Self::Bun, true) => vec!["add", "-g"],
+            (_, false) => vec!["add"],
+        }
+    }
+
+    /// Arguments for removing packages, globally or from the current project
+    pub fn remove_args(self, global: bool) -> Vec<&'static str> {
+        match (self, global) {
+            (Self::Npm, true) => vec!["uninstall", "-g"],
+            (Self::Npm, false) => vec!["uninstall"],
+            (Self::Yarn, true) => vec!["global", "remove"],
+            (Self::Pnpm | Self::Bun, true) => vec!["remove", "-g"],
+            (_, false) => vec!["remove"],
+        }
+    }
+
+    /// Arguments for updating global packages (all of them when `names` is
+    /// empty); bun has no global update, so packages are re-added at @latest
+    pub fn update_args(self, names: &[&str]) -> Vec<String> {
+        let mut args: Vec<String> = match self {
+            Self::Npm => vec!["update".into(), "-g".into()],
+            Self::Pnpm => vec!["update".into(), "-g".into(), "--latest".into()],
+            Self::Yarn => vec!["global".into(), "upgrade".into(), "--latest".into()],
+            Self::Bun => vec!["add".into(), "-g".into()],
+        };
+        if self == Self::Bun {
+            args.extend(names.iter().map(|n| format!("{}@latest", n)));
+        } else {
+            args.extend(names.iter().map(|n| n.to_string()));
+        }
+        args
+    }
+
+    /// Globally installed packages as (name, version)
+    pub fn global_packages(self) -> Result<Vec<(String, String)>> {
+        let args: &[&str] = match self {
+            Self::Npm | Self::Pnpm => &["list", "-g", "--depth", "0", "--json"],
+            Self::Yarn => &["global", "list", "--json"],
+            Self::Bun => &["pm", "ls", "-g"],
+        };
+        let output = self.output(args)?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        Ok(match self {
+            Self::Npm | Self::Pnpm => parse_json_list(&stdout),
+            Self::Yarn => parse_yarn_global_list(&stdout),
+            Self::Bun => parse_bun_list(&stdout),
+        })
+    }
+
+    /// Names of outdated global packages, for clients that can report them.
+    /// Returns None when the caller has to compare against the registry itself.
+    pub fn outdated_globals(self) -> Result<Option<Vec<String>>> {
+        let args: &[&str] = match self {
+            Self::Npm => &["outdated", "-g", "--json"],
+            Self::Pnpm => &["outdated", "-g", "--format", "json"],
+            Self::Yarn | Self::Bun => return Ok(None),
+        };
+        let output = self.output(args)?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        let value: Value = serde_json::from_str(stdout.trim()).unwrap_or(Value::Null);
+        Ok(Some(
+            value
+                .as_object()
+                .map(|map| map.keys().cloned().collect())
+                .unwrap_or_default(),
+        ))
+    }
+
+    /// Directory the client links global executables into
+    pub fn global_bin_dir(self) -> Option<PathBuf> {
+        let args: &[&str] = match self {
+            Self::Npm => &["prefix", "-g"],
+            Self::Pnpm => &["bin", "-g"],
+            Self::Yarn => &["global", "bin"],
+            Self::Bun => &["pm", "bin", "-g"],
+        };
+        let output = self.output(args).ok().filter(|o| o.status.success())?;
+        let dir = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if dir.is_empty() {
+            return None;
+        }
+
+        // npm prints its prefix; executables live in <prefix>/bin outside Windows
+        if self == Self::Npm && !cfg!(target_os = "windows") {
+            Some(Path::new(&dir).join("bin"))
+        } else {
+            Some(PathBuf::from(dir))
+        }
+    }
+
+    pub fn run(self, args: &[&str]) -> Result<std::process::ExitStatus> {
+        Command::new(self.command())
+            .args(args)
+            .stdin(Stdio::inherit())
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::inherit())
+            .status()
+            .with_context(|| format!("Failed to run {} command", self.name()))
+    }
+
+    /// Run the client in a project directory
+    pub fn run_in(self, dir: &Path, args: &[&str]) -> Result<std::process::ExitStatus> {
+        Command::new(self.command())
+            .args(args)
+            .current_dir(dir)
+            .stdin(Stdio::inherit())
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::inherit())
+            .status()
+            .with_context(|| format!("Failed to run {} command", self.name()))
+    }
+
+    fn output(self, args: &[&str]) -> Result<std::process::Output> {
+        Command::new(self.command())
+            .args(args)
+            .output()
+            .with_context(|| format!("Failed to run {} {}", self.name(), args.join(" ")))
+    }
+}
+
+/// `"packageManager": "pnpm@9.1.0+sha512..."` (corepack)
+fn parse_package_manager_field(package_json: &str) -> Option<NodeClient> {
+    let value: Value = serde_json::from_str(package_json).ok()?;
+    let field = value.get("packageManager")?.as_str()?;
+    NodeClient::from_name(field.split('@').next()?)
+}
+
+/// `npm list -g --json` prints an object, `pnpm list -g --json` an array of them
+fn parse_json_list(output: &str) -> Vec<(String, String)> {
+    let value: Value = serde_json::from_str(output.trim()).unwrap_or(Value::Null);
+    let roots = match &value {
+        Value::Array(roots) => roots.iter().collect(),
+        other => vec![other],
+    };
+
+    let mut packages = vec![];
+    for root in roots {
+        if let Some(deps) = root.get("dependencies").and_then(|d| d.as_object()) {
+            for (name, info) in deps {
+                if let Some(version) = info.get("version").and_then(|v| v.as_str()) {
+                    packages.push((name.clone(), version.to_string()));
+                }
+            }
+        }
+    }
+    packages
+}
+
+/// Yarn classic prints one JSON event per line; installed packages appear as
+/// `{"type":"info","data":"\"typescript@5.4.5\" has binaries:"}`
+fn parse_yarn_global_list(output: &str) -> Vec<(String, String)> {
+    output
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .filter(|event| event.get("type").and_then(|t| t.as_str()) == Some("info"))
+        .filter_map(|event| {
+            let data = event.get("data")?.as_str()?;
+            let spec = data.strip_prefix('"')?.split('"').next()?;
+            split_spec(spec)
+        })
+        .collect()
+}
+
+/// `bun pm ls -g` prints a tree: `├── typescript@5.4.5`
+fn parse_bun_list(output: &str) -> Vec<(String, String)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let spec = line
+                .trim()
+                .strip_prefix("├──")
+                .or_else(|| line.trim().strip_prefix("└──"))?;
+            split_spec(spec.trim())
+        })
+        .collect()
+}
+
+/// "@scope/name@1.0.0" -> ("@scope/name", "1.0.0")
+fn split_spec(spec: &str) -> Option<(String, String)> {
+    let (name, version) = spec.rsplit_once('@')?;
+    if name.is_empty() {
+        return None;
+    }
+    Some((name.to_string(), version.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_package_manager_field() {
+        assert_eq!(
+            parse_package_manager_field(
+                r#"{"name":"app","packageManager":"pnpm@9.1.0+sha512.abc"}"#
+            ),
+            Some(NodeClient::Pnpm)
+        );
+        assert_eq!(parse_package_manager_field(r#"{"name":"app"}"#), None);
+    }
+
+    #[test]
+    fn test_parse_global_lists() {
+        let pnpm = r#"[{"path":"/home/u/.local/share/pnpm/global/5","dependencies":{"typescript":{"version":"5.4.5"},"@biomejs/biome":{"version":"1.8.0"}}}]"#;
+        let mut packages = parse_json_list(pnpm);
+        packages.sort();
+        assert_eq!(
+            packages,
+            [
+                ("@biomejs/biome".to_string(), "1.8.0".to_string()),
+                ("typescript".to_string(), "5.4.5".to_string()),
+            ]
+        );
+
+        let yarn = "{\"type\":\"info\",\"data\":\"\\\"@vue/cli@5.0.8\\\" has binaries:\"}\n{\"type\":\"list\",\"data\":{\"type\":\"bins-@vue/cli\",\"items\":[\"vue\"]}}\n";
+        assert_eq!(
+            parse_yarn_global_list(yarn),
+            [("@vue/cli".to_string(), "5.0.8".to_string())]
+        );
+
+        let bun = "/home/u/.bun/install/global node_modules (2)\n├── prettier@3.3.2\n└── typescript@5.4.5\n";
+        assert_eq!(
+            parse_bun_list(bun),
+            [
+                ("prettier".to_string(), "3.3.2".to_string()),
+                ("typescript".to_string(), "5.4.5".to_string()),
+            ]
+        );
+    }
+}
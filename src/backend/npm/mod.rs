@@ -1,15 +1,21 @@
+mod client;
+
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use serde::Deserialize;
 use serde_json::Value;
 use std::collections::HashMap;
-use std::process::{Command, Stdio};
+use std::path::{Path, PathBuf};
+use std::process::Command;
 
 use super::{InstallResult, Package, PackageExtra, PackageManager};
+pub use client::NodeClient;
 
-/// npm package manager backend for JavaScript/TypeScript packages
+/// npm registry backend for JavaScript/TypeScript packages, installed with
+/// npm, pnpm, yarn or bun
 pub struct NpmBackend {
     client: reqwest::Client,
+    node: NodeClient,
 }
 
 #[derive(Debug, Deserialize)]
@@ -58,9 +64,16 @@ struct NpmScoreDetail {
 
 impl NpmBackend {
     pub fn new() -> Result<Self> {
-        if !command_exists(Self::npm_command()) {
+        let dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        Self::with_client(NodeClient::select(&dir)?)
+    }
+
+    /// Use a specific client instead of auto-selecting one
+    pub fn with_client(node: NodeClient) -> Result<Self> {
+        if !node.is_available() {
             anyhow::bail!(
-                "npm is not available on this system. Install Node.js to use this backend."
+                "{} is not available on this system. Install it to use this backend.",
+                node.name()
             );
         }
 
@@ -69,15 +82,7 @@ impl NpmBackend {
             .build()
             .context("Failed to create HTTP client")?;
 
-        Ok(Self { client })
-    }
-
-    fn npm_command() -> &'static str {
-        if cfg!(target_os = "windows") {
-            "npm.cmd"
-        } else {
-            "npm"
-        }
+        Ok(Self { client, node })
     }
 
     async fn search_registry(&self, query: &str) -> Result<Vec<Package>> {
@@ -104,7 +109,9 @@ impl NpmBackend {
             .map(Self::package_from_search)
             .collect();
 
-        let installed = Self::global_dependencies()?
+        let installed = self
+            .node
+            .global_packages()?
             .into_iter()
             .collect::<HashMap<_, _>>();
 
@@ -187,6 +194,12 @@ impl NpmBackend {
         if let Some(license) = value.get("license").and_then(|v| v.as_str()) {
             extra.license = vec![license.to_string()];
         }
+        extra.created_at = value
+            .get("time")
+            .and_then(|time| time.get("created"))
+            .and_then(|v| v.as_str())
+            .and_then(|v| chrono::DateTime::parse_from_rfc3339(v).ok())
+            .map(|d| d.timestamp());
 
         let maintainer = value
             .get("maintainers")
@@ -233,46 +246,17 @@ impl NpmBackend {
             extra,
         }))
     }
-
-    fn global_dependencies() -> Result<Vec<(String, String)>> {
-        let output = Command::new(Self::npm_command())
-            .args(["list", "-g", "--depth", "0", "--json"])
-            .output()
-            .context("Failed to run npm list")?;
-
-        if output.stdout.is_empty() {
-            return Ok(vec![]);
-        }
-
-        let value: Value = serde_json::from_slice(&output.stdout).unwrap_or(Value::Null);
-
-        let mut packages = Vec::new();
-        if let Some(deps) = value.get("dependencies").and_then(|v| v.as_object()) {
-            for (name, info) in deps {
-                if let Some(version) = info.get("version").and_then(|v| v.as_str()) {
-                    packages.push((name.clone(), version.to_string()));
-                }
-            }
-        }
-
-        Ok(packages)
-    }
-
-    fn run_npm(args: &[&str]) -> Result<std::process::ExitStatus> {
-        Command::new(Self::npm_command())
-            .args(args)
-            .stdin(Stdio::inherit())
-            .stdout(Stdio::inherit())
-            .stderr(Stdio::inherit())
-            .status()
-            .context("Failed to run npm command")
-    }
 }
 
 #[async_trait]
 impl PackageManager for NpmBackend {
     fn name(&self) -> &str {
-        "npm (Node.js)"
+        match self.node {
+            NodeClient::Npm => "npm (Node.js)",
+            NodeClient::Pnpm => "pnpm (Node.js)",
+            NodeClient::Yarn => "yarn (Node.js)",
+            NodeClient::Bun => "bun",
+        }
     }
 
     fn id(&self) -> &str {
@@ -301,27 +285,31 @@ impl PackageManager for NpmBackend {
     }
 
     async fn install(&self, packages: &[Package]) -> Result<Vec<InstallResult>> {
-        let pkg_names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
-        if pkg_names.is_empty() {
+        let specs: Vec<String> = packages.iter().map(|p| p.spec("@")).collect();
+        if specs.is_empty() {
             return Ok(vec![]);
         }
 
-        // Check if we're in a project directory (has package.json)
+        // Inside a project the packages become dependencies; elsewhere they
+        // are installed as global tools
         let is_project = std::path::Path::new("package.json").exists();
-        
+
         if is_project {
-            println!("--> Installing packages locally (project dependencies)...");
+            println!(
+                "--> Installing packages locally with {} (project dependencies)...",
+                self.node.name()
+            );
         } else {
-            println!("--> Installing packages locally (creating package.json)...");
-            // Initialize package.json if it doesn't exist
-            let _ = Self::run_npm(&["init", "-y"]);
+            println!(
+                "--> Installing packages globally with {}...",
+                self.node.name()
+            );
         }
 
-        // Install locally (without -g flag)
-        let mut args = vec!["install", "--save"];
-        args.extend(pkg_names.iter().copied());
+        let mut args = self.node.add_args(!is_project);
+        args.extend(specs.iter().map(|s| s.as_str()));
 
-        let status = Self::run_npm(&args)?;
+        let status = self.node.run(&args)?;
         let success = status.success();
 
         Ok(packages
@@ -332,41 +320,104 @@ impl PackageManager for NpmBackend {
                 message: if success {
                     None
                 } else {
-                    Some("npm install failed".to_string())
+                    Some(format!("{} install failed", self.node.name()))
+                },
+            })
+            .collect())
+    }
+
+    async fn remove(&self, packages: &[&str]) -> Result<Vec<InstallResult>> {
+        if packages.is_empty() {
+            return Ok(vec![]);
+        }
+
+        // Mirror install: project dependencies inside a project, global tools elsewhere
+        let is_project = std::path::Path::new("package.json").exists();
+
+        if is_project {
+            println!(
+                "--> Removing project dependencies with {}...",
+                self.node.name()
+            );
+        } else {
+            println!(
+                "--> Removing global packages with {}...",
+                self.node.name()
+            );
+        }
+
+        let mut args = self.node.remove_args(!is_project);
+        args.extend(packages.iter().copied());
+
+        let status = self.node.run(&args)?;
+        let success = status.success();
+
+        Ok(packages
+            .iter()
+            .map(|name| InstallResult {
+                package: name.to_string(),
+                success,
+                message: if success {
+                    None
+                } else {
+                    Some(format!("{} remove failed", self.node.name()))
                 },
             })
             .collect())
     }
 
     fn is_installed(&self, package: &str) -> Result<bool> {
-        let installed = Self::global_dependencies()?;
+        let installed = self.node.global_packages()?;
         Ok(installed.iter().any(|(name, _)| name == package))
     }
 
+    fn owns(&self, path: &Path) -> Result<Option<(String, String)>> {
+        // Global bins are symlinks into <prefix>/lib/node_modules/<package>
+        if !path.is_symlink() {
+            return Ok(None);
+        }
+        let Some(package_dir) = path
+            .canonicalize()
+            .ok()
+            .and_then(|target| node_package_dir(&target))
+        else {
+            return Ok(None);
+        };
+
+        let manifest: Value = std::fs::read_to_string(package_dir.join("package.json"))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or(Value::Null);
+        let field = |key: &str| manifest.get(key).and_then(|v| v.as_str()).map(|v| v.to_string());
+        Ok(field("name").map(|name| (name, field("version").unwrap_or_default())))
+    }
+
     fn list_installed(&self) -> Result<Vec<(String, String)>> {
-        Self::global_dependencies()
+        self.node.global_packages()
     }
 
     async fn check_updates(&self) -> Result<Vec<Package>> {
-        let output = Command::new(Self::npm_command())
-            .args(["outdated", "-g", "--json"])
-            .output()
-            .context("Failed to run npm outdated")?;
-
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let trimmed = stdout.trim();
-        if trimmed.is_empty() || trimmed == "{}" || trimmed == "null" {
-            return Ok(vec![]);
-        }
-
-        let value: Value = serde_json::from_str(trimmed).unwrap_or(Value::Null);
+        let outdated = self.node.outdated_globals()?;
         let mut updates = Vec::new();
 
-        if let Some(map) = value.as_object() {
-            for name in map.keys() {
-                if let Some(mut pkg) = self.fetch_package(name).await? {
-                    pkg.extra.out_of_date = Some(1);
-                    updates.push(pkg);
+        match outdated {
+            Some(names) => {
+                for name in names {
+                    if let Some(mut pkg) = self.fetch_package(&name).await? {
+                        pkg.extra.out_of_date = Some(1);
+                        updates.push(pkg);
+                    }
+                }
+            }
+            // yarn and bun can't list outdated globals; compare with the registry
+            None => {
+                for (name, version) in self.node.global_packages()? {
+                    if let Some(mut pkg) = self.fetch_package(&name).await? {
+                        if pkg.version != version {
+                            pkg.extra.out_of_date = Some(1);
+                            updates.push(pkg);
+                        }
+                    }
                 }
             }
         }
@@ -376,25 +427,39 @@ impl PackageManager for NpmBackend {
 
     async fn update(&self, packages: &[Package]) -> Result<Vec<InstallResult>> {
         let mut results = Vec::new();
+        let failed = format!("{} update failed", self.node.name());
 
         if packages.is_empty() {
-            println!("--> Updating all global npm packages...");
-            let status = Self::run_npm(&["update", "-g"])?;
+            println!("--> Updating all global {} packages...", self.node.name());
+            let installed = self.node.global_packages()?;
+            // bun has no update-all, so every package is named explicitly
+            let names: Vec<&str> = if self.node == NodeClient::Bun {
+                installed.iter().map(|(name, _)| name.as_str()).collect()
+            } else {
+                vec![]
+            };
+            if self.node == NodeClient::Bun && names.is_empty() {
+                return Ok(results);
+            }
+
+            let args = self.node.update_args(&names);
+            let args: Vec<&str> = args.iter().map(|a| a.as_str()).collect();
+            let status = self.node.run(&args)?;
             results.push(InstallResult {
                 package: "all".to_string(),
                 success: status.success(),
                 message: if status.success() {
                     None
                 } else {
-                    Some("npm update failed".to_string())
+                    Some(failed)
                 },
             });
         } else {
             let pkg_names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
-            let mut args = vec!["update", "-g"];
-            args.extend(pkg_names.iter().copied());
+            let args = self.node.update_args(&pkg_names);
+            let args: Vec<&str> = args.iter().map(|a| a.as_str()).collect();
 
-            let status = Self::run_npm(&args)?;
+            let status = self.node.run(&args)?;
             let success = status.success();
 
             for pkg in packages {
@@ -404,7 +469,7 @@ impl PackageManager for NpmBackend {
                     message: if success {
                         None
                     } else {
-                        Some("npm update failed".to_string())
+                        Some(failed.clone())
                     },
                 });
             }
@@ -412,6 +477,71 @@ impl PackageManager for NpmBackend {
 
         Ok(results)
     }
+
+    async fn provides(&self, target: &str) -> Result<Vec<Package>> {
+        let Some(command) = super::command_name(target) else {
+            return Ok(vec![]);
+        };
+
+        // A package named after the command is the likeliest provider
+        let mut candidates = vec![command.to_string()];
+        let results = self.search(command).await.unwrap_or_default();
+        for pkg in results {
+            if candidates.len() >= super::MAX_PROVIDER_CANDIDATES {
+                break;
+            }
+            if !candidates.contains(&pkg.name) {
+                candidates.push(pkg.name);
+            }
+        }
+
+        let checks = candidates.iter().map(|name| async move {
+            let url = format!("https://registry.npmjs.org/{name}/latest");
+            let manifest: Value = self.client.get(&url).send().await.ok()?.json().await.ok()?;
+            let bins = bin_names(name, &manifest);
+            bins.iter().any(|b| b == command).then_some(name.as_str())
+        });
+        let providers: Vec<&str> = futures::future::join_all(checks)
+            .await
+            .into_iter()
+            .flatten()
+            .collect();
+
+        self.info(&providers).await
+    }
+
+    /// Downloads over the last year, from the npm download counts API
+    async fn downloads(&self, package: &str) -> Option<u64> {
+        let url = format!("https://api.npmjs.org/downloads/point/last-year/{package}");
+        let value: Value = self.client.get(&url).send().await.ok()?.json().await.ok()?;
+        value.get("downloads").and_then(|d| d.as_u64())
+    }
+}
+
+/// The package directory of a file under `node_modules`: the innermost
+/// `node_modules/<name>`, or `node_modules/@scope/<name>` for scoped packages
+fn node_package_dir(target: &Path) -> Option<PathBuf> {
+    let components: Vec<&std::ffi::OsStr> = target.components().map(|c| c.as_os_str()).collect();
+    let i = components.iter().rposition(|c| *c == "node_modules")?;
+    let mut package_dir: PathBuf = components[..=i].iter().collect();
+    let first = components.get(i + 1)?;
+    package_dir.push(first);
+    if first.to_string_lossy().starts_with('@') {
+        package_dir.push(components.get(i + 2)?);
+    }
+    Some(package_dir)
+}
+
+/// Executables from the `bin` field of a package manifest. A plain string
+/// installs one executable named after the package, without its scope.
+fn bin_names(name: &str, manifest: &Value) -> Vec<String> {
+    match manifest.get("bin") {
+        Some(Value::String(_)) => {
+            vec![name.rsplit('/').next().unwrap_or(name).to_string()]
+        }
+        Some(Value::Object(bins)) => bins.keys().cloned().collect(),
+        _ => vec![],
+    }
 }
 
 fn extract_dependencies(value: &Value, version: &str) -> Vec<String> {
@@ -461,3 +591,28 @@ fn command_exists(cmd: &str) -> bool {
             .unwrap_or(false)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_node_package_dir() {
+        assert_eq!(
+            node_package_dir(Path::new("/usr/lib/node_modules/typescript/bin/tsc")),
+            Some(PathBuf::from("/usr/lib/node_modules/typescript"))
+        );
+        assert_eq!(
+            node_package_dir(Path::new("/opt/node/lib/node_modules/@angular/cli/bin/ng.js")),
+            Some(PathBuf::from("/opt/node/lib/node_modules/@angular/cli"))
+        );
+        // A dependency's own node_modules: the innermost package owns the file
+        assert_eq!(
+            node_package_dir(Path::new("/usr/lib/node_modules/npm/node_modules/semver/bin/semver.js")),
+            Some(PathBuf::from("/usr/lib/node_modules/npm/node_modules/semver"))
+        );
+        assert_eq!(node_package_dir(Path::new("/usr/lib/node_modules")), None);
+        assert_eq!(node_package_dir(Path::new("/usr/lib/node_modules/@types")), None);
+        assert_eq!(node_package_dir(Path::new("/usr/bin/node")), None);
+    }
+}
@@ -0,0 +1,356 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::cmp::Ordering;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use super::{InstallResult, Package, PackageManager};
+use crate::audit::compare_versions;
+
+/// opam backend for OCaml packages in the current switch
+pub struct OpamBackend;
+
+/// Fields asked of `opam show`; opam file fields end in ":"
+const SHOW_FIELDS: &str = "name,version,synopsis,homepage:,license:,maintainer:,depends:";
+
+impl OpamBackend {
+    pub fn new() -> Result<Self> {
+        if !command_exists("opam") {
+            anyhow::bail!("opam is not available on this system");
+        }
+        Ok(Self)
+    }
+
+    /// The bin directory of the current switch
+    pub(crate) fn bin_dir() -> Option<PathBuf> {
+        let output = Command::new("opam")
+            .args(["var", "bin"])
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+        let dir = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        (output.status.success() && !dir.is_empty()).then(|| PathBuf::from(dir))
+    }
+
+    /// `opam list` (or `search`) with the given columns, one row per package
+    fn list_columns(&self, args: &[&str], columns: &str) -> Result<String> {
+        let output = Command::new("opam")
+            .args(args)
+            .arg(format!("--columns={}", columns))
+            .args(["--short", "--color=never"])
+            .stdin(Stdio::null())
+            .output()
+            .with_context(|| format!("Failed to run opam {}", args[0]))?;
+
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    fn run_opam(&self, action: &str, packages: &[&str]) -> Result<Vec<InstallResult>> {
+        if packages.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let status = Command::new("opam")
+            .args([action, "-y"])
+            .args(packages)
+            .stdin(Stdio::inherit())
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::inherit())
+            .status()
+            .with_context(|| format!("Failed to run opam {}", action))?;
+
+        let success = status.success();
+        Ok(packages
+            .iter()
+            .map(|name| InstallResult {
+                package: name.to_string(),
+                success,
+                message: if success {
+                    None
+                } else {
+                    Some(format!("opam {} failed", action))
+                },
+            })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl PackageManager for OpamBackend {
+    fn name(&self) -> &str {
+        "opam (OCaml)"
+    }
+
+    fn id(&self) -> &str {
+        "opam"
+    }
+
+    async fn search(&self, query: &str) -> Result<Vec<Package>> {
+        if query.len() < 2 {
+            return Ok(vec![]);
+        }
+
+        let stdout = self.list_columns(
+            &["search", query],
+            "name,version,installed-version,synopsis",
+        )?;
+        let mut packages = parse_search(&stdout);
+
+        // Matches in the name before matches in the description
+        let query = query.to_lowercase();
+        packages.sort_by_key(|pkg| {
+            let name = pkg.name.to_lowercase();
+            (name != query, !name.contains(&query))
+        });
+        packages.truncate(30);
+
+        Ok(packages)
+    }
+
+    async fn info(&self, packages: &[&str]) -> Result<Vec<Package>> {
+        let installed = self.list_installed()?;
+        let mut results = vec![];
+
+        for pkg_name in packages {
+            let output = Command::new("opam")
+                .args(["show", "--normalise", "--color=never"])
+                .arg(format!("--field={}", SHOW_FIELDS))
+                .arg(pkg_name)
+                .stdin(Stdio::null())
+                .output()
+                .context("Failed to run opam show")?;
+
+            if output.status.success() {
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                if let Some(mut pkg) = parse_show(&stdout) {
+                    pkg.installed = installed.iter().any(|(name, _)| *name == pkg.name);
+                    results.push(pkg);
+                }
+            }
+        }
+
+        Ok(results)
+    }
+
+    async fn install(&self, packages: &[Package]) -> Result<Vec<InstallResult>> {
+        let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+        if !names.is_empty() {
+            println!("--> Installing packages with opam...");
+        }
+        self.run_opam("install", &names)
+    }
+
+    async fn remove(&self, packages: &[&str]) -> Result<Vec<InstallResult>> {
+        if !packages.is_empty() {
+            println!("--> Removing packages with opam...");
+        }
+        self.run_opam("remove", packages)
+    }
+
+    async fn update(&self, packages: &[Package]) -> Result<Vec<InstallResult>> {
+        let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+        self.run_opam("upgrade", &names)
+    }
+
+    fn is_installed(&self, package: &str) -> Result<bool> {
+        Ok(self
+            .list_installed()?
+            .iter()
+            .any(|(name, _)| name == package))
+    }
+
+    fn list_installed(&self) -> Result<Vec<(String, String)>> {
+        let stdout = self.list_columns(&["list", "--installed"], "name,installed-version")?;
+        Ok(stdout
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                Some((fields.next()?.to_string(), fields.next()?.to_string()))
+            })
+            .collect())
+    }
+
+    async fn check_updates(&self) -> Result<Vec<Package>> {
+        println!("--> Refreshing opam repositories...");
+        let _ = Command::new("opam")
+            .args(["update", "--quiet"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+
+        let stdout = self.list_columns(
+            &["list", "--installed"],
+            "name,installed-version,available-versions",
+        )?;
+        Ok(parse_available(&stdout))
+    }
+}
+
+/// Parse `--columns=name,version,installed-version,synopsis --short` rows,
+/// where "--" stands for not installed
+fn parse_search(output: &str) -> Vec<Package> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let (name, version, installed) = (fields.next()?, fields.next()?, fields.next()?);
+            let synopsis = fields.collect::<Vec<_>>().join(" ");
+
+            let description = Some(synopsis).filter(|s| !s.is_empty());
+            let mut pkg = Package::new(name, version).with_description(description);
+            pkg.installed = installed != "--";
+            Some(pkg)
+        })
+        .collect()
+}
+
+/// Parse `opam show --normalise --field=...`: the field title, then its value
+/// on the same line. Values of opam file fields keep their opam syntax.
+fn parse_show(output: &str) -> Option<Package> {
+    let mut pkg = Package::new("", "");
+
+    for line in output.lines() {
+        let Some((key, value)) = line.split_once(char::is_whitespace) else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim_end_matches(':') {
+            "name" => pkg.name = unquote(value).to_string(),
+            "version" => pkg.version = unquote(value).to_string(),
+            "synopsis" => pkg.description = Some(unquote(value).to_string()),
+            "homepage" => pkg.url = quoted_strings(value).into_iter().next(),
+            "license" => pkg.extra.license = quoted_strings(value),
+            "maintainer" => pkg.maintainer = Some(quoted_strings(value).join(", ")),
+            "depends" => pkg.extra.depends = quoted_strings(value),
+            _ => {}
+        }
+    }
+
+    pkg.description = pkg.description.filter(|d| !d.is_empty());
+    pkg.maintainer = pkg.maintainer.filter(|m| !m.is_empty());
+    (!pkg.name.is_empty()).then_some(pkg)
+}
+
+/// Parse `--columns=name,installed-version,available-versions` rows into the
+/// installed packages that have a newer version available
+fn parse_available(output: &str) -> Vec<Package> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split([' ', ',']).filter(|f| !f.is_empty());
+            let (name, installed) = (fields.next()?, fields.next()?);
+            let newest = fields.max_by(|a, b| compare_versions(a, b))?;
+            if compare_versions(newest, installed) != Ordering::Greater {
+                return None;
+            }
+            let mut pkg = Package::new(name, newest);
+            pkg.installed = true;
+            Some(pkg)
+        })
+        .collect()
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+/// The strings in an opam value, skipping the version constraints and
+/// filters inside `{ }`: `"ocaml" {>= "4.08"} "dune"` -> ["ocaml", "dune"]
+fn quoted_strings(value: &str) -> Vec<String> {
+    let mut strings: Vec<String> = vec![];
+    let mut depth = 0;
+    let mut current: Option<String> = None;
+
+    for c in value.chars() {
+        match (c, current.as_mut()) {
+            ('"', Some(_)) => {
+                let s = current.take().unwrap_or_default();
+                if depth == 0 && !strings.contains(&s) {
+                    strings.push(s);
+                }
+            }
+            (c, Some(s)) => s.push(c),
+            ('"', None) => current = Some(String::new()),
+            ('{', None) => depth += 1,
+            ('}', None) => depth -= 1,
+            _ => {}
+        }
+    }
+    strings
+}
+
+fn command_exists(cmd: &str) -> bool {
+    Command::new("which")
+        .arg(cmd)
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_search() {
+        let output = "ocamlformat 0.26.2 -- Auto-formatter for OCaml code\n\
+                      ocamlformat-lib 0.26.2 0.26.2 OCaml Code Formatter\n\
+                      ocamlformat-rpc 0.21.0 --\n";
+        let packages = parse_search(output);
+        assert_eq!(packages.len(), 3);
+        assert_eq!(packages[0].name, "ocamlformat");
+        assert_eq!(packages[0].version, "0.26.2");
+        assert!(!packages[0].installed);
+        assert_eq!(
+            packages[0].description.as_deref(),
+            Some("Auto-formatter for OCaml code")
+        );
+        assert!(packages[1].installed);
+        assert!(packages[2].description.is_none());
+    }
+
+    #[test]
+    fn test_parse_show() {
+        let output = "name           dune\n\
+                      version        3.16.0\n\
+                      synopsis       Fast, portable, and opinionated build system\n\
+                      homepage:      \"https://github.com/ocaml/dune\"\n\
+                      license:       \"MIT\"\n\
+                      maintainer:    [\"Jane Street Group, LLC <opensource@janestreet.com>\"]\n\
+                      depends:       (\"ocaml\" {>= \"4.08\"} | (\"ocaml\" {< \"4.08~~\"} & \"ocamlfind-secondary\")) \"base-unix\" \"base-threads\"\n";
+        let pkg = parse_show(output).unwrap();
+        assert_eq!(pkg.name, "dune");
+        assert_eq!(pkg.version, "3.16.0");
+        assert_eq!(
+            pkg.description.as_deref(),
+            Some("Fast, portable, and opinionated build system")
+        );
+        assert_eq!(pkg.url.as_deref(), Some("https://github.com/ocaml/dune"));
+        assert_eq!(pkg.extra.license, ["MIT"]);
+        assert_eq!(
+            pkg.maintainer.as_deref(),
+            Some("Jane Street Group, LLC <opensource@janestreet.com>")
+        );
+        assert_eq!(
+            pkg.extra.depends,
+            ["ocaml", "ocamlfind-secondary", "base-unix", "base-threads"]
+        );
+    }
+
+    #[test]
+    fn test_parse_available() {
+        let output = "dune 3.15.3 3.15.0 3.15.2 3.15.3 3.16.0\n\
+                      ocaml 5.1.1 5.1.1\n\
+                      utop 2.14.0 2.13.1, 2.14.0, 2.15.0-1\n";
+        let updates = parse_available(output);
+        assert_eq!(updates.len(), 2);
+        assert_eq!(updates[0].name, "dune");
+        assert_eq!(updates[0].version, "3.16.0");
+        assert_eq!(updates[1].name, "utop");
+        assert_eq!(updates[1].version, "2.15.0-1");
+    }
+}
@@ -1,5 +1,6 @@
 use anyhow::{Context, Result};
 use async_trait::async_trait;
+use std::path::Path;
 use std::process::{Command, Stdio};
 
 use super::sudo;
@@ -232,6 +233,33 @@ impl PackageManager for PacmanBackend {
         Ok(results)
     }
 
+    async fn remove(&self, packages: &[&str]) -> Result<Vec<InstallResult>> {
+        if packages.is_empty() {
+            return Ok(vec![]);
+        }
+
+        println!("--> Removing packages with pacman...");
+
+        let mut args = vec!["pacman", "-Rns", "--noconfirm"];
+        args.extend(packages.iter().copied());
+
+        let status = sudo::run_sudo(&args).context("Failed to run pacman -Rns")?;
+
+        let success = status.success();
+        Ok(packages
+            .iter()
+            .map(|name| InstallResult {
+                package: name.to_string(),
+                success,
+                message: if success {
+                    None
+                } else {
+                    Some("pacman -Rns failed".to_string())
+                },
+            })
+            .collect())
+    }
+
     fn is_installed(&self, package: &str) -> Result<bool> {
         let output = Command::new("pacman")
             .args(["-Q", package])
@@ -290,6 +318,108 @@ impl PackageManager for PacmanBackend {
 
         Ok(updates)
     }
+
+    fn owns(&self, path: &Path) -> Result<Option<(String, String)>> {
+        query_owner(path, "-Qn")
+    }
+
+    async fn provides(&self, target: &str) -> Result<Vec<Package>> {
+        if !files_database_synced() {
+            anyhow::bail!(
+                "the pacman file database is missing; run 'sudo pacman -Fy' to download it"
+            );
+        }
+
+        // A bare command matches file names anywhere; only bin directories are kept
+        let output = Command::new("pacman")
+            .args(["-F", "--machinereadable", target])
+            .output()
+            .context("Failed to run pacman -F")?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let mut packages = parse_file_owners(&stdout, target);
+        for pkg in &mut packages {
+            pkg.installed = self.is_installed(&pkg.name).unwrap_or(false);
+        }
+        Ok(packages)
+    }
+}
+
+/// The package owning `path` in the local pacman database, if `pacman <filter>`
+/// lists it: -Qn for official repo packages, -Qm for foreign (AUR) ones
+pub(crate) fn query_owner(path: &Path, filter: &str) -> Result<Option<(String, String)>> {
+    let output = Command::new("pacman")
+        .arg("-Qo")
+        .arg(path)
+        .env("LC_ALL", "C")
+        .output()
+        .context("Failed to run pacman -Qo")?;
+
+    if !output.status.success() {
+        return Ok(None);
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let Some((name, version)) = parse_owner(&stdout) else {
+        return Ok(None);
+    };
+
+    let listed = Command::new("pacman")
+        .args([filter, name])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()?
+        .success();
+    Ok(listed.then(|| (name.to_string(), version.to_string())))
+}
+
+/// Name and version from `pacman -Qo`: "/usr/bin/rg is owned by ripgrep 14.1.0-1"
+fn parse_owner(output: &str) -> Option<(&str, &str)> {
+    output
+        .trim()
+        .rsplit_once(" is owned by ")
+        .and_then(|(_, owner)| owner.split_once(' '))
+}
+
+/// Whether `pacman -Fy` has downloaded any .files databases
+fn files_database_synced() -> bool {
+    std::fs::read_dir("/var/lib/pacman/sync")
+        .map(|entries| {
+            entries
+                .flatten()
+                .any(|e| e.path().extension().is_some_and(|ext| ext == "files"))
+        })
+        .unwrap_or(false)
+}
+
+/// `pacman -F --machinereadable` prints `repo\0name\0version\0path` per file
+fn parse_file_owners(output: &str, target: &str) -> Vec<Package> {
+    let mut packages: Vec<Package> = vec![];
+
+    for line in output.lines() {
+        let fields: Vec<&str> = line.split('\0').collect();
+        let [repo, name, version, path] = fields[..] else {
+            continue;
+        };
+        if !super::provides_match(path, target) || packages.iter().any(|p| p.name == name) {
+            continue;
+        }
+        packages.push(Package {
+            name: name.to_string(),
+            version: version.to_string(),
+            description: None,
+            popularity: 0.0,
+            installed: false,
+            maintainer: None,
+            url: None,
+            extra: PackageExtra {
+                apt_section: Some(repo.to_string()), // Reuse apt_section for repo
+                ..Default::default()
+            },
+        });
+    }
+
+    packages
 }
 
 fn command_exists(cmd: &str) -> bool {
@@ -299,3 +429,51 @@ fn command_exists(cmd: &str) -> bool {
         .map(|o| o.status.success())
         .unwrap_or(false)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_owner() {
+        assert_eq!(
+            parse_owner("/usr/bin/rg is owned by ripgrep 14.1.0-1\n"),
+            Some(("ripgrep", "14.1.0-1"))
+        );
+        assert_eq!(
+            parse_owner("/usr/bin/is owned by x is owned by python-foo 1:2.0-3\n"),
+            Some(("python-foo", "1:2.0-3"))
+        );
+        assert_eq!(parse_owner("error: No package owns /usr/bin/rg\n"), None);
+    }
+
+    #[test]
+    fn test_parse_file_owners() {
+        let output = [
+            ["extra", "ripgrep", "14.1.0-1", "usr/bin/rg"],
+            [
+                "extra",
+                "ripgrep",
+                "14.1.0-1",
+                "usr/share/zsh/site-functions/rg",
+            ],
+            [
+                "extra",
+                "fish",
+                "3.7.1-2",
+                "usr/share/fish/completions/rg.fish",
+            ],
+        ]
+        .map(|fields| fields.join("\0"))
+        .join("\n");
+        let output = output.as_str();
+        let packages = parse_file_owners(output, "rg");
+        assert_eq!(packages.len(), 1);
+        assert_eq!(packages[0].name, "ripgrep");
+        assert_eq!(packages[0].version, "14.1.0-1");
+        assert_eq!(packages[0].extra.apt_section.as_deref(), Some("extra"));
+
+        assert_eq!(parse_file_owners(output, "/usr/bin/rg").len(), 1);
+        assert!(parse_file_owners(output, "/usr/bin/grep").is_empty());
+    }
+}
@@ -1,6 +1,8 @@
 use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
 use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 
 use super::{
@@ -8,6 +10,11 @@ use super::{
     InstallResult, Package, PackageExtra, PackageManager,
 };
 
+/// Why pip can't install into a PEP 668 Python, and what to do instead
+const EXTERNALLY_MANAGED: &str =
+    "this Python is externally managed (PEP 668); activate a virtualenv \
+     (python3 -m venv .venv && . .venv/bin/activate) or install pipx for applications";
+
 /// pip package manager backend for Python packages
 pub struct PipBackend {
     client: reqwest::Client,
@@ -28,6 +35,171 @@ struct PyPIInfo {
     home_page: Option<String>,
     project_url: Option<String>,
     license: Option<String>,
+    #[serde(default)]
+    classifiers: Vec<String>,
+}
+
+/// The Python environment `pip install` would write to
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PythonEnv {
+    /// An activated virtualenv ($VIRTUAL_ENV)
+    Virtualenv(PathBuf),
+    /// An activated conda environment ($CONDA_PREFIX)
+    Conda(PathBuf),
+    /// A distro Python that refuses pip installs (PEP 668 EXTERNALLY-MANAGED marker)
+    ExternallyManaged,
+    /// A Python project directory (requirements.txt or pyproject.toml)
+    Project,
+    /// A Python that allows `pip install --user`
+    User,
+}
+
+impl PythonEnv {
+    fn detect() -> Self {
+        if let Some(venv) = std::env::var_os("VIRTUAL_ENV") {
+            return Self::Virtualenv(PathBuf::from(venv));
+        }
+        if let Some(prefix) = std::env::var_os("CONDA_PREFIX") {
+            return Self::Conda(PathBuf::from(prefix));
+        }
+        if is_externally_managed() {
+            return Self::ExternallyManaged;
+        }
+        if Path::new("requirements.txt").exists() || Path::new("pyproject.toml").exists() {
+            return Self::Project;
+        }
+        Self::User
+    }
+
+    /// Whether packages are dependencies of an environment or project rather
+    /// than tools for the user
+    fn is_local(&self) -> bool {
+        matches!(self, Self::Virtualenv(_) | Self::Conda(_) | Self::Project)
+    }
+
+    /// Extra pip arguments to stay within this environment
+    fn scope_args(&self) -> &'static [&'static str] {
+        if self.is_local() {
+            &[]
+        } else {
+            &["--user"]
+        }
+    }
+}
+
+/// Outcome of installing a package as an application
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AppInstall {
+    Installed,
+    Failed,
+    /// The package has no console scripts, so it is a library
+    NoApps,
+}
+
+/// Tools that install Python applications into their own isolated environments
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AppInstaller {
+    Pipx,
+    Uv,
+}
+
+impl AppInstaller {
+    fn detect() -> Option<Self> {
+        if command_exists("pipx") {
+            Some(Self::Pipx)
+        } else if command_exists("uv") {
+            Some(Self::Uv)
+        } else {
+            None
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Pipx => "pipx",
+            Self::Uv => "uv tool",
+        }
+    }
+
+    fn command(self, action: &str, package: &str) -> Command {
+        let mut cmd = match self {
+            Self::Pipx => Command::new("pipx"),
+            Self::Uv => {
+                let mut cmd = Command::new("uv");
+                cmd.arg("tool");
+                cmd
+            }
+        };
+        cmd.args([action, package]);
+        cmd
+    }
+
+    /// Install `package` as an application. Errors are shown unless the
+    /// package turns out to have no apps to install.
+    fn install(self, package: &str) -> Result<AppInstall> {
+        let output = self
+            .command("install", package)
+            .stdin(Stdio::inherit())
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::piped())
+            .output()
+            .with_context(|| format!("Failed to run {}", self.name()))?;
+
+        if output.status.success() {
+            return Ok(AppInstall::Installed);
+        }
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if is_no_apps_error(&stderr) {
+            return Ok(AppInstall::NoApps);
+        }
+        eprint!("{}", stderr);
+        Ok(AppInstall::Failed)
+    }
+
+    /// Applications this tool manages, with their versions
+    fn list(self) -> Vec<(String, String)> {
+        match self {
+            Self::Pipx => {
+                let output = Command::new("pipx").args(["list", "--json"]).output();
+                match output {
+                    Ok(o) if o.status.success() => {
+                        parse_pipx_list(&String::from_utf8_lossy(&o.stdout))
+                    }
+                    _ => vec![],
+                }
+            }
+            Self::Uv => {
+                let output = Command::new("uv").args(["tool", "list"]).output();
+                match output {
+                    Ok(o) if o.status.success() => {
+                        parse_uv_tool_list(&String::from_utf8_lossy(&o.stdout))
+                    }
+                    _ => vec![],
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PipxList {
+    venvs: HashMap<String, PipxVenv>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PipxVenv {
+    metadata: PipxMetadata,
+}
+
+#[derive(Debug, Deserialize)]
+struct PipxMetadata {
+    main_package: PipxPackage,
+}
+
+#[derive(Debug, Deserialize)]
+struct PipxPackage {
+    package: String,
+    package_version: String,
 }
 
 impl PipBackend {
@@ -77,6 +249,7 @@ impl PipBackend {
                         url: result.info.home_page.or(result.info.project_url),
                         extra: PackageExtra {
                             license: result.info.license.map(|l| vec![l]).unwrap_or_default(),
+                            categories: result.info.classifiers,
                             ..Default::default()
                         },
                     }]);
@@ -89,6 +262,38 @@ impl PipBackend {
         Ok(vec![])
     }
 
+    /// Applications installed through pipx or uv tool
+    fn list_apps() -> Vec<(String, String, AppInstaller)> {
+        let mut apps = vec![];
+        for installer in [AppInstaller::Pipx, AppInstaller::Uv] {
+            let available = match installer {
+                AppInstaller::Pipx => command_exists("pipx"),
+                AppInstaller::Uv => command_exists("uv"),
+            };
+            if !available {
+                continue;
+            }
+            for (name, version) in installer.list() {
+                apps.push((name, version, installer));
+            }
+        }
+        apps
+    }
+
+    fn pip_install(&self, env: &PythonEnv, args: &[&str], packages: &[&str]) -> Result<bool> {
+        let status = Command::new(Self::get_pip_cmd())
+            .arg("install")
+            .args(env.scope_args())
+            .args(args)
+            .args(packages)
+            .stdin(Stdio::inherit())
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::inherit())
+            .status()
+            .context("Failed to run pip install")?;
+        Ok(status.success())
+    }
+
     fn parse_pip_list(&self, output: &str) -> Vec<(String, String)> {
         let mut packages = vec![];
 
@@ -152,6 +357,8 @@ impl PackageManager for PipBackend {
                             url: result.info.home_page.or(result.info.project_url),
                             extra: PackageExtra {
                                 license: result.info.license.map(|l| vec![l]).unwrap_or_default(),
+                                categories: result.info.classifiers,
+                                created_at: result.releases.as_ref().and_then(first_upload),
                                 ..Default::default()
                             },
                         };
@@ -167,55 +374,152 @@ impl PackageManager for PipBackend {
 
     async fn install(&self, packages: &[Package]) -> Result<Vec<InstallResult>> {
         let mut results = vec![];
-        let pkg_names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
-
-        if pkg_names.is_empty() {
+        if packages.is_empty() {
             return Ok(results);
         }
 
-        // Check if we're in a project directory (has requirements.txt or pyproject.toml)
-        let is_project = std::path::Path::new("requirements.txt").exists() 
-            || std::path::Path::new("pyproject.toml").exists();
-        
-        let pip_cmd = Self::get_pip_cmd();
-        
-        let status = if is_project {
-            println!("--> Installing packages locally (project dependencies)...");
-            // Install to current directory or virtual environment
-            Command::new(pip_cmd)
-                .args(["install"])
-                .args(&pkg_names)
-                .stdin(Stdio::inherit())
-                .stdout(Stdio::inherit())
-                .stderr(Stdio::inherit())
-                .status()
-                .context("Failed to run pip install")?
+        let env = PythonEnv::detect();
+
+        // Inside an environment or project everything is a dependency of it
+        let app_installer = if env.is_local() {
+            None
         } else {
-            println!("--> Installing packages locally (user directory)...");
-            // Install to user directory (not system-wide)
-            Command::new(pip_cmd)
-                .args(["install", "--user"])
-                .args(&pkg_names)
+            AppInstaller::detect()
+        };
+
+        // Elsewhere packages with console scripts get their own environment
+        // via pipx/uv tool, and the rest are libraries for pip
+        let mut libraries = vec![];
+        match app_installer {
+            Some(installer) => {
+                for pkg in packages {
+                    println!("--> Installing {} with {}...", pkg.name, installer.name());
+                    let (success, message) = match installer.install(&pkg.spec("=="))? {
+                        AppInstall::Installed => (true, None),
+                        AppInstall::Failed => {
+                            (false, Some(format!("{} install failed", installer.name())))
+                        }
+                        AppInstall::NoApps => {
+                            println!(
+                                "--> {} has no applications, installing it with pip",
+                                pkg.name
+                            );
+                            libraries.push(pkg);
+                            continue;
+                        }
+                    };
+                    results.push(InstallResult {
+                        package: pkg.name.clone(),
+                        success,
+                        message,
+                    });
+                }
+            }
+            None => libraries.extend(packages),
+        }
+
+        if libraries.is_empty() {
+            return Ok(results);
+        }
+
+        let (success, message) = match &env {
+            PythonEnv::ExternallyManaged => (false, Some(EXTERNALLY_MANAGED.to_string())),
+            env => {
+                match env {
+                    PythonEnv::Virtualenv(path) | PythonEnv::Conda(path) => {
+                        println!("--> Installing packages into {}...", path.display())
+                    }
+                    PythonEnv::Project => {
+                        println!("--> Installing packages locally (project dependencies)...")
+                    }
+                    _ => println!("--> Installing packages locally (user directory)..."),
+                }
+
+                let specs: Vec<String> = libraries.iter().map(|p| p.spec("==")).collect();
+                let specs: Vec<&str> = specs.iter().map(|s| s.as_str()).collect();
+                let success = self.pip_install(env, &[], &specs)?;
+                (
+                    success,
+                    (!success).then(|| "pip install failed".to_string()),
+                )
+            }
+        };
+
+        for pkg in libraries {
+            results.push(InstallResult {
+                package: pkg.name.clone(),
+                success,
+                message: message.clone(),
+            });
+        }
+
+        Ok(results)
+    }
+
+    async fn remove(&self, packages: &[&str]) -> Result<Vec<InstallResult>> {
+        let mut results = vec![];
+        if packages.is_empty() {
+            return Ok(results);
+        }
+
+        // Applications go back through the tool that installed them
+        let apps = Self::list_apps();
+        let mut libraries = vec![];
+        for name in packages {
+            let Some((_, _, installer)) = apps
+                .iter()
+                .find(|(app, _, _)| app.eq_ignore_ascii_case(name))
+            else {
+                libraries.push(*name);
+                continue;
+            };
+
+            println!(
+                "--> Removing application {} with {}...",
+                name,
+                installer.name()
+            );
+            let status = installer
+                .command("uninstall", name)
                 .stdin(Stdio::inherit())
                 .stdout(Stdio::inherit())
                 .stderr(Stdio::inherit())
                 .status()
-                .context("Failed to run pip install")?
-        };
+                .with_context(|| format!("Failed to run {}", installer.name()))?;
 
-        let success = status.success();
-        for pkg in packages {
             results.push(InstallResult {
-                package: pkg.name.clone(),
-                success,
-                message: if success {
+                package: name.to_string(),
+                success: status.success(),
+                message: if status.success() {
                     None
                 } else {
-                    Some("pip install failed".to_string())
+                    Some(format!("{} uninstall failed", installer.name()))
                 },
             });
         }
 
+        if libraries.is_empty() {
+            return Ok(results);
+        }
+
+        println!("--> Removing packages with pip...");
+        let status = Command::new(Self::get_pip_cmd())
+            .args(["uninstall", "-y"])
+            .args(&libraries)
+            .stdin(Stdio::inherit())
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::inherit())
+            .status()
+            .context("Failed to run pip uninstall")?;
+
+        for name in libraries {
+            results.push(InstallResult {
+                package: name.to_string(),
+                success: status.success(),
+                message: (!status.success()).then(|| "pip uninstall failed".to_string()),
+            });
+        }
+
         Ok(results)
     }
 
@@ -230,43 +534,81 @@ impl PackageManager for PipBackend {
         Ok(output.success())
     }
 
+    fn owns(&self, path: &Path) -> Result<Option<(String, String)>> {
+        // pipx and uv link executables to their venvs: pipx/venvs/<app>/bin, uv/tools/<app>/bin
+        let Some(app) = path
+            .canonicalize()
+            .ok()
+            .and_then(|target| tool_venv_app(&target))
+        else {
+            return Ok(None);
+        };
+
+        let version = Self::list_apps()
+            .into_iter()
+            .find(|(name, _, _)| name.eq_ignore_ascii_case(&app))
+            .map(|(_, version, _)| version)
+            .unwrap_or_default();
+        Ok(Some((app, version)))
+    }
+
     fn list_installed(&self) -> Result<Vec<(String, String)>> {
+        let env = PythonEnv::detect();
         let pip_cmd = Self::get_pip_cmd();
         let output = Command::new(pip_cmd)
             .args(["list", "--format=columns"])
+            .args(env.scope_args())
             .output()?;
 
-        if !output.status.success() {
-            return Ok(vec![]);
+        let mut packages = if output.status.success() {
+            self.parse_pip_list(&String::from_utf8_lossy(&output.stdout))
+        } else {
+            vec![]
+        };
+
+        for (name, version, _) in Self::list_apps() {
+            if !packages.iter().any(|(n, _)| n == &name) {
+                packages.push((name, version));
+            }
         }
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        Ok(self.parse_pip_list(&stdout))
+        Ok(packages)
     }
 
     async fn check_updates(&self) -> Result<Vec<Package>> {
+        let env = PythonEnv::detect();
         let pip_cmd = Self::get_pip_cmd();
         let output = Command::new(pip_cmd)
             .args(["list", "--outdated", "--format=columns"])
+            .args(env.scope_args())
             .output()
             .context("Failed to check for updates")?;
 
-        if !output.status.success() {
-            return Ok(vec![]);
-        }
-
-        let stdout = String::from_utf8_lossy(&output.stdout);
         let mut updates = vec![];
 
-        for line in stdout.lines() {
-            if line.starts_with("Package") || line.starts_with("-") || line.is_empty() {
-                continue;
+        if output.status.success() {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            for line in stdout.lines() {
+                if line.starts_with("Package") || line.starts_with("-") || line.is_empty() {
+                    continue;
+                }
+
+                let parts: Vec<_> = line.split_whitespace().collect();
+                if let Some(name) = parts.first() {
+                    if let Ok(info_results) = self.info(&[name]).await {
+                        if let Some(pkg) = info_results.into_iter().next() {
+                            updates.push(pkg);
+                        }
+                    }
+                }
             }
+        }
 
-            let parts: Vec<_> = line.split_whitespace().collect();
-            if let Some(name) = parts.first() {
-                if let Ok(info_results) = self.info(&[name]).await {
-                    if let Some(pkg) = info_results.into_iter().next() {
+        // pipx/uv apps live in their own venvs, so compare against PyPI directly
+        for (name, version, _) in Self::list_apps() {
+            if let Ok(info_results) = self.info(&[&name]).await {
+                if let Some(pkg) = info_results.into_iter().next() {
+                    if pkg.version != version && !updates.iter().any(|u| u.name == pkg.name) {
                         updates.push(pkg);
                     }
                 }
@@ -278,64 +620,64 @@ impl PackageManager for PipBackend {
 
     async fn update(&self, packages: &[Package]) -> Result<Vec<InstallResult>> {
         let mut results = vec![];
-        let pip_cmd = Self::get_pip_cmd();
+        let env = PythonEnv::detect();
 
-        if packages.is_empty() {
-            // Update all outdated packages
+        let packages = if packages.is_empty() {
             println!("--> Updating all pip packages...");
+            self.check_updates().await?
+        } else {
+            packages.to_vec()
+        };
 
-            let output = Command::new(pip_cmd)
-                .args(["list", "--outdated", "--format=freeze"])
-                .output()?;
-
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            let pkgs: Vec<&str> = stdout
-                .lines()
-                .filter_map(|l| l.split("==").next())
-                .collect();
-
-            if !pkgs.is_empty() {
-                let status = Command::new(pip_cmd)
-                    .args(["install", "--user", "--upgrade"])
-                    .args(&pkgs)
-                    .stdin(Stdio::inherit())
-                    .stdout(Stdio::inherit())
-                    .stderr(Stdio::inherit())
-                    .status()
-                    .context("Failed to run pip upgrade")?;
+        let apps = Self::list_apps();
+        let mut pip_packages = vec![];
 
-                results.push(InstallResult {
-                    package: "all".to_string(),
-                    success: status.success(),
-                    message: if status.success() {
-                        None
-                    } else {
-                        Some("pip upgrade failed".to_string())
-                    },
-                });
-            }
-        } else {
-            let pkg_names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+        for pkg in &packages {
+            let app = apps
+                .iter()
+                .find(|(name, _, _)| name.eq_ignore_ascii_case(&pkg.name));
+            let Some((_, _, installer)) = app else {
+                pip_packages.push(pkg);
+                continue;
+            };
 
-            let status = Command::new(pip_cmd)
-                .args(["install", "--user", "--upgrade"])
-                .args(&pkg_names)
+            println!("--> Upgrading {} with {}...", pkg.name, installer.name());
+            let status = installer
+                .command("upgrade", &pkg.name)
                 .stdin(Stdio::inherit())
                 .stdout(Stdio::inherit())
                 .stderr(Stdio::inherit())
                 .status()
-                .context("Failed to run pip upgrade")?;
+                .with_context(|| format!("Failed to run {}", installer.name()))?;
 
-            let success = status.success();
-            for pkg in packages {
+            results.push(InstallResult {
+                package: pkg.name.clone(),
+                success: status.success(),
+                message: if status.success() {
+                    None
+                } else {
+                    Some(format!("{} upgrade failed", installer.name()))
+                },
+            });
+        }
+
+        if !pip_packages.is_empty() {
+            let (success, message) = if env == PythonEnv::ExternallyManaged {
+                (false, Some(EXTERNALLY_MANAGED.to_string()))
+            } else {
+                let pkg_names: Vec<&str> = pip_packages.iter().map(|p| p.name.as_str()).collect();
+                let success = self.pip_install(&env, &["--upgrade"], &pkg_names)?;
+                (
+                    success,
+                    (!success).then(|| "pip upgrade failed".to_string()),
+                )
+            };
+
+            for pkg in pip_packages {
                 results.push(InstallResult {
                     package: pkg.name.clone(),
                     success,
-                    message: if success {
-                        None
-                    } else {
-                        Some("pip upgrade failed".to_string())
-                    },
+                    message: message.clone(),
                 });
             }
         }
@@ -344,6 +686,94 @@ impl PackageManager for PipBackend {
     }
 }
 
+/// pipx and uv tool refuse packages without console scripts with these errors
+fn is_no_apps_error(stderr: &str) -> bool {
+    stderr.contains("No apps associated with package")
+        || stderr.contains("No executables are provided by package")
+}
+
+/// PEP 668: the interpreter's stdlib directory contains an EXTERNALLY-MANAGED file
+fn is_externally_managed() -> bool {
+    let Some(python) = detect_python_command() else {
+        return false;
+    };
+    let output = Command::new(python)
+        .args([
+            "-c",
+            "import sysconfig; print(sysconfig.get_path('stdlib'))",
+        ])
+        .output();
+
+    match output {
+        Ok(o) if o.status.success() => {
+            let stdlib = String::from_utf8_lossy(&o.stdout).trim().to_string();
+            Path::new(&stdlib).join("EXTERNALLY-MANAGED").exists()
+        }
+        _ => false,
+    }
+}
+
+/// The app whose pipx or uv tool venv a file is in:
+/// `pipx/venvs/<app>/...` or `uv/tools/<app>/...`
+fn tool_venv_app(target: &Path) -> Option<String> {
+    let components: Vec<String> = target
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+    components
+        .windows(3)
+        .find_map(|w| match (w[0].as_str(), w[1].as_str()) {
+            ("pipx", "venvs") | ("uv", "tools") => Some(w[2].clone()),
+            _ => None,
+        })
+}
+
+/// Parse `pipx list --json`
+fn parse_pipx_list(output: &str) -> Vec<(String, String)> {
+    let Ok(list) = serde_json::from_str::<PipxList>(output) else {
+        return vec![];
+    };
+    let mut apps: Vec<(String, String)> = list
+        .venvs
+        .into_values()
+        .map(|v| {
+            (
+                v.metadata.main_package.package,
+                v.metadata.main_package.package_version,
+            )
+        })
+        .collect();
+    apps.sort();
+    apps
+}
+
+/// Parse `uv tool list`: "name vX.Y.Z" lines followed by "- executable" lines
+fn parse_uv_tool_list(output: &str) -> Vec<(String, String)> {
+    output
+        .lines()
+        .filter(|line| !line.starts_with('-') && !line.trim().is_empty())
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let name = parts.next()?;
+            let version = parts.next()?.trim_start_matches('v');
+            Some((name.to_string(), version.to_string()))
+        })
+        .collect()
+}
+
+/// Time of the earliest file upload across all releases
+fn first_upload(releases: &serde_json::Value) -> Option<i64> {
+    releases
+        .as_object()?
+        .values()
+        .filter_map(|files| files.as_array())
+        .flatten()
+        .filter_map(|file| file.get("upload_time_iso_8601")?.as_str())
+        .filter_map(|time| chrono::DateTime::parse_from_rfc3339(time).ok())
+        .map(|time| time.timestamp())
+        .min()
+}
+
 fn command_exists(cmd: &str) -> bool {
     #[cfg(target_os = "windows")]
     {
@@ -393,3 +823,73 @@ fn install_pip_with_python() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pipx_list() {
+        let output = r#"{
+            "pipx_spec_version": "0.1",
+            "venvs": {
+                "ruff": {"metadata": {"main_package": {"package": "ruff", "package_version": "0.4.4", "apps": ["ruff"]}, "python_version": "Python 3.12.3"}},
+                "black": {"metadata": {"main_package": {"package": "black", "package_version": "24.1.0", "apps": ["black", "blackd"]}, "python_version": "Python 3.12.3"}}
+            }
+        }"#;
+        assert_eq!(
+            parse_pipx_list(output),
+            [
+                ("black".to_string(), "24.1.0".to_string()),
+                ("ruff".to_string(), "0.4.4".to_string()),
+            ]
+        );
+        assert!(parse_pipx_list("not json").is_empty());
+    }
+
+    #[test]
+    fn test_parse_uv_tool_list() {
+        let output = "black v24.1.0\n- black\n- blackd\nruff v0.4.4\n- ruff\n";
+        assert_eq!(
+            parse_uv_tool_list(output),
+            [
+                ("black".to_string(), "24.1.0".to_string()),
+                ("ruff".to_string(), "0.4.4".to_string()),
+            ]
+        );
+        assert!(parse_uv_tool_list("").is_empty());
+    }
+
+    #[test]
+    fn test_is_no_apps_error() {
+        assert!(is_no_apps_error(
+            "No apps associated with package requests. Try again with '--include-deps' to include apps of dependent packages"
+        ));
+        assert!(is_no_apps_error(
+            "error: No executables are provided by package `requests`; removing tool"
+        ));
+        assert!(!is_no_apps_error(
+            "ERROR: Could not find a version that satisfies the requirement nope"
+        ));
+    }
+
+    #[test]
+    fn test_tool_venv_app() {
+        assert_eq!(
+            tool_venv_app(Path::new(
+                "/home/me/.local/share/pipx/venvs/black/bin/black"
+            ))
+            .as_deref(),
+            Some("black")
+        );
+        assert_eq!(
+            tool_venv_app(Path::new("/home/me/.local/share/uv/tools/ruff/bin/ruff")).as_deref(),
+            Some("ruff")
+        );
+        assert_eq!(tool_venv_app(Path::new("/usr/bin/python3")), None);
+        assert_eq!(
+            tool_venv_app(Path::new("/home/me/.local/share/pipx/venvs")),
+            None
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use anyhow::{Context, Result};
 use async_trait::async_trait;
+use std::path::Path;
 use std::process::{Command, Stdio};
 
 use super::sudo;
@@ -235,6 +236,33 @@ impl PackageManager for PkgBackend {
         Ok(results)
     }
 
+    async fn remove(&self, packages: &[&str]) -> Result<Vec<InstallResult>> {
+        if packages.is_empty() {
+            return Ok(vec![]);
+        }
+
+        println!("--> Removing packages with pkg...");
+
+        let mut args = vec!["pkg", "delete", "-y"];
+        args.extend(packages.iter().copied());
+
+        let status = sudo::run_sudo(&args).context("Failed to run pkg delete")?;
+
+        let success = status.success();
+        Ok(packages
+            .iter()
+            .map(|name| InstallResult {
+                package: name.to_string(),
+                success,
+                message: if success {
+                    None
+                } else {
+                    Some("pkg delete failed".to_string())
+                },
+            })
+            .collect())
+    }
+
     fn is_installed(&self, package: &str) -> Result<bool> {
         let output = Command::new("pkg")
             .args(["info", package])
@@ -295,6 +323,65 @@ impl PackageManager for PkgBackend {
 
         Ok(updates)
     }
+
+    fn owns(&self, path: &Path) -> Result<Option<(String, String)>> {
+        let output = Command::new("pkg")
+            .args(["which", "-q"])
+            .arg(path)
+            .output()
+            .context("Failed to run pkg which")?;
+
+        if !output.status.success() {
+            return Ok(None);
+        }
+
+        Ok(parse_pkg_which(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    async fn provides(&self, target: &str) -> Result<Vec<Package>> {
+        let output = Command::new("pkg")
+            .args(["provides", &super::provides_pattern(target)])
+            .output()
+            .context("Failed to run pkg provides")?;
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("unknown command") {
+            anyhow::bail!("pkg provides needs the pkg-provides plugin; run 'sudo pkg install pkg-provides && sudo pkg provides -u'");
+        }
+
+        // Blocks of "Name    : ripgrep-14.1.0" ... "Filename: usr/local/bin/rg",
+        // with further file names on indented lines
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let mut current = "";
+        let mut names: Vec<&str> = vec![];
+        for line in stdout.lines() {
+            let (key, value) = match line.split_once(':') {
+                Some((key, value)) if !line.starts_with(' ') => (key.trim(), value.trim()),
+                _ => ("Filename", line.trim()),
+            };
+            match key {
+                "Name" => current = value.rsplit_once('-').map(|(n, _)| n).unwrap_or(value),
+                "Filename"
+                    if super::provides_match(value, target)
+                        && !current.is_empty()
+                        && !names.contains(&current) =>
+                {
+                    names.push(current);
+                }
+                _ => {}
+            }
+        }
+
+        self.info(&names).await
+    }
+}
+
+/// Name and version from `pkg which -q`: "ripgrep-14.1.0"
+fn parse_pkg_which(output: &str) -> Option<(String, String)> {
+    output
+        .trim()
+        .rsplit_once('-')
+        .map(|(name, version)| (name.to_string(), version.to_string()))
 }
 
 fn command_exists(cmd: &str) -> bool {
@@ -304,3 +391,21 @@ fn command_exists(cmd: &str) -> bool {
         .map(|o| o.status.success())
         .unwrap_or(false)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pkg_which() {
+        assert_eq!(
+            parse_pkg_which("ripgrep-14.1.0\n"),
+            Some(("ripgrep".to_string(), "14.1.0".to_string()))
+        );
+        assert_eq!(
+            parse_pkg_which("py311-black-24.4.2_1\n"),
+            Some(("py311-black".to_string(), "24.4.2_1".to_string()))
+        );
+        assert_eq!(parse_pkg_which(""), None);
+    }
+}
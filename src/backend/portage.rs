@@ -0,0 +1,547 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use super::sudo;
+use super::{InstallResult, Package, PackageManager};
+
+/// Metadata keys asked of `portageq metadata`, one value per output line
+const METADATA_KEYS: [&str; 5] = ["DESCRIPTION", "HOMEPAGE", "LICENSE", "RDEPEND", "IUSE"];
+
+/// Portage (emerge) backend for Gentoo
+pub struct PortageBackend;
+
+impl PortageBackend {
+    pub fn new() -> Result<Self> {
+        if !command_exists("emerge") {
+            anyhow::bail!("emerge is not available on this system");
+        }
+        Ok(Self)
+    }
+
+    fn portageq(&self, args: &[&str]) -> Option<String> {
+        let output = Command::new("portageq").args(args).output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    /// USE flags of a package version, disabled ones prefixed with "-"
+    fn use_flags(&self, cpv: &str, iuse: &str) -> Vec<String> {
+        if command_exists("equery") {
+            if let Ok(output) = Command::new("equery")
+                .args(["-q", "-C", "uses", &format!("={}", cpv)])
+                .output()
+            {
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                let flags: Vec<String> = stdout
+                    .lines()
+                    .map(|l| l.trim().trim_start_matches('+'))
+                    .filter(|l| !l.is_empty())
+                    .map(str::to_string)
+                    .collect();
+                if output.status.success() && !flags.is_empty() {
+                    return flags;
+                }
+            }
+        }
+
+        // Without gentoolkit, fall back to the ebuild's IUSE defaults ("+ssl" is on by default)
+        iuse.split_whitespace()
+            .map(|flag| match flag.strip_prefix('+') {
+                Some(on) => on.to_string(),
+                None => format!("-{}", flag.trim_start_matches('-')),
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl PackageManager for PortageBackend {
+    fn name(&self) -> &str {
+        "Portage (Gentoo)"
+    }
+
+    fn id(&self) -> &str {
+        "portage"
+    }
+
+    async fn search(&self, query: &str) -> Result<Vec<Package>> {
+        if query.len() < 2 {
+            return Ok(vec![]);
+        }
+
+        let mut packages = if command_exists("eix") {
+            let output = Command::new("eix")
+                .args(["--compact", "--nocolor", query])
+                .output()
+                .context("Failed to run eix")?;
+            parse_eix(&String::from_utf8_lossy(&output.stdout))
+        } else {
+            let output = Command::new("emerge")
+                .args(["--search", "--color=n", query])
+                .output()
+                .context("Failed to run emerge --search")?;
+            parse_emerge_search(&String::from_utf8_lossy(&output.stdout))
+        };
+        packages.truncate(30);
+
+        Ok(packages)
+    }
+
+    async fn info(&self, packages: &[&str]) -> Result<Vec<Package>> {
+        let mut results = vec![];
+
+        for atom in packages {
+            let installed = self.portageq(&["best_version", "/", atom]);
+            let visible = self.portageq(&["best_visible", "/", atom]);
+            let Some(cpv) = installed
+                .clone()
+                .filter(|cpv| !cpv.is_empty())
+                .or(visible)
+                .filter(|cpv| !cpv.is_empty())
+            else {
+                continue;
+            };
+
+            let tree = if installed.as_deref() == Some(cpv.as_str()) {
+                "installed"
+            } else {
+                "ebuild"
+            };
+            let mut args = vec!["metadata", "/", tree, cpv.as_str()];
+            args.extend(METADATA_KEYS);
+            let metadata = self.portageq(&args).unwrap_or_default();
+
+            let (name, version) = split_cpv(&cpv);
+            let mut pkg = Package::new(name, version);
+            let mut values = metadata.lines();
+            let mut next = || values.next().unwrap_or_default().trim();
+            let (description, homepage, license, rdepend, iuse) =
+                (next(), next(), next(), next(), next());
+
+            pkg.description = Some(description.to_string()).filter(|d| !d.is_empty());
+            pkg.url = homepage.split_whitespace().next().map(str::to_string);
+            pkg.extra.license = license_names(license);
+            pkg.extra.depends = dependency_atoms(rdepend);
+            pkg.extra.use_flags = self.use_flags(&cpv, iuse);
+            pkg.installed = tree == "installed";
+            results.push(pkg);
+        }
+
+        Ok(results)
+    }
+
+    async fn install(&self, packages: &[Package]) -> Result<Vec<InstallResult>> {
+        let mut results = vec![];
+        let pkg_names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+
+        if pkg_names.is_empty() {
+            return Ok(results);
+        }
+
+        println!("--> Installing packages with emerge...");
+
+        let mut args = vec!["emerge", "--ask=n", "--verbose"];
+        args.extend(pkg_names.iter().copied());
+
+        let status = sudo::run_sudo(&args).context("Failed to run emerge")?;
+
+        let success = status.success();
+        for pkg in packages {
+            results.push(InstallResult {
+                package: pkg.name.clone(),
+                success,
+                message: if success {
+                    None
+                } else {
+                    Some("emerge failed".to_string())
+                },
+            });
+        }
+
+        Ok(results)
+    }
+
+    async fn remove(&self, packages: &[&str]) -> Result<Vec<InstallResult>> {
+        if packages.is_empty() {
+            return Ok(vec![]);
+        }
+
+        println!("--> Removing packages with emerge --depclean...");
+
+        // --depclean keeps everything in @world, which is where emerge put them
+        let mut deselect = vec!["emerge", "--ask=n", "--deselect"];
+        deselect.extend(packages.iter().copied());
+        let status = sudo::run_sudo(&deselect).context("Failed to run emerge --deselect")?;
+        if !status.success() {
+            anyhow::bail!("emerge --deselect failed");
+        }
+
+        // --depclean refuses to remove packages something else still needs
+        let mut args = vec!["emerge", "--ask=n", "--depclean"];
+        args.extend(packages.iter().copied());
+        let status = sudo::run_sudo(&args).context("Failed to run emerge --depclean")?;
+
+        let kept: Vec<&str> = packages
+            .iter()
+            .copied()
+            .filter(|name| self.is_installed(name).unwrap_or(false))
+            .collect();
+        if !kept.is_empty() {
+            // Put them back in @world so they are not dropped by a later --depclean
+            let mut reselect = vec!["emerge", "--ask=n", "--noreplace"];
+            reselect.extend(kept.iter().copied());
+            let _ = sudo::run_sudo(&reselect);
+        }
+
+        Ok(packages
+            .iter()
+            .map(|name| {
+                let removed = status.success() && !kept.contains(name);
+                InstallResult {
+                    package: name.to_string(),
+                    success: removed,
+                    message: if removed {
+                        None
+                    } else if kept.contains(name) {
+                        Some("not removed: other packages depend on it".to_string())
+                    } else {
+                        Some("emerge --depclean failed".to_string())
+                    },
+                }
+            })
+            .collect())
+    }
+
+    fn is_installed(&self, package: &str) -> Result<bool> {
+        let status = Command::new("portageq")
+            .args(["has_version", "/", package])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()?;
+
+        Ok(status.success())
+    }
+
+    fn list_installed(&self) -> Result<Vec<(String, String)>> {
+        let output = Command::new("qlist")
+            .args(["-IRv"])
+            .output()
+            .context("Failed to run qlist (install app-portage/portage-utils)")?;
+
+        if !output.status.success() {
+            return Ok(vec![]);
+        }
+
+        // "sys-apps/ripgrep-14.1.0::gentoo"
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout
+            .lines()
+            .filter_map(|line| line.split("::").next())
+            .filter(|cpv| !cpv.trim().is_empty())
+            .map(|cpv| {
+                let (name, version) = split_cpv(cpv.trim());
+                (name.to_string(), version.to_string())
+            })
+            .collect())
+    }
+
+    async fn check_updates(&self) -> Result<Vec<Package>> {
+        println!("--> Calculating @world updates...");
+
+        let output = Command::new("emerge")
+            .args(["-puvDN", "--color=n", "@world"])
+            .output()
+            .context("Failed to run emerge -puvDN @world")?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(parse_pretend(&stdout))
+    }
+
+    fn owns(&self, path: &Path) -> Result<Option<(String, String)>> {
+        if !command_exists("qfile") {
+            return Ok(None);
+        }
+
+        let output = Command::new("qfile")
+            .args(["-C", "-q", "-v"])
+            .arg(path)
+            .output()
+            .context("Failed to run qfile")?;
+
+        if !output.status.success() {
+            return Ok(None);
+        }
+
+        // "sys-apps/ripgrep-14.1.0"
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout.lines().next().map(|cpv| {
+            let (name, version) = split_cpv(cpv.split("::").next().unwrap_or(cpv).trim());
+            (name.to_string(), version.to_string())
+        }))
+    }
+}
+
+/// Split "sys-apps/ripgrep-14.1.0-r1" into ("sys-apps/ripgrep", "14.1.0-r1"):
+/// the version starts at the first dash followed by a digit after the category
+fn split_cpv(cpv: &str) -> (&str, &str) {
+    let start = cpv.find('/').map(|i| i + 1).unwrap_or(0);
+    let split = cpv[start..]
+        .char_indices()
+        .find(|&(i, c)| {
+            c == '-'
+                && cpv[start + i + 1..]
+                    .chars()
+                    .next()
+                    .is_some_and(|c| c.is_ascii_digit())
+        })
+        .map(|(i, _)| start + i);
+    match split {
+        Some(i) => (&cpv[..i], &cpv[i + 1..]),
+        None => (cpv, ""),
+    }
+}
+
+/// License names from a LICENSE string, without USE conditionals and groupings
+fn license_names(license: &str) -> Vec<String> {
+    let mut names: Vec<String> = vec![];
+    for token in license.split_whitespace() {
+        if matches!(token, "||" | "(" | ")") || token.ends_with('?') {
+            continue;
+        }
+        if !names.iter().any(|n| n == token) {
+            names.push(token.to_string());
+        }
+    }
+    names
+}
+
+/// Package atoms from a dependency string like
+/// ">=dev-libs/libpcre2-10.22 ssl? ( dev-libs/openssl:= )", without duplicates
+fn dependency_atoms(depend: &str) -> Vec<String> {
+    let mut atoms: Vec<String> = vec![];
+    // "!" marks blockers, which are not dependencies
+    for token in depend
+        .split_whitespace()
+        .filter(|t| t.contains('/') && !t.starts_with('!'))
+    {
+        let atom = token.trim_start_matches(['>', '<', '=', '~']);
+        let atom = atom.split([':', '[']).next().unwrap_or(atom);
+        let (name, _) = split_cpv(atom);
+        if !atoms.iter().any(|a| a == name) {
+            atoms.push(name.to_string());
+        }
+    }
+    atoms
+}
+
+/// Parse `eix --compact`: "[I] app-misc/jq (1.7.1@01/02/2024): Command-line JSON processor",
+/// where the state is N(ot installed), I(nstalled), U(pdate) or D(owngrade)
+fn parse_eix(output: &str) -> Vec<Package> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (state, rest) = line.strip_prefix('[')?.split_once("] ")?;
+            let (name, rest) = rest.split_once(" (")?;
+            let (versions, description) = rest.split_once("): ").unwrap_or((rest, ""));
+            // The installed version for installed packages, else the best available
+            let version = versions
+                .split([' ', ';', '@'])
+                .next()
+                .unwrap_or_default()
+                .trim_start_matches("(~)")
+                .trim_start_matches('~');
+
+            let description = Some(description.trim())
+                .filter(|d| !d.is_empty())
+                .map(str::to_string);
+            let mut pkg = Package::new(name.trim(), version).with_description(description);
+            pkg.installed = matches!(state, "I" | "U" | "D");
+            Some(pkg)
+        })
+        .collect()
+}
+
+/// Parse `emerge --search` result blocks:
+/// "*  sys-apps/ripgrep" followed by indented "Key: value" lines
+fn parse_emerge_search(output: &str) -> Vec<Package> {
+    let mut packages: Vec<Package> = vec![];
+
+    for line in output.lines() {
+        if let Some(name) = line.strip_prefix('*') {
+            let name = name.split_whitespace().next().unwrap_or_default();
+            if name.contains('/') {
+                packages.push(Package::new(name, ""));
+            }
+            continue;
+        }
+
+        let Some(pkg) = packages.last_mut() else {
+            continue;
+        };
+        let Some((key, value)) = line.trim().split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "Latest version available" => pkg.version = value.to_string(),
+            "Latest version installed" => pkg.installed = !value.contains("Not Installed"),
+            "Homepage" => pkg.url = Some(value.to_string()),
+            "Description" => pkg.description = Some(value.to_string()),
+            "License" => pkg.extra.license = license_names(value),
+            _ => {}
+        }
+    }
+
+    packages
+}
+
+/// Parse `emerge -puvDN @world`, keeping upgrades but not downgrades ("UD"):
+/// "[ebuild     U  ] sys-apps/ripgrep-14.1.1::gentoo [14.1.0::gentoo] USE="pcre -debug" 1,024 KiB"
+fn parse_pretend(output: &str) -> Vec<Package> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let rest = line.strip_prefix("[ebuild")?;
+            let (flags, rest) = rest.split_once(']')?;
+            if !flags.contains('U') || flags.contains('D') {
+                return None;
+            }
+
+            let mut tokens = rest.split_whitespace();
+            let cpv = tokens.next()?.split("::").next()?;
+            let (name, version) = split_cpv(cpv);
+            let mut pkg = Package::new(name, version);
+            pkg.installed = true;
+
+            // USE="pcre -debug" spans tokens until the closing quote
+            if let Some(start) = rest.find("USE=\"") {
+                let flags = &rest[start + 5..];
+                let flags = flags.split('"').next().unwrap_or_default();
+                pkg.extra.use_flags = flags
+                    .split_whitespace()
+                    .map(|flag| flag.trim_end_matches(['*', '%']).to_string())
+                    .collect();
+            }
+            Some(pkg)
+        })
+        .collect()
+}
+
+fn command_exists(cmd: &str) -> bool {
+    Command::new("which")
+        .arg(cmd)
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_cpv() {
+        assert_eq!(
+            split_cpv("sys-apps/ripgrep-14.1.0"),
+            ("sys-apps/ripgrep", "14.1.0")
+        );
+        assert_eq!(
+            split_cpv("dev-lang/python-3.12.3-r1"),
+            ("dev-lang/python", "3.12.3-r1")
+        );
+        assert_eq!(
+            split_cpv("x11-libs/gtk+-3.24.41"),
+            ("x11-libs/gtk+", "3.24.41")
+        );
+        assert_eq!(split_cpv("sys-apps/ripgrep"), ("sys-apps/ripgrep", ""));
+    }
+
+    #[test]
+    fn test_dependency_atoms() {
+        let rdepend = ">=sys-libs/glibc-2.36 pcre? ( dev-libs/libpcre2:=[jit] ) \
+                       || ( dev-libs/openssl:0/3 dev-libs/libressl ) !sys-apps/ripgrep-bin";
+        assert_eq!(
+            dependency_atoms(rdepend),
+            [
+                "sys-libs/glibc",
+                "dev-libs/libpcre2",
+                "dev-libs/openssl",
+                "dev-libs/libressl"
+            ]
+        );
+        assert_eq!(
+            license_names("Apache-2.0 || ( MIT Unlicense ) doc? ( CC-BY-4.0 )"),
+            ["Apache-2.0", "MIT", "Unlicense", "CC-BY-4.0"]
+        );
+    }
+
+    #[test]
+    fn test_parse_eix() {
+        let output = "[I] app-misc/jq (1.7.1@01/02/2024): Command-line JSON processor\n\
+                      [N] sys-apps/ripgrep (~14.1.0): a search tool that combines the usability of ag with the raw speed of grep\n\
+                      Found 2 matches\n";
+        let packages = parse_eix(output);
+        assert_eq!(packages.len(), 2);
+        assert_eq!(packages[0].name, "app-misc/jq");
+        assert_eq!(packages[0].version, "1.7.1");
+        assert!(packages[0].installed);
+        assert_eq!(packages[1].version, "14.1.0");
+        assert!(!packages[1].installed);
+    }
+
+    #[test]
+    fn test_parse_emerge_search() {
+        let output = "\n\
+                      [ Results for search key : ripgrep ]\n\
+                      Searching...\n\
+                      \n\
+                      *  sys-apps/ripgrep\n\
+                      \x20     Latest version available: 14.1.0\n\
+                      \x20     Latest version installed: [ Not Installed ]\n\
+                      \x20     Size of files: 1,871 KiB\n\
+                      \x20     Homepage:      https://github.com/BurntSushi/ripgrep\n\
+                      \x20     Description:   a search tool that combines the usability of ag with the raw speed of grep\n\
+                      \x20     License:       Apache-2.0 BSD MIT Unicode-DFS-2016 || ( MIT Unlicense )\n\
+                      \n\
+                      *  sys-apps/ripgrep-all [ Masked ]\n\
+                      \x20     Latest version available: 0.10.6\n\
+                      \x20     Latest version installed: 0.10.6\n\
+                      \n\
+                      [ Applications found : 2 ]\n";
+        let packages = parse_emerge_search(output);
+        assert_eq!(packages.len(), 2);
+        assert_eq!(packages[0].name, "sys-apps/ripgrep");
+        assert_eq!(packages[0].version, "14.1.0");
+        assert!(!packages[0].installed);
+        assert_eq!(
+            packages[0].url.as_deref(),
+            Some("https://github.com/BurntSushi/ripgrep")
+        );
+        assert_eq!(packages[0].extra.license.len(), 5);
+        assert_eq!(packages[1].name, "sys-apps/ripgrep-all");
+        assert!(packages[1].installed);
+    }
+
+    #[test]
+    fn test_parse_pretend() {
+        let output = "\n\
+                      These are the packages that would be merged, in order:\n\
+                      \n\
+                      Calculating dependencies... done!\n\
+                      [ebuild     U  ] sys-apps/ripgrep-14.1.1::gentoo [14.1.0::gentoo] USE=\"pcre -debug\" 1,871 KiB\n\
+                      [ebuild  N     ] dev-libs/libpcre2-10.44::gentoo  USE=\"jit -static-libs\" 1,024 KiB\n\
+                      [ebuild   R    ] app-misc/jq-1.7.1::gentoo  USE=\"oniguruma* -test\" 0 KiB\n\
+                      [ebuild     UD ] dev-lang/rust-bin-1.79.0::gentoo [1.80.1::gentoo] 0 KiB\n\
+                      \n\
+                      Total: 4 packages (1 upgrade, 1 downgrade, 1 new, 1 reinstall), Size of downloads: 2,895 KiB\n";
+        let updates = parse_pretend(output);
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].name, "sys-apps/ripgrep");
+        assert_eq!(updates[0].version, "14.1.1");
+        assert_eq!(updates[0].extra.use_flags, ["pcre", "-debug"]);
+    }
+}
@@ -11,6 +11,29 @@ use std::sync::OnceLock;
 /// Global password storage - only set once per session
 static SUDO_PASSWORD: OnceLock<String> = OnceLock::new();
 
+#[cfg(unix)]
+/// The command used to run as root: sudo, or doas where sudo is not installed
+/// (Alpine and other minimal systems)
+fn elevator() -> &'static str {
+    static ELEVATOR: OnceLock<&'static str> = OnceLock::new();
+    ELEVATOR.get_or_init(|| {
+        if !command_exists("sudo") && command_exists("doas") {
+            "doas"
+        } else {
+            "sudo"
+        }
+    })
+}
+
+#[cfg(unix)]
+fn command_exists(cmd: &str) -> bool {
+    Command::new("which")
+        .arg(cmd)
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
 #[cfg(unix)]
 /// Check if we need sudo (not running as root)
 pub fn needs_sudo() -> bool {
@@ -28,8 +51,15 @@ pub fn ensure_password() -> Result<()> {
         return Ok(()); // Already have password
     }
 
+    // doas has no -S and always reads the password from the terminal, so
+    // let it prompt on first use (and cache it when doas.conf says persist)
+    if elevator() == "doas" {
+        let _ = SUDO_PASSWORD.set(String::new());
+        return Ok(());
+    }
+
     // First try to see if we already have sudo access (e.g., from recent sudo use)
-    let check = Command::new("sudo")
+    let check = Command::new(elevator())
         .args(["-n", "true"])
         .stdout(Stdio::null())
         .stderr(Stdio::null())
@@ -59,7 +89,7 @@ fn prompt_password() -> Result<()> {
     let password = rpassword::read_password().context("Failed to read password")?;
 
     // Verify the password works
-    let mut child = Command::new("sudo")
+    let mut child = Command::new(elevator())
         .args(["-S", "-v"])
         .stdin(Stdio::piped())
         .stdout(Stdio::null())
@@ -111,7 +141,7 @@ pub fn run_sudo(args: &[&str]) -> Result<ExitStatus> {
 
     // If we have an empty password (passwordless sudo), run without -S
     if password.map(|p| p.is_empty()).unwrap_or(false) {
-        let status = Command::new("sudo")
+        let status = Command::new(elevator())
             .args(args)
             .stdin(Stdio::inherit())
             .stdout(Stdio::inherit())
@@ -122,7 +152,7 @@ pub fn run_sudo(args: &[&str]) -> Result<ExitStatus> {
     }
 
     // Run with password via stdin
-    let mut child = Command::new("sudo")
+    let mut child = Command::new(elevator())
         .arg("-S")
         .args(args)
         .stdin(Stdio::piped())
@@ -159,7 +189,7 @@ pub fn run_sudo_output(args: &[&str]) -> Result<Output> {
 
     // If we have an empty password (passwordless sudo), run without -S
     if password.map(|p| p.is_empty()).unwrap_or(false) {
-        let output = Command::new("sudo")
+        let output = Command::new(elevator())
             .args(args)
             .output()
             .context("Failed to run sudo command")?;
@@ -167,7 +197,7 @@ pub fn run_sudo_output(args: &[&str]) -> Result<Output> {
     }
 
     // Run with password via stdin
-    let mut child = Command::new("sudo")
+    let mut child = Command::new(elevator())
         .arg("-S")
         .args(args)
         .stdin(Stdio::piped())
@@ -206,7 +236,7 @@ pub fn run_sudo_in_dir(args: &[&str], dir: &std::path::Path) -> Result<ExitStatu
     let password = get_password();
 
     if password.map(|p| p.is_empty()).unwrap_or(false) {
-        let status = Command::new("sudo")
+        let status = Command::new(elevator())
             .args(args)
             .current_dir(dir)
             .stdin(Stdio::inherit())
@@ -217,7 +247,7 @@ pub fn run_sudo_in_dir(args: &[&str], dir: &std::path::Path) -> Result<ExitStatu
         return Ok(status);
     }
 
-    let mut child = Command::new("sudo")
+    let mut child = Command::new(elevator())
         .arg("-S")
         .args(args)
         .current_dir(dir)
@@ -0,0 +1,330 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use super::sudo;
+use super::{InstallResult, Package, PackageManager};
+
+/// XBPS package manager backend for Void Linux
+pub struct XbpsBackend;
+
+impl XbpsBackend {
+    pub fn new() -> Result<Self> {
+        if !command_exists("xbps-install") || !command_exists("xbps-query") {
+            anyhow::bail!("xbps is not available on this system");
+        }
+        Ok(Self)
+    }
+
+    /// Run an xbps tool as root, with one result per package
+    fn run_root(&self, args: &[&str], packages: &[&str]) -> Result<Vec<InstallResult>> {
+        if packages.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut command = args.to_vec();
+        command.extend(packages.iter().copied());
+
+        let status =
+            sudo::run_sudo(&command).with_context(|| format!("Failed to run {}", args[0]))?;
+
+        let success = status.success();
+        Ok(packages
+            .iter()
+            .map(|name| InstallResult {
+                package: name.to_string(),
+                success,
+                message: if success {
+                    None
+                } else {
+                    Some(format!("{} failed", args[0]))
+                },
+            })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl PackageManager for XbpsBackend {
+    fn name(&self) -> &str {
+        "XBPS (Void Linux)"
+    }
+
+    fn id(&self) -> &str {
+        "xbps"
+    }
+
+    async fn search(&self, query: &str) -> Result<Vec<Package>> {
+        if query.len() < 2 {
+            return Ok(vec![]);
+        }
+
+        let output = Command::new("xbps-query")
+            .args(["-Rs", query])
+            .output()
+            .context("Failed to run xbps-query -Rs")?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let mut packages = parse_search(&stdout);
+        packages.truncate(30);
+
+        Ok(packages)
+    }
+
+    async fn info(&self, packages: &[&str]) -> Result<Vec<Package>> {
+        let mut results = vec![];
+
+        for pkg_name in packages {
+            let output = Command::new("xbps-query")
+                .args(["-R", pkg_name])
+                .output()
+                .context("Failed to run xbps-query -R")?;
+
+            if output.status.success() {
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                if let Some(mut pkg) = parse_info(&stdout) {
+                    pkg.installed = self.is_installed(&pkg.name)?;
+                    results.push(pkg);
+                }
+            }
+        }
+
+        Ok(results)
+    }
+
+    async fn install(&self, packages: &[Package]) -> Result<Vec<InstallResult>> {
+        let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+        if !names.is_empty() {
+            println!("--> Installing packages with xbps-install...");
+        }
+        self.run_root(&["xbps-install", "-S", "-y"], &names)
+    }
+
+    async fn remove(&self, packages: &[&str]) -> Result<Vec<InstallResult>> {
+        if !packages.is_empty() {
+            println!("--> Removing packages with xbps-remove...");
+        }
+        self.run_root(&["xbps-remove", "-y"], packages)
+    }
+
+    fn is_installed(&self, package: &str) -> Result<bool> {
+        let status = Command::new("xbps-query")
+            .arg(package)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()?;
+
+        Ok(status.success())
+    }
+
+    fn list_installed(&self) -> Result<Vec<(String, String)>> {
+        let output = Command::new("xbps-query").arg("-l").output()?;
+
+        if !output.status.success() {
+            return Ok(vec![]);
+        }
+
+        // "ii ripgrep-14.1.0_1   Fast line-oriented search tool"
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout
+            .lines()
+            .filter_map(|line| line.split_whitespace().nth(1))
+            .filter_map(split_pkgver)
+            .map(|(name, version)| (name.to_string(), version.to_string()))
+            .collect())
+    }
+
+    async fn check_updates(&self) -> Result<Vec<Package>> {
+        // -M syncs the repository indexes in memory, so no root is needed
+        let output = Command::new("xbps-install")
+            .args(["-Mun"])
+            .output()
+            .context("Failed to check for updates")?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(parse_updates(&stdout))
+    }
+
+    fn owns(&self, path: &Path) -> Result<Option<(String, String)>> {
+        let output = Command::new("xbps-query")
+            .arg("-o")
+            .arg(path)
+            .output()
+            .context("Failed to run xbps-query -o")?;
+
+        // "ripgrep-14.1.0_1: /usr/bin/rg (regular file)"
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout
+            .lines()
+            .find_map(|line| line.split_once(": "))
+            .and_then(|(pkgver, _)| split_pkgver(pkgver))
+            .map(|(name, version)| (name.to_string(), version.to_string())))
+    }
+}
+
+/// Split "ripgrep-14.1.0_1" into ("ripgrep", "14.1.0_1"). XBPS versions
+/// never contain dashes and always end in a "_<revision>".
+fn split_pkgver(pkgver: &str) -> Option<(&str, &str)> {
+    pkgver
+        .rsplit_once('-')
+        .filter(|(name, version)| !name.is_empty() && version.contains('_'))
+}
+
+/// Parse `xbps-query -Rs`: "[*] ripgrep-14.1.0_1   Fast line-oriented search tool",
+/// where "[*]" marks installed packages and "[-]" the rest
+fn parse_search(output: &str) -> Vec<Package> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (state, rest) = line.trim().split_once(' ')?;
+            let rest = rest.trim_start();
+            let (pkgver, description) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+            let (name, version) = split_pkgver(pkgver)?;
+
+            let description = Some(description.trim())
+                .filter(|d| !d.is_empty())
+                .map(str::to_string);
+            let mut pkg = Package::new(name, version).with_description(description);
+            pkg.installed = state == "[*]";
+            Some(pkg)
+        })
+        .collect()
+}
+
+/// Parse the "key: value" output of `xbps-query -R <pkg>`. List values such as
+/// run_depends follow their key on indented lines.
+fn parse_info(output: &str) -> Option<Package> {
+    let mut name = None;
+    let mut version = String::new();
+    let mut pkg = Package::new("", "");
+    let mut list_key = "";
+
+    for line in output.lines() {
+        if line.starts_with([' ', '\t']) {
+            if list_key == "run_depends" {
+                pkg.extra.depends.push(line.trim().to_string());
+            }
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        list_key = if value.is_empty() { key } else { "" };
+
+        match key {
+            "pkgname" => name = Some(value.to_string()),
+            "pkgver" => {
+                if let Some((_, v)) = split_pkgver(value) {
+                    version = v.to_string();
+                }
+            }
+            "short_desc" => pkg.description = Some(value.to_string()),
+            "homepage" => pkg.url = Some(value.to_string()),
+            "maintainer" => pkg.maintainer = Some(value.to_string()),
+            "license" => {
+                pkg.extra.license = value.split(", ").map(str::to_string).collect();
+            }
+            _ => {}
+        }
+    }
+
+    pkg.name = name?;
+    pkg.version = version;
+    Some(pkg)
+}
+
+/// Parse `xbps-install -Mun`: "<pkgver> <action> <arch> <repository> ..." per
+/// package in the transaction
+fn parse_updates(output: &str) -> Vec<Package> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let (pkgver, action) = (fields.next()?, fields.next()?);
+            if action != "update" {
+                return None;
+            }
+            let (name, version) = split_pkgver(pkgver)?;
+            let mut pkg = Package::new(name, version);
+            pkg.installed = true;
+            Some(pkg)
+        })
+        .collect()
+}
+
+fn command_exists(cmd: &str) -> bool {
+    Command::new("which")
+        .arg(cmd)
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_search() {
+        let output = "[*] ripgrep-14.1.0_1           Fast line-oriented search tool, similar to AG and ack\n\
+                      [-] ripgrep-all-0.10.6_1       Ripgrep, but also search in PDFs, E-Books, Office documents, zip, etc\n";
+        let packages = parse_search(output);
+        assert_eq!(packages.len(), 2);
+        assert_eq!(packages[0].name, "ripgrep");
+        assert_eq!(packages[0].version, "14.1.0_1");
+        assert!(packages[0].installed);
+        assert_eq!(packages[1].name, "ripgrep-all");
+        assert!(!packages[1].installed);
+        assert_eq!(
+            packages[1].description.as_deref(),
+            Some("Ripgrep, but also search in PDFs, E-Books, Office documents, zip, etc")
+        );
+    }
+
+    #[test]
+    fn test_parse_info() {
+        let output = "architecture: x86_64\n\
+                      filename-sha256: 9f2c1f0b0a4c1e5d\n\
+                      filename-size: 1581KB\n\
+                      homepage: https://github.com/BurntSushi/ripgrep\n\
+                      installed_size: 4712KB\n\
+                      license: Unlicense, MIT\n\
+                      maintainer: Orphaned <orphan@voidlinux.org>\n\
+                      pkgname: ripgrep\n\
+                      pkgver: ripgrep-14.1.0_1\n\
+                      repository: https://repo-default.voidlinux.org/current\n\
+                      run_depends:\n\
+                      \tglibc>=2.36_1\n\
+                      \tlibpcre2>=10.22_1\n\
+                      shlib-requires:\n\
+                      \tlibpcre2-8.so.0\n\
+                      \tlibc.so.6\n\
+                      short_desc: Fast line-oriented search tool, similar to AG and ack\n";
+        let pkg = parse_info(output).unwrap();
+        assert_eq!(pkg.name, "ripgrep");
+        assert_eq!(pkg.version, "14.1.0_1");
+        assert_eq!(pkg.extra.depends, ["glibc>=2.36_1", "libpcre2>=10.22_1"]);
+        assert_eq!(pkg.extra.license, ["Unlicense", "MIT"]);
+        assert_eq!(
+            pkg.url.as_deref(),
+            Some("https://github.com/BurntSushi/ripgrep")
+        );
+        assert_eq!(
+            pkg.description.as_deref(),
+            Some("Fast line-oriented search tool, similar to AG and ack")
+        );
+    }
+
+    #[test]
+    fn test_parse_updates() {
+        let output = "ripgrep-14.1.1_1 update x86_64 https://repo-default.voidlinux.org/current 4712960 1619968\n\
+                      libpcre2-10.44_1 install x86_64 https://repo-default.voidlinux.org/current 1048576 409600\n";
+        let updates = parse_updates(output);
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].name, "ripgrep");
+        assert_eq!(updates[0].version, "14.1.1_1");
+    }
+}
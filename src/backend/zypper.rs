@@ -1,5 +1,6 @@
 use anyhow::{Context, Result};
 use async_trait::async_trait;
+use std::path::Path;
 use std::process::{Command, Stdio};
 
 use super::sudo;
@@ -224,6 +225,33 @@ impl PackageManager for ZypperBackend {
         Ok(results)
     }
 
+    async fn remove(&self, packages: &[&str]) -> Result<Vec<InstallResult>> {
+        if packages.is_empty() {
+            return Ok(vec![]);
+        }
+
+        println!("--> Removing packages with zypper...");
+
+        let mut args = vec!["zypper", "remove", "-y"];
+        args.extend(packages.iter().copied());
+
+        let status = sudo::run_sudo(&args).context("Failed to run zypper remove")?;
+
+        let success = status.success();
+        Ok(packages
+            .iter()
+            .map(|name| InstallResult {
+                package: name.to_string(),
+                success,
+                message: if success {
+                    None
+                } else {
+                    Some("zypper remove failed".to_string())
+                },
+            })
+            .collect())
+    }
+
     fn is_installed(&self, package: &str) -> Result<bool> {
         let output = Command::new("rpm")
             .args(["-q", package])
@@ -288,6 +316,10 @@ impl PackageManager for ZypperBackend {
 
         Ok(updates)
     }
+
+    fn owns(&self, path: &Path) -> Result<Option<(String, String)>> {
+        super::dnf::rpm_owner(path)
+    }
 }
 
 fn command_exists(cmd: &str) -> bool {
@@ -0,0 +1,462 @@
+//! `zap doctor`: PATH problems and package manager CLIs that do not work
+
+use anyhow::Result;
+use colored::Colorize;
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::backend::bootstrap::BootstrapTarget;
+use crate::backend::cabal::CabalBackend;
+use crate::backend::composer::ComposerBackend;
+use crate::backend::dotnet::DotnetToolBackend;
+use crate::backend::gem::GemBackend;
+use crate::backend::go::GoBackend;
+use crate::backend::multi::MultiBackend;
+use crate::backend::npm::NodeClient;
+use crate::backend::opam::OpamBackend;
+use crate::backend::{self, detect_available_package_managers};
+use crate::owns::{find_on_path, is_executable, path_dirs, same_file};
+use crate::ui::{print_info, print_success, print_warning};
+
+/// How long a package manager gets to print its version
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Something wrong, and what to do about it
+struct Problem {
+    message: String,
+    fix: String,
+}
+
+/// An executable name and every distinct file found for it, in PATH order
+type Executables = Vec<(OsString, Vec<PathBuf>)>;
+
+pub async fn run() -> Result<()> {
+    let dirs = path_dirs();
+    let mut problems = vec![];
+
+    print_info("Checking PATH...");
+    let (executables, broken) = scan_path(&dirs);
+    problems.extend(broken.into_iter().map(|(link, target)| Problem {
+        message: format!(
+            "{} is a broken symlink to {}",
+            link.display(),
+            target.display()
+        ),
+        fix: format!("remove it: rm '{}'", link.display()),
+    }));
+    problems.extend(shadowed(executables));
+    problems.extend(missing_path_entries(expected_bin_dirs(), &dirs));
+
+    print_info("Checking package manager CLIs...");
+    problems.extend(failing_clis().await);
+
+    println!();
+    if problems.is_empty() {
+        print_success("No problems found");
+        return Ok(());
+    }
+    for problem in &problems {
+        print_warning(&problem.message);
+        println!("       {} {}", "fix:".green(), problem.fix);
+    }
+    println!();
+    print_info(&format!("{} problems found", problems.len()));
+    Ok(())
+}
+
+/// Executables on PATH and the broken symlinks (with their targets) next to them
+fn scan_path(dirs: &[PathBuf]) -> (Executables, Vec<(PathBuf, PathBuf)>) {
+    let mut executables: Executables = vec![];
+    let mut index: HashMap<OsString, usize> = HashMap::new();
+    let mut broken = vec![];
+
+    for dir in dirs {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            continue;
+        };
+        let mut paths: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+        paths.sort();
+
+        for path in paths {
+            if path.is_symlink() && !path.exists() {
+                let target = std::fs::read_link(&path).unwrap_or_default();
+                broken.push((path, target));
+                continue;
+            }
+            if !is_executable(&path) {
+                continue;
+            }
+            let Some(name) = path.file_name().map(|n| n.to_os_string()) else {
+                continue;
+            };
+
+            match index.get(&name) {
+                Some(&i) => {
+                    let copies = &mut executables[i].1;
+                    if !copies.iter().any(|c| same_file(c, &path)) {
+                        copies.push(path);
+                    }
+                }
+                None => {
+                    index.insert(name.clone(), executables.len());
+                    executables.push((name, vec![path]));
+                }
+            }
+        }
+    }
+
+    (executables, broken)
+}
+
+/// Commands installed more than once, where the first copy on PATH hides the rest.
+/// Owners are only looked up for these, as that takes a process per file.
+fn shadowed(executables: Executables) -> Vec<Problem> {
+    let duplicates: Vec<(OsString, Vec<PathBuf>)> = executables
+        .into_iter()
+        .filter(|(_, copies)| copies.len() > 1)
+        .collect();
+    if duplicates.is_empty() {
+        return vec![];
+    }
+
+    let multi = MultiBackend::quiet().ok();
+    let describe = |path: &Path| {
+        let owner = multi
+            .as_ref()
+            .and_then(|m| m.owns_all(path).into_iter().next());
+        match owner {
+            Some((backend, name, version)) => {
+                format!("{} ({}: {} {})", path.display(), backend, name, version)
+            }
+            None => format!("{} (unmanaged)", path.display()),
+        }
+    };
+
+    duplicates
+        .into_iter()
+        .map(|(name, copies)| {
+            let hidden: Vec<String> = copies[1..].iter().map(|c| describe(c)).collect();
+            Problem {
+                message: format!(
+                    "'{}' resolves to {}, shadowing {}",
+                    name.to_string_lossy(),
+                    describe(&copies[0]),
+                    hidden.join(", ")
+                ),
+                fix: format!(
+                    "uninstall the copies you do not use, or put {} earlier on PATH to run that one",
+                    copies[1].parent().unwrap_or(Path::new("")).display()
+                ),
+            }
+        })
+        .collect()
+}
+
+/// The directories installed backends put executables in, with the installer
+fn expected_bin_dirs() -> Vec<(&'static str, PathBuf)> {
+    let mut expected: Vec<(&str, PathBuf)> = vec![];
+
+    if let Some(dir) = backend::cargo::bin_dir() {
+        expected.push(("cargo install", dir));
+    }
+    // The first existing directory is the one `go install` writes to
+    if let Some(dir) = GoBackend::bin_dirs().into_iter().next() {
+        expected.push(("go install", dir));
+    }
+    for client in [
+        NodeClient::Npm,
+        NodeClient::Pnpm,
+        NodeClient::Yarn,
+        NodeClient::Bun,
+    ] {
+        if client.is_available() {
+            if let Some(dir) = client.global_bin_dir() {
+                expected.push((client.name(), dir));
+            }
+        }
+    }
+    if let Some(home) = dirs::home_dir() {
+        if !cfg!(target_os = "windows") {
+            expected.push(("pipx, uv tool and pip --user", home.join(".local/bin")));
+        }
+        if !find_on_path("flatpak".as_ref()).is_empty() {
+            expected.push(("flatpak", PathBuf::from("/var/lib/flatpak/exports/bin")));
+            expected.push((
+                "flatpak --user",
+                home.join(".local/share/flatpak/exports/bin"),
+            ));
+        }
+        if !find_on_path("gem".as_ref()).is_empty() {
+            if let Some(dir) = GemBackend::user_bin_dir() {
+                expected.push(("gem install --user-install", dir));
+            }
+        }
+        if !find_on_path("composer".as_ref()).is_empty() {
+            if let Some(dir) = ComposerBackend::bin_dir() {
+                expected.push(("composer global require", dir));
+            }
+        }
+        if backend::cabal::cabal_command().is_some() {
+            if let Some(dir) = backend::cabal::ghcup_bin_dir() {
+                expected.push(("ghcup", dir));
+            }
+            if let Some(dir) = CabalBackend::installdir() {
+                expected.push(("cabal install", dir));
+            }
+        }
+        if !find_on_path("opam".as_ref()).is_empty() {
+            if let Some(dir) = OpamBackend::bin_dir() {
+                expected.push(("opam", dir));
+            }
+        }
+        if !find_on_path("dotnet".as_ref()).is_empty() {
+            if let Some(dir) = DotnetToolBackend::tools_dir() {
+                expected.push(("dotnet tool install -g", dir));
+            }
+        }
+        if !find_on_path("nix".as_ref()).is_empty() {
+            expected.push(("the Nix profile", home.join(".nix-profile/bin")));
+        }
+    }
+    expected
+}
+
+/// The `expected` directories that exist but are not among the PATH `dirs`
+fn missing_path_entries(mut expected: Vec<(&str, PathBuf)>, dirs: &[PathBuf]) -> Vec<Problem> {
+    // cabal and pipx often share ~/.local/bin: report each directory once
+    let mut seen: Vec<PathBuf> = vec![];
+    expected.retain(|(_, dir)| {
+        let new = !seen.contains(dir);
+        seen.push(dir.clone());
+        new
+    });
+
+    expected
+        .into_iter()
+        .filter(|(_, dir)| dir.is_dir())
+        .filter(|(_, dir)| !dirs.iter().any(|d| d == dir || same_file(d, dir)))
+        .map(|(installer, dir)| Problem {
+            message: format!(
+                "{} is not on PATH, so programs from {} are not found",
+                dir.display(),
+                installer
+            ),
+            fix: path_fix(&dir),
+        })
+        .collect()
+}
+
+fn path_fix(dir: &Path) -> String {
+    if cfg!(target_os = "windows") {
+        return format!(
+            "add {} to your user PATH in the system settings",
+            dir.display()
+        );
+    }
+    let dir = match dirs::home_dir().and_then(|home| dir.strip_prefix(home).ok().map(PathBuf::from))
+    {
+        Some(relative) => format!("$HOME/{}", relative.display()),
+        None => dir.display().to_string(),
+    };
+    format!(
+        "add export PATH=\"{}:$PATH\" to your shell profile (~/.profile, ~/.zshrc, ...)",
+        dir
+    )
+}
+
+/// Package manager CLIs that are on PATH but cannot even print their version
+async fn failing_clis() -> Vec<Problem> {
+    let mut checks: Vec<(String, Vec<&'static str>)> = vec![];
+    let mut add = |command: &str, args: &[&'static str]| {
+        if !checks.iter().any(|(c, _)| c == command) {
+            checks.push((command.to_string(), args.to_vec()));
+        }
+    };
+
+    for id in detect_available_package_managers() {
+        match id {
+            "aur" => {
+                for helper in ["paru", "yay"] {
+                    if !find_on_path(helper.as_ref()).is_empty() {
+                        add(helper, &["--version"]);
+                    }
+                }
+            }
+            "apt" => add("apt-get", &["--version"]),
+            "xbps" => add("xbps-query", &["--version"]),
+            "portage" => add("emerge", &["--version"]),
+            "pkg" => add("pkg", &["-v"]),
+            "go" => add("go", &["version"]),
+            "pub" => add("dart", &["--version"]),
+            "cabal" => {
+                if let Some(cabal) = backend::cabal::cabal_command() {
+                    add(&cabal.to_string_lossy(), &["--version"]);
+                }
+            }
+            "dockerhub" => add("docker", &["--version"]),
+            "pip" => {
+                for pip in ["pip3", "pip"] {
+                    if !find_on_path(pip.as_ref()).is_empty() {
+                        add(pip, &["--version"]);
+                        break;
+                    }
+                }
+            }
+            "conda" => {
+                for tool in ["micromamba", "mamba", "conda"] {
+                    if !find_on_path(tool.as_ref()).is_empty() {
+                        add(tool, &["--version"]);
+                        break;
+                    }
+                }
+            }
+            "npm" => {
+                for client in [
+                    NodeClient::Npm,
+                    NodeClient::Pnpm,
+                    NodeClient::Yarn,
+                    NodeClient::Bun,
+                ] {
+                    if client.is_available() {
+                        add(&client.command(), &["--version"]);
+                    }
+                }
+            }
+            other => add(other, &["--version"]),
+        }
+    }
+
+    // Tools zap installs on demand, e.g. Python behind the pip backend
+    for target in BootstrapTarget::ALL {
+        if target.supported_on_current_platform() && target.is_installed() {
+            if let Some([command, arg]) = target.health_check() {
+                add(command, &[arg]);
+            }
+        }
+    }
+
+    let runs = checks.into_iter().map(|(command, args)| async move {
+        let error = health_check(&command, &args).await.err()?;
+        let location = find_on_path(command.as_ref())
+            .into_iter()
+            .next()
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|| command.clone());
+        Some(Problem {
+            message: format!("`{} {}` failed: {}", command, args.join(" "), error),
+            fix: format!(
+                "reinstall {}, or remove {} if it is a leftover",
+                command, location
+            ),
+        })
+    });
+    futures::future::join_all(runs)
+        .await
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+/// Run a version command, describing why it failed if it did
+async fn health_check(command: &str, args: &[&str]) -> std::result::Result<(), String> {
+    let run = tokio::process::Command::new(command)
+        .args(args)
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true)
+        .output();
+
+    match tokio::time::timeout(HEALTH_CHECK_TIMEOUT, run).await {
+        Err(_) => Err(format!(
+            "no answer after {} seconds",
+            HEALTH_CHECK_TIMEOUT.as_secs()
+        )),
+        Ok(Err(e)) => Err(e.to_string()),
+        Ok(Ok(output)) if output.status.success() => Ok(()),
+        Ok(Ok(output)) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let reason = stderr
+                .lines()
+                .map(|l| l.trim())
+                .find(|l| !l.is_empty())
+                .map(|l| l.to_string())
+                .unwrap_or_else(|| output.status.to_string());
+            Err(reason)
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::{symlink, PermissionsExt};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("zap-doctor-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn executable(path: &Path) {
+        std::fs::write(path, "#!/bin/sh\n").unwrap();
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[test]
+    fn test_scan_path() {
+        let root = temp_dir("scan");
+        let (first, second) = (root.join("first"), root.join("second"));
+        std::fs::create_dir_all(&first).unwrap();
+        std::fs::create_dir_all(&second).unwrap();
+
+        executable(&first.join("tool"));
+        executable(&second.join("tool"));
+        executable(&first.join("only"));
+        std::fs::write(first.join("README"), "not executable").unwrap();
+        // A link to a copy already seen is the same program, not a duplicate
+        symlink(first.join("only"), second.join("only")).unwrap();
+        symlink(root.join("gone"), second.join("broken")).unwrap();
+
+        let (executables, broken) = scan_path(&[first.clone(), second.clone()]);
+        let copies = |name: &str| {
+            executables
+                .iter()
+                .find(|(n, _)| n == name)
+                .map(|(_, copies)| copies.clone())
+                .unwrap_or_default()
+        };
+        assert_eq!(copies("tool"), [first.join("tool"), second.join("tool")]);
+        assert_eq!(copies("only"), [first.join("only")]);
+        assert!(copies("README").is_empty());
+        assert_eq!(broken, [(second.join("broken"), root.join("gone"))]);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_missing_path_entries() {
+        let root = temp_dir("missing");
+        let (local, on_path) = (root.join(".local/bin"), root.join("bin"));
+        std::fs::create_dir_all(&local).unwrap();
+        std::fs::create_dir_all(&on_path).unwrap();
+
+        let expected = vec![
+            ("pipx, uv tool and pip --user", local.clone()),
+            ("cabal install", local.clone()),
+            ("cargo install", on_path.clone()),
+            ("go install", root.join("go/bin")),
+        ];
+        let problems = missing_path_entries(expected, &[on_path]);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0]
+            .message
+            .starts_with(&local.display().to_string()));
+        assert!(problems[0]
+            .message
+            .contains("programs from pipx, uv tool and pip --user"));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
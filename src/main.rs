@@ -1,5 +1,11 @@
+mod audit;
 mod backend;
 mod devtools;
+mod doctor;
+mod manifest;
+mod owns;
+mod provides;
+mod typosquat;
 mod ui;
 mod update;
 
@@ -13,6 +19,7 @@ use crossterm::{
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
 use std::io::stdout;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -35,14 +42,27 @@ enum BackendChoice {
     Pacman,
     Pkg,
     Zypper,
+    Apk,
+    Xbps,
+    Portage,
     // Universal package managers
     Flatpak,
     Snap,
+    Nix,
     // Language package managers
     Cargo,
     Go,
     Pip,
+    Conda,
+    Gem,
+    Composer,
+    Cabal,
+    Opam,
+    Dotnet,
     Npm,
+    Pnpm,
+    Yarn,
+    Bun,
     Deno,
     Pub,
     // Special
@@ -68,6 +88,14 @@ struct Cli {
     #[arg(short, long, value_enum, default_value = "auto", global = true)]
     backend: BackendChoice,
 
+    /// Build AUR packages in a clean chroot (requires devtools)
+    #[arg(long, global = true)]
+    chroot: bool,
+
+    /// Conda environment to use instead of the active one
+    #[arg(long, global = true, value_name = "NAME")]
+    env: Option<String>,
+
     /// Package names to install directly
     #[arg(trailing_var_arg = true)]
     packages: Vec<String>,
@@ -93,7 +121,22 @@ enum Commands {
     /// Install packages
     #[command(alias = "i")]
     Install {
-        /// Package names to install
+        /// Package names to install (omit to install from manifests in the current directory)
+        packages: Vec<String>,
+
+        /// Install the dependencies listed in a manifest (requirements.txt, package.json, ...)
+        #[arg(long, value_name = "FILE", conflicts_with = "packages")]
+        from: Option<PathBuf>,
+
+        /// Use prebuilt release binaries for cargo crates when available
+        #[arg(long)]
+        prebuilt: bool,
+    },
+
+    /// Uninstall packages
+    #[command(alias = "rm")]
+    Remove {
+        /// Package names to remove
         #[arg(required = true)]
         packages: Vec<String>,
     },
@@ -102,6 +145,10 @@ enum Commands {
     Info {
         /// Package name
         package: String,
+
+        /// Also show pinned and recent AUR comments
+        #[arg(long)]
+        comments: bool,
     },
 
     /// Interactive mode - live search and select packages
@@ -109,7 +156,53 @@ enum Commands {
     Interactive,
 
     /// Update installed packages
-    Update,
+    Update {
+        /// Also rebuild AUR development packages (-git, ...) whose upstream has new commits
+        #[arg(long)]
+        devel: bool,
+
+        /// Include pre-release versions when checking cargo crates
+        #[arg(long)]
+        pre: bool,
+    },
+
+    /// Check installed packages for known vulnerabilities (OSV)
+    Audit {
+        /// Use the cached offline vulnerability database instead of the OSV API
+        #[arg(long)]
+        offline: bool,
+
+        /// Download the offline vulnerability database, then audit against it
+        #[arg(long)]
+        download_db: bool,
+
+        /// Read OSV advisories (.json or .zip files) from a directory
+        #[arg(long, value_name = "DIR", conflicts_with_all = ["offline", "download_db"])]
+        db: Option<PathBuf>,
+    },
+
+    /// Find the packages that provide a command or file
+    Provides {
+        /// Command name (e.g. rg) or file path (e.g. /usr/bin/rg)
+        target: String,
+
+        /// Run as the shell's command-not-found handler
+        #[arg(long, hide = true)]
+        hook: bool,
+    },
+
+    /// Print shell integration code
+    #[command(subcommand)]
+    Hook(HookCommands),
+
+    /// Show which package manager and package installed a file or command
+    Owns {
+        /// File path (e.g. /usr/bin/rg) or command name
+        path: String,
+    },
+
+    /// Find shadowed duplicate commands, PATH gaps and broken package manager CLIs
+    Doctor,
 
     /// Show detected system info and available package managers
     System,
@@ -149,6 +242,10 @@ enum Commands {
     #[command(subcommand)]
     Go(GoCommands),
 
+    /// AUR build management commands
+    #[command(subcommand)]
+    Aur(AurCommands),
+
     /// Docker Hub commands
     #[command(subcommand)]
     Docker(DockerCommands),
@@ -239,6 +336,32 @@ enum GoCommands {
     List,
 }
 
+#[derive(Subcommand)]
+enum AurCommands {
+    /// Remove old builds from the AUR build cache
+    Clean {
+        /// Remove all cached clones and builds, including installed packages
+        #[arg(long)]
+        all: bool,
+    },
+    /// Build packages in a clean chroot without installing them
+    Build {
+        /// Package names to build
+        #[arg(required = true)]
+        packages: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum HookCommands {
+    /// Suggest packages for unknown commands: eval "$(zap hook command-not-found --shell zsh)"
+    CommandNotFound {
+        /// Shell to print the handler for
+        #[arg(long, value_enum)]
+        shell: provides::Shell,
+    },
+}
+
 #[derive(Subcommand)]
 enum DockerCommands {
     /// Install (pull) Docker images
@@ -271,6 +394,8 @@ enum DockerCommands {
 async fn main() -> Result<()> {
     let cli = Cli::parse();
     backend::bootstrap::set_auto_approve(cli.yes);
+    backend::aur::set_chroot(cli.chroot);
+    backend::conda::set_env(cli.env.clone());
 
     // Detect the system and create appropriate backend (only if needed)
     let system = detect_system();
@@ -281,8 +406,14 @@ async fn main() -> Result<()> {
         Some(Commands::Zsh(ZshCommands::Plugins))
             | Some(Commands::System)
             | Some(Commands::Managers)
+            | Some(Commands::Audit { .. })
+            | Some(Commands::Provides { .. })
+            | Some(Commands::Hook(_))
+            | Some(Commands::Owns { .. })
+            | Some(Commands::Doctor)
             | Some(Commands::SelfUpdate)
             | Some(Commands::DevTools)
+            | Some(Commands::Aur(_))
     );
     
     let pm: Arc<dyn PackageManager> = if needs_backend {
@@ -297,7 +428,8 @@ async fn main() -> Result<()> {
     let needs_sudo_for_operation = matches!(
         cli.command.as_ref(),
         Some(Commands::Install { .. })
-            | Some(Commands::Update)
+            | Some(Commands::Remove { .. })
+            | Some(Commands::Update { .. })
             | Some(Commands::Interactive)
             | Some(Commands::Search { interactive: true, .. })
     ) || !cli.packages.is_empty();
@@ -353,27 +485,78 @@ async fn main() -> Result<()> {
             }
         }
 
-        Some(Commands::Install { packages }) => {
-            // Use multi-backend for auto-detection when backend is Auto
-            if matches!(cli.backend, BackendChoice::Auto) {
+        Some(Commands::Install {
+            packages,
+            from,
+            prebuilt,
+        }) => {
+            backend::cargo::set_prebuilt(prebuilt);
+            if let Some(path) = from {
+                install_from_manifests(vec![path]).await?;
+            } else if packages.is_empty() {
+                let manifests = manifest::detect(&std::env::current_dir()?);
+                if manifests.is_empty() {
+                    anyhow::bail!("No packages specified and no dependency manifest found in the current directory");
+                }
+                install_from_manifests(manifests).await?;
+            } else if matches!(cli.backend, BackendChoice::Auto) {
+                // Use multi-backend for auto-detection when backend is Auto
                 install_packages_multi(packages).await?;
             } else {
                 install_packages(&pm, packages).await?;
             }
         }
 
-        Some(Commands::Info { package }) => {
-            show_package_info(&pm, &package).await?;
+        Some(Commands::Remove { packages }) => {
+            remove_packages(&pm, &packages).await?;
+        }
+
+        Some(Commands::Info { package, comments }) => {
+            show_package_info(&pm, &package, comments).await?;
         }
 
         Some(Commands::Interactive) => {
             interactive_mode(&pm).await?;
         }
 
-        Some(Commands::Update) => {
+        Some(Commands::Update { devel, pre }) => {
+            backend::aur::set_devel(devel);
+            backend::cargo::set_prereleases(pre);
             update_packages(&pm).await?;
         }
 
+        Some(Commands::Audit {
+            offline,
+            download_db,
+            db,
+        }) => {
+            let source = match db {
+                Some(dir) => audit::Source::Directory(dir),
+                None if offline || download_db => audit::Source::Cache {
+                    download: download_db,
+                },
+                None => audit::Source::Online,
+            };
+            audit::run(&system, source).await?;
+        }
+
+        Some(Commands::Provides { target, hook }) => {
+            provides::run(&target, hook).await?;
+        }
+
+        Some(Commands::Hook(HookCommands::CommandNotFound { shell })) => {
+            let zap = std::env::current_exe().context("Failed to locate the zap binary")?;
+            print!("{}", provides::hook_script(shell, &zap.to_string_lossy()));
+        }
+
+        Some(Commands::Owns { path }) => {
+            owns::run(&path)?;
+        }
+
+        Some(Commands::Doctor) => {
+            doctor::run().await?;
+        }
+
         Some(Commands::System) => {
             show_system_info(&system, &pm);
         }
@@ -414,6 +597,11 @@ async fn main() -> Result<()> {
             handle_go_command(cmd, &go_pm).await?;
         }
 
+        Some(Commands::Aur(cmd)) => {
+            let aur = backend::aur::AurBackend::new()?;
+            handle_aur_command(cmd, &aur).await?;
+        }
+
         Some(Commands::Docker(cmd)) => {
             let docker_pm: Arc<dyn PackageManager> = Arc::new(backend::dockerhub::DockerhubBackend::new()?);
             handle_docker_command(cmd, &docker_pm).await?;
@@ -457,12 +645,31 @@ fn create_backend(system: &System, choice: BackendChoice) -> Result<Arc<dyn Pack
         BackendChoice::Pacman => Ok(Arc::new(backend::pacman::PacmanBackend::new()?)),
         BackendChoice::Pkg => Ok(Arc::new(backend::pkg::PkgBackend::new()?)),
         BackendChoice::Zypper => Ok(Arc::new(backend::zypper::ZypperBackend::new()?)),
+        BackendChoice::Apk => Ok(Arc::new(backend::apk::ApkBackend::new()?)),
+        BackendChoice::Xbps => Ok(Arc::new(backend::xbps::XbpsBackend::new()?)),
+        BackendChoice::Portage => Ok(Arc::new(backend::portage::PortageBackend::new()?)),
         BackendChoice::Flatpak => Ok(Arc::new(backend::flatpak::FlatpakBackend::new()?)),
         BackendChoice::Snap => Ok(Arc::new(backend::snap::SnapBackend::new()?)),
+        BackendChoice::Nix => Ok(Arc::new(backend::nix::NixBackend::new()?)),
         BackendChoice::Cargo => Ok(Arc::new(backend::cargo::CargoBackend::new()?)),
         BackendChoice::Go => Ok(Arc::new(backend::go::GoBackend::new()?)),
         BackendChoice::Pip => Ok(Arc::new(backend::pip::PipBackend::new()?)),
+        BackendChoice::Conda => Ok(Arc::new(backend::conda::CondaBackend::new()?)),
+        BackendChoice::Gem => Ok(Arc::new(backend::gem::GemBackend::new()?)),
+        BackendChoice::Composer => Ok(Arc::new(backend::composer::ComposerBackend::new()?)),
+        BackendChoice::Cabal => Ok(Arc::new(backend::cabal::CabalBackend::new()?)),
+        BackendChoice::Opam => Ok(Arc::new(backend::opam::OpamBackend::new()?)),
+        BackendChoice::Dotnet => Ok(Arc::new(backend::dotnet::DotnetToolBackend::new()?)),
         BackendChoice::Npm => Ok(Arc::new(backend::npm::NpmBackend::new()?)),
+        BackendChoice::Pnpm => Ok(Arc::new(backend::npm::NpmBackend::with_client(
+            backend::npm::NodeClient::Pnpm,
+        )?)),
+        BackendChoice::Yarn => Ok(Arc::new(backend::npm::NpmBackend::with_client(
+            backend::npm::NodeClient::Yarn,
+        )?)),
+        BackendChoice::Bun => Ok(Arc::new(backend::npm::NpmBackend::with_client(
+            backend::npm::NodeClient::Bun,
+        )?)),
         BackendChoice::Deno => Ok(Arc::new(backend::deno::DenoBackend::new()?)),
         BackendChoice::Pub => Ok(Arc::new(backend::r#pub::PubBackend::new()?)),
         BackendChoice::Dockerhub => Ok(Arc::new(backend::dockerhub::DockerhubBackend::new()?)),
@@ -488,6 +695,22 @@ fn create_auto_backend(system: &System) -> Result<Arc<dyn PackageManager>> {
             let backend = backend::zypper::ZypperBackend::new()?;
             Ok(Arc::new(backend))
         }
+        System::Alpine => {
+            let backend = backend::apk::ApkBackend::new()?;
+            Ok(Arc::new(backend))
+        }
+        System::Void => {
+            let backend = backend::xbps::XbpsBackend::new()?;
+            Ok(Arc::new(backend))
+        }
+        System::NixOS => {
+            let backend = backend::nix::NixBackend::new()?;
+            Ok(Arc::new(backend))
+        }
+        System::Gentoo => {
+            let backend = backend::portage::PortageBackend::new()?;
+            Ok(Arc::new(backend))
+        }
         System::FreeBSD => {
             let backend = backend::pkg::PkgBackend::new()?;
             Ok(Arc::new(backend))
@@ -526,10 +749,11 @@ fn create_auto_backend(system: &System) -> Result<Arc<dyn PackageManager>> {
         System::Unknown(name) => {
             anyhow::bail!(
                 "Unsupported system: {}. Use -b to specify a backend. Supported backends:\n\
-                 System: apt, aur, brew, dnf, pacman, pkg, zypper\n\
-                 Universal: flatpak, snap\n\
-                 Language: cargo, go, pip, npm",
-                name
+                 System: apk, apt, aur, brew, dnf, pacman, pkg, portage, xbps, zypper\n\
+                 Universal: flatpak, snap, nix\n\
+                 Language: {}",
+                name,
+                backend::multi::LANGUAGE_BACKENDS.join(", ")
             );
         }
     }
@@ -566,7 +790,8 @@ fn show_available_managers() {
     // System package managers
     println!("\n{}", "System:".yellow());
     for pm in [
-        "pacman", "aur", "apt", "dnf", "zypper", "pkg", "brew", "winget", "scoop", "choco",
+        "pacman", "aur", "apt", "dnf", "zypper", "apk", "xbps", "portage", "pkg", "brew",
+        "winget", "scoop", "choco",
     ] {
         if available.contains(&pm) {
             println!("  {} {}", "✓".green(), pm);
@@ -575,7 +800,7 @@ fn show_available_managers() {
 
     // Universal package managers
     println!("\n{}", "Universal:".yellow());
-    for pm in ["flatpak", "snap"] {
+    for pm in ["flatpak", "snap", "nix"] {
         if available.contains(&pm) {
             println!("  {} {}", "✓".green(), pm);
         }
@@ -583,7 +808,9 @@ fn show_available_managers() {
 
     // Language package managers
     println!("\n{}", "Language:".yellow());
-    for pm in ["pip", "cargo", "go", "npm"] {
+    for pm in [
+        "pip", "conda", "gem", "composer", "cabal", "opam", "dotnet", "cargo", "go", "npm",
+    ] {
         if available.contains(&pm) {
             println!("  {} {}", "✓".green(), pm);
         }
@@ -753,9 +980,35 @@ async fn install_packages(pm: &Arc<dyn PackageManager>, package_names: Vec<Strin
         }
     }
 
+    let packages = typosquat::confirm(pm, packages).await?;
+    if packages.is_empty() {
+        return Ok(());
+    }
+
     install_selected(pm, packages).await
 }
 
+async fn remove_packages(pm: &Arc<dyn PackageManager>, package_names: &[String]) -> Result<()> {
+    let refs: Vec<&str> = package_names.iter().map(|s| s.as_str()).collect();
+    let question = format!("Remove {} with {}?", refs.join(", "), pm.name());
+    if !backend::bootstrap::confirm(&question)? {
+        return Ok(());
+    }
+
+    for result in pm.remove(&refs).await? {
+        if result.success {
+            print_success(&format!("Removed {}", result.package));
+        } else {
+            print_error(&format!(
+                "Failed to remove {}: {}",
+                result.package,
+                result.message.as_deref().unwrap_or("Unknown error")
+            ));
+        }
+    }
+    Ok(())
+}
+
 async fn install_selected(pm: &Arc<dyn PackageManager>, packages: Vec<Package>) -> Result<()> {
     println!();
     println!("Packages to install:");
@@ -777,13 +1030,174 @@ async fn install_selected(pm: &Arc<dyn PackageManager>, packages: Vec<Package>)
     Ok(())
 }
 
-async fn show_package_info(pm: &Arc<dyn PackageManager>, package: &str) -> Result<()> {
+/// Install the dependencies listed in manifests, each through its own backend
+async fn install_from_manifests(paths: Vec<PathBuf>) -> Result<()> {
+    let mut entries = vec![];
+    let mut results = vec![];
+    for path in &paths {
+        let found = manifest::parse_file(path)?;
+        print_info(&format!(
+            "Found {} dependencies in {}",
+            found.len(),
+            path.display()
+        ));
+
+        // Node projects install their own dependencies, keeping the declared
+        // ranges and the dev/prod split
+        if path.file_name().is_some_and(|name| name == "package.json") {
+            if !found.is_empty() {
+                results.extend(install_node_project(path, &found));
+            }
+            continue;
+        }
+        entries.extend(found);
+    }
+
+    if entries.is_empty() && results.is_empty() {
+        print_warning("No dependencies to install");
+        return Ok(());
+    }
+
+    let mut backend_ids: Vec<&str> = entries.iter().map(|e| e.backend).collect();
+    backend_ids.sort();
+    backend_ids.dedup();
+
+    for id in backend_ids {
+        let wanted: Vec<&manifest::ManifestEntry> =
+            entries.iter().filter(|e| e.backend == id).collect();
+
+        let pm = match backend::multi::create_backend_by_id(id) {
+            Ok(pm) => pm,
+            Err(e) => {
+                results.extend(wanted.iter().map(|entry| backend::InstallResult {
+                    package: entry.name.clone(),
+                    success: false,
+                    message: Some(format!("{} is not available: {}", id, e)),
+                }));
+                continue;
+            }
+        };
+
+        println!();
+        print_info(&format!("Installing {} packages with {}...", wanted.len(), pm.name()));
+
+        let names: Vec<&str> = wanted.iter().map(|e| e.name.as_str()).collect();
+        let found = match pm.info(&names).await {
+            Ok(found) => found,
+            Err(e) => {
+                results.extend(wanted.iter().map(|entry| backend::InstallResult {
+                    package: entry.name.clone(),
+                    success: false,
+                    message: Some(format!("Failed to look up in {}: {}", pm.name(), e)),
+                }));
+                continue;
+            }
+        };
+
+        let mut packages = vec![];
+        for entry in wanted {
+            let Some(pkg) = found
+                .iter()
+                .find(|p| p.name.eq_ignore_ascii_case(&entry.name))
+            else {
+                results.push(backend::InstallResult {
+                    package: entry.name.clone(),
+                    success: false,
+                    message: Some(format!("Package not found in {}", pm.name())),
+                });
+                continue;
+            };
+
+            let mut pkg = pkg.clone();
+            if let Some(version) = &entry.version {
+                pkg.version = version.clone();
+                pkg.extra.pinned = true;
+            }
+            pkg.extra.requirement = entry.requirement.clone();
+            if entry.cask {
+                pkg.extra.brew_cask = Some(true);
+            }
+            packages.push(pkg);
+        }
+
+        let packages = typosquat::confirm(&pm, packages).await?;
+        if packages.is_empty() {
+            continue;
+        }
+        match pm.install(&packages).await {
+            Ok(installed) => results.extend(installed),
+            Err(e) => results.extend(packages.iter().map(|pkg| backend::InstallResult {
+                package: pkg.name.clone(),
+                success: false,
+                message: Some(e.to_string()),
+            })),
+        }
+    }
+
+    print_install_summary(&results);
+
+    Ok(())
+}
+
+/// Run the project's Node client's `install` in the directory of its package.json
+fn install_node_project(
+    path: &Path,
+    entries: &[manifest::ManifestEntry],
+) -> Vec<backend::InstallResult> {
+    let dir = path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+
+    let outcome = backend::npm::NodeClient::select(dir).and_then(|client| {
+        println!();
+        print_info(&format!(
+            "Installing {} dependencies with {} in {}...",
+            entries.len(),
+            client.name(),
+            dir.display()
+        ));
+        Ok((client, client.run_in(dir, &["install"])?))
+    });
+
+    let (success, message) = match outcome {
+        Ok((_, status)) if status.success() => (true, None),
+        Ok((client, _)) => (false, Some(format!("{} install failed", client.name()))),
+        Err(e) => (false, Some(e.to_string())),
+    };
+    entries
+        .iter()
+        .map(|entry| backend::InstallResult {
+            package: entry.name.clone(),
+            success,
+            message: message.clone(),
+        })
+        .collect()
+}
+
+async fn show_package_info(
+    pm: &Arc<dyn PackageManager>,
+    package: &str,
+    comments: bool,
+) -> Result<()> {
     let results = pm.info(&[package]).await?;
 
-    if let Some(pkg) = results.into_iter().next() {
-        print_package_details(&pkg);
-    } else {
+    let Some(pkg) = results.into_iter().next() else {
         print_error(&format!("Package '{}' not found", package));
+        return Ok(());
+    };
+    print_package_details(&pkg);
+
+    if comments {
+        if pkg.extra.aur_id.is_none() {
+            print_warning("Comments are only available for AUR packages");
+            return Ok(());
+        }
+
+        let aur = backend::aur::AurBackend::new()?;
+        let pkgbase = pkg.extra.aur_package_base.as_deref().unwrap_or(&pkg.name);
+        let comments = aur.comments(pkgbase).await?;
+        print_aur_comments(&comments);
     }
 
     Ok(())
@@ -999,6 +1413,36 @@ async fn handle_go_command(cmd: GoCommands, pm: &Arc<dyn PackageManager>) -> Res
     Ok(())
 }
 
+/// Handle aur subcommands
+async fn handle_aur_command(cmd: AurCommands, aur: &backend::aur::AurBackend) -> Result<()> {
+    match cmd {
+        AurCommands::Clean { all } => {
+            print_info("Cleaning AUR build cache...");
+            let stats = aur.clean(all)?;
+            print_success(&format!(
+                "Removed {} package clones and {} old build artifacts",
+                stats.removed_repos, stats.removed_files
+            ));
+        }
+        AurCommands::Build { packages } => {
+            let names: Vec<&str> = packages.iter().map(|s| s.as_str()).collect();
+            let found = aur.info(&names).await?;
+            for name in &names {
+                if !found.iter().any(|p| p.name == *name) {
+                    anyhow::bail!("Package '{}' not found in the AUR", name);
+                }
+            }
+
+            let outputs = aur.build(&found).await?;
+            print_success(&format!("Built {} packages:", outputs.len()));
+            for path in outputs {
+                println!("  {}", path.display());
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Handle docker subcommands
 async fn handle_docker_command(cmd: DockerCommands, pm: &Arc<dyn PackageManager>) -> Result<()> {
     match cmd {
@@ -0,0 +1,365 @@
+//! Dependency manifests (requirements.txt, package.json, ...) for `zap install --from`
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+/// Manifest file names recognized when auto-detecting in a directory
+const MANIFEST_FILES: &[&str] = &[
+    "requirements.txt",
+    "pyproject.toml",
+    "package.json",
+    "pubspec.yaml",
+    "go.mod",
+    "Brewfile",
+    "Cargo.toml",
+];
+
+/// A dependency to install with a specific backend
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestEntry {
+    /// Backend id, e.g. "pip"
+    pub backend: &'static str,
+    pub name: String,
+    /// Exact version, when the manifest pins one
+    pub version: Option<String>,
+    /// The requirement as written, for installers that take one verbatim
+    /// (pip's `requests[socks]>=2.28,<3`)
+    pub requirement: Option<String>,
+    /// Homebrew cask rather than formula
+    pub cask: bool,
+}
+
+impl ManifestEntry {
+    fn new(backend: &'static str, name: impl Into<String>, version: Option<String>) -> Self {
+        Self {
+            backend,
+            name: name.into(),
+            version,
+            requirement: None,
+            cask: false,
+        }
+    }
+}
+
+/// Manifests present in a directory
+pub fn detect(dir: &Path) -> Vec<PathBuf> {
+    MANIFEST_FILES
+        .iter()
+        .map(|name| dir.join(name))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+/// Read a manifest and return the dependencies it lists
+pub fn parse_file(path: &Path) -> Result<Vec<ManifestEntry>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    match file_name.as_str() {
+        "pyproject.toml" => parse_pyproject(&content),
+        "package.json" => parse_package_json(&content),
+        "pubspec.yaml" | "pubspec.yml" => Ok(parse_pubspec(&content)),
+        "go.mod" => Ok(parse_go_mod(&content)),
+        "Brewfile" => Ok(parse_brewfile(&content)),
+        "Cargo.toml" => parse_cargo_toml(&content),
+        name if name.ends_with(".txt") => Ok(parse_requirements(&content)),
+        _ => anyhow::bail!("Unsupported manifest: {}", path.display()),
+    }
+}
+
+/// requirements.txt: one PEP 508 requirement per line
+fn parse_requirements(content: &str) -> Vec<ManifestEntry> {
+    content
+        .lines()
+        .map(|line| line.split(" #").next().unwrap_or(line).trim())
+        // Options (-r, -e, --index-url, ...) and URLs aren't package names
+        .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with('-'))
+        .filter(|line| !line.contains("://"))
+        .filter_map(parse_pep508)
+        .collect()
+}
+
+/// "requests[socks]>=2.0; python_version>'3'" -> requests, no version;
+/// "black==24.1.0" -> black 24.1.0. Either way the whole requirement is kept
+/// for pip.
+fn parse_pep508(line: &str) -> Option<ManifestEntry> {
+    let requirement = line.split(';').next()?.trim();
+    let name_end = requirement
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.'))
+        .unwrap_or(requirement.len());
+    let name = &requirement[..name_end];
+    if name.is_empty() {
+        return None;
+    }
+
+    let version = requirement
+        .split_once("==")
+        .map(|(_, v)| v.split(',').next().unwrap_or(v).trim().to_string())
+        .filter(|v| !v.contains('*'));
+
+    let mut entry = ManifestEntry::new("pip", name, version);
+    entry.requirement = Some(line.trim().to_string());
+    Some(entry)
+}
+
+/// PEP 621 `[project] dependencies` and Poetry `[tool.poetry.dependencies]`
+fn parse_pyproject(content: &str) -> Result<Vec<ManifestEntry>> {
+    let table: toml::Table = toml::from_str(content).context("Failed to parse pyproject.toml")?;
+    let mut entries = vec![];
+
+    if let Some(deps) = table
+        .get("project")
+        .and_then(|p| p.get("dependencies"))
+        .and_then(|d| d.as_array())
+    {
+        entries.extend(
+            deps.iter()
+                .filter_map(|d| d.as_str())
+                .filter_map(parse_pep508),
+        );
+    }
+
+    if let Some(deps) = table
+        .get("tool")
+        .and_then(|t| t.get("poetry"))
+        .and_then(|p| p.get("dependencies"))
+        .and_then(|d| d.as_table())
+    {
+        for (name, spec) in deps {
+            if name == "python" {
+                continue;
+            }
+            let version = spec
+                .as_str()
+                .filter(|v| v.chars().next().is_some_and(|c| c.is_ascii_digit()))
+                .map(|v| v.to_string());
+            entries.push(ManifestEntry::new("pip", name.as_str(), version));
+        }
+    }
+
+    Ok(entries)
+}
+
+/// package.json `dependencies` and `devDependencies`
+fn parse_package_json(content: &str) -> Result<Vec<ManifestEntry>> {
+    let json: serde_json::Value =
+        serde_json::from_str(content).context("Failed to parse package.json")?;
+    let mut entries = vec![];
+
+    for section in ["dependencies", "devDependencies"] {
+        if let Some(deps) = json.get(section).and_then(|d| d.as_object()) {
+            for name in deps.keys() {
+                entries.push(ManifestEntry::new("npm", name.as_str(), None));
+            }
+        }
+    }
+
+    Ok(entries)
+}
+
+/// pubspec.yaml `dependencies` and `dev_dependencies`, skipping SDK packages
+fn parse_pubspec(content: &str) -> Vec<ManifestEntry> {
+    let mut entries: Vec<ManifestEntry> = vec![];
+    let mut in_deps = false;
+
+    for line in content.lines() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+
+        let indent = line.len() - line.trim_start().len();
+        if indent == 0 {
+            let key = line.trim_end().trim_end_matches(':');
+            in_deps = key == "dependencies" || key == "dev_dependencies";
+            continue;
+        }
+        if !in_deps {
+            continue;
+        }
+
+        let Some((key, _)) = line.trim().split_once(':') else {
+            continue;
+        };
+        if indent == 2 {
+            entries.push(ManifestEntry::new("pub", key.trim(), None));
+        } else if key.trim() == "sdk" {
+            // "flutter: { sdk: flutter }" style entries come with the SDK
+            entries.pop();
+        }
+    }
+
+    entries
+}
+
+/// go.mod `tool` directives (Go 1.24+), with versions from the matching require
+fn parse_go_mod(content: &str) -> Vec<ManifestEntry> {
+    let mut tools = vec![];
+    let mut requires: Vec<(String, String)> = vec![];
+    let mut block: Option<&str> = None;
+
+    for line in content.lines() {
+        let line = line.split("//").next().unwrap_or(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if line == ")" {
+            block = None;
+            continue;
+        }
+        if let Some(directive) = line.strip_suffix('(').map(|d| d.trim()) {
+            block = Some(directive);
+            continue;
+        }
+
+        let (directive, rest) = match block {
+            Some(directive) => (directive, line),
+            None => match line.split_once(char::is_whitespace) {
+                Some((directive, rest)) => (directive, rest.trim()),
+                None => continue,
+            },
+        };
+
+        let mut parts = rest.split_whitespace();
+        match directive {
+            "tool" => tools.extend(parts.next().map(|p| p.to_string())),
+            "require" => {
+                if let (Some(module), Some(version)) = (parts.next(), parts.next()) {
+                    requires.push((module.to_string(), version.to_string()));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    tools
+        .into_iter()
+        .map(|tool| {
+            // The module providing the tool is the longest matching require
+            let version = requires
+                .iter()
+                .filter(|(module, _)| tool == *module || tool.starts_with(&format!("{}/", module)))
+                .max_by_key(|(module, _)| module.len())
+                .map(|(_, version)| version.clone());
+            ManifestEntry::new("go", tool, version)
+        })
+        .collect()
+}
+
+/// Brewfile `brew "name"` and `cask "name"` lines
+fn parse_brewfile(content: &str) -> Vec<ManifestEntry> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            let (kind, rest) = line.split_once(char::is_whitespace)?;
+            if kind != "brew" && kind != "cask" {
+                return None;
+            }
+            let name = rest.trim().strip_prefix('"')?.split('"').next()?;
+            let mut entry = ManifestEntry::new("brew", name, None);
+            entry.cask = kind == "cask";
+            Some(entry)
+        })
+        .collect()
+}
+
+/// Cargo.toml tool lists in `[workspace.metadata.bin]` or `[package.metadata.bin]`
+/// (the cargo-run-bin format): `name = "1.0"` or `name = { version = "1.0" }`
+fn parse_cargo_toml(content: &str) -> Result<Vec<ManifestEntry>> {
+    let table: toml::Table = toml::from_str(content).context("Failed to parse Cargo.toml")?;
+    let mut entries = vec![];
+
+    for section in ["workspace", "package"] {
+        let Some(bins) = table
+            .get(section)
+            .and_then(|s| s.get("metadata"))
+            .and_then(|m| m.get("bin"))
+            .and_then(|b| b.as_table())
+        else {
+            continue;
+        };
+
+        for (name, spec) in bins {
+            let version = spec
+                .as_str()
+                .or_else(|| spec.get("version").and_then(|v| v.as_str()))
+                .map(|v| v.to_string());
+            entries.push(ManifestEntry::new("cargo", name.as_str(), version));
+        }
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(entries: &[ManifestEntry]) -> Vec<&str> {
+        entries.iter().map(|e| e.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_parse_requirements() {
+        let entries = parse_requirements(
+            "# tools\nblack==24.1.0\nrequests[socks]>=2.0 ; python_version > '3.8'\n-r dev.txt\n-e .\nhttps://example.com/pkg.whl\nnumpy  # math\n",
+        );
+        assert_eq!(names(&entries), ["black", "requests", "numpy"]);
+        assert_eq!(entries[0].version.as_deref(), Some("24.1.0"));
+        assert_eq!(entries[1].version, None);
+        assert_eq!(
+            entries[1].requirement.as_deref(),
+            Some("requests[socks]>=2.0 ; python_version > '3.8'")
+        );
+    }
+
+    #[test]
+    fn test_parse_pubspec() {
+        let entries = parse_pubspec(
+            "name: app\ndependencies:\n  flutter:\n    sdk: flutter\n  http: ^1.2.0\n  provider:\n    version: ^6.0.0\ndev_dependencies:\n  lints: ^3.0.0\nflutter:\n  uses-material-design: true\n",
+        );
+        assert_eq!(names(&entries), ["http", "provider", "lints"]);
+    }
+
+    #[test]
+    fn test_parse_go_mod() {
+        let entries = parse_go_mod(
+            "module example.com/app\n\ngo 1.24\n\nrequire (\n\tgolang.org/x/tools v0.30.0 // indirect\n\tgithub.com/a/b v1.0.0\n)\n\ntool golang.org/x/tools/cmd/stringer\n\ntool (\n\tgithub.com/a/b/cmd/gen\n)\n",
+        );
+        assert_eq!(
+            entries,
+            [
+                ManifestEntry::new(
+                    "go",
+                    "golang.org/x/tools/cmd/stringer",
+                    Some("v0.30.0".to_string())
+                ),
+                ManifestEntry::new("go", "github.com/a/b/cmd/gen", Some("v1.0.0".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_brewfile() {
+        let entries = parse_brewfile(
+            "tap \"homebrew/bundle\"\nbrew \"git\"\nbrew \"node\", restart_service: true\ncask \"firefox\"\n",
+        );
+        assert_eq!(names(&entries), ["git", "node", "firefox"]);
+        assert!(entries[2].cask);
+    }
+
+    #[test]
+    fn test_parse_cargo_toml() {
+        let entries = parse_cargo_toml(
+            "[workspace]\nmembers = []\n\n[workspace.metadata.bin]\ncargo-nextest = { version = \"0.9.57\", locked = true }\nflamegraph = \"0.6.5\"\n",
+        )
+        .unwrap();
+        assert_eq!(names(&entries), ["cargo-nextest", "flamegraph"]);
+        assert_eq!(entries[0].version.as_deref(), Some("0.9.57"));
+    }
+}
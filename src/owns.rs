@@ -0,0 +1,190 @@
+//! `zap owns`: which package manager put a file on this system
+
+use anyhow::{Context, Result};
+use colored::Colorize;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+use crate::backend::multi::MultiBackend;
+use crate::ui::{print_info, print_warning};
+
+pub fn run(target: &str) -> Result<()> {
+    let path = resolve(target)?;
+    let multi = MultiBackend::quiet()?;
+
+    let owners = multi.owns_all(&path);
+    if owners.is_empty() {
+        print_warning(&format!(
+            "{} is not owned by any package manager zap knows about",
+            path.display()
+        ));
+    }
+    for (backend, name, version) in &owners {
+        print_info(&format!(
+            "{} is owned by {} {} ({})",
+            path.display(),
+            name.cyan().bold(),
+            version.green(),
+            backend
+        ));
+    }
+    if owners.len() > 1 {
+        print_warning(&format!(
+            "{} is claimed by more than one package manager",
+            path.display()
+        ));
+    }
+
+    // Other executables with the same name, and who installed them
+    let Some(name) = path.file_name() else {
+        return Ok(());
+    };
+    let mut copies = find_on_path(name);
+    if !copies.iter().any(|p| same_file(p, &path)) {
+        copies.push(path.clone());
+    }
+    if copies.len() < 2 {
+        return Ok(());
+    }
+
+    println!();
+    print_info(&format!(
+        "'{}' is installed more than once:",
+        name.to_string_lossy()
+    ));
+    let mut managers: Vec<String> = vec![];
+    for (i, copy) in copies.iter().enumerate() {
+        let owner = multi.owns_all(copy).into_iter().next();
+        let description = match &owner {
+            Some((backend, name, version)) => format!("{} {} ({})", name, version, backend),
+            None => "not managed by a package manager".to_string(),
+        };
+        let marker = if i == 0 {
+            format!(" {}", "[runs]".green())
+        } else {
+            String::new()
+        };
+        println!(
+            "  {} {} {}{}",
+            "•".green(),
+            copy.display(),
+            description.bright_black(),
+            marker
+        );
+
+        if let Some((backend, _, _)) = owner {
+            if !managers.contains(&backend) {
+                managers.push(backend);
+            }
+        }
+    }
+
+    if managers.len() > 1 {
+        println!();
+        print_warning(&format!(
+            "'{}' is installed by {}; {} comes first on PATH",
+            name.to_string_lossy(),
+            managers.join(" and "),
+            copies[0].display()
+        ));
+    }
+
+    Ok(())
+}
+
+/// A file path as given, or the first executable of that name on PATH
+fn resolve(target: &str) -> Result<PathBuf> {
+    let path = Path::new(target);
+    if target.contains(std::path::MAIN_SEPARATOR) || target.contains('/') || path.exists() {
+        if !path.exists() && !path.is_symlink() {
+            anyhow::bail!("{} does not exist", target);
+        }
+        // Keep symlinks as they are: they are how brew, npm and pipx link their files
+        return std::path::absolute(path).with_context(|| format!("Failed to resolve {}", target));
+    }
+
+    find_on_path(OsStr::new(target))
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("'{}' is neither a file nor a command on PATH", target))
+}
+
+/// Executables called `name` in PATH order, one per distinct file
+pub fn find_on_path(name: &OsStr) -> Vec<PathBuf> {
+    let mut found: Vec<PathBuf> = vec![];
+    for dir in path_dirs() {
+        let candidate = dir.join(name);
+        if is_executable(&candidate) && !found.iter().any(|f| same_file(f, &candidate)) {
+            found.push(candidate);
+        }
+    }
+    found
+}
+
+/// The directories on PATH, without repeats
+pub fn path_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = vec![];
+    if let Some(path) = std::env::var_os("PATH") {
+        for dir in std::env::split_paths(&path) {
+            if !dir.as_os_str().is_empty() && !dirs.contains(&dir) {
+                dirs.push(dir);
+            }
+        }
+    }
+    dirs
+}
+
+pub fn is_executable(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        path.metadata()
+            .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+            .unwrap_or(false)
+    }
+    #[cfg(not(unix))]
+    {
+        path.is_file()
+    }
+}
+
+/// Whether two paths lead to the same file, e.g. /bin/ls and /usr/bin/ls on merged-/usr systems
+pub fn same_file(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::{symlink, PermissionsExt};
+
+    #[test]
+    fn test_resolve_and_same_file() {
+        let dir = std::env::temp_dir().join(format!("zap-owns-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let file = dir.join("tool");
+        std::fs::write(&file, "#!/bin/sh\n").unwrap();
+        assert!(!is_executable(&file));
+        std::fs::set_permissions(&file, std::fs::Permissions::from_mode(0o755)).unwrap();
+        assert!(is_executable(&file));
+        assert!(!is_executable(&dir));
+
+        // Links are kept as given, since they are what package managers own
+        let link = dir.join("link");
+        symlink(&file, &link).unwrap();
+        let target = link.to_string_lossy();
+        assert_eq!(resolve(&target).unwrap(), link);
+        assert!(same_file(&link, &file));
+        assert!(!same_file(&link, &dir));
+
+        let missing = dir.join("missing");
+        assert!(resolve(&missing.to_string_lossy()).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,146 @@
+//! `zap provides` and the command-not-found hook built on it
+
+use anyhow::Result;
+use clap::ValueEnum;
+use colored::Colorize;
+use std::io::IsTerminal;
+
+use crate::backend::bootstrap;
+use crate::backend::multi::MultiBackend;
+use crate::backend::Package;
+use crate::ui::{print_info, print_install_summary, print_warning};
+
+/// Shells with a command-not-found hook
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Shell {
+    Zsh,
+    Bash,
+    Fish,
+}
+
+/// Show the packages that provide `target` and offer to install the best match.
+///
+/// As a hook, only system backends are asked (their file indexes are local, the
+/// language registries are not), everything goes to stderr like the shell's own
+/// error, and installing is only offered in an interactive terminal.
+pub async fn run(target: &str, hook: bool) -> Result<()> {
+    let multi = if hook {
+        MultiBackend::quiet()?
+    } else {
+        MultiBackend::new()?
+    };
+
+    if hook {
+        eprintln!("{}: command not found", target);
+    } else {
+        print_info(&format!(
+            "Looking for packages that provide {}...",
+            target.cyan()
+        ));
+    }
+
+    let results = multi.provides_all(target, hook).await;
+    let Some((top_backend, top)) = results
+        .first()
+        .and_then(|(id, packages)| packages.first().map(|pkg| (id.clone(), pkg.clone())))
+    else {
+        if !hook {
+            print_warning(&format!("No package provides '{}'", target));
+        }
+        return Ok(());
+    };
+
+    if hook {
+        eprintln!("It is provided by:");
+    } else {
+        println!();
+    }
+    for (backend_id, packages) in &results {
+        for pkg in packages {
+            let line = format_provider(backend_id, pkg);
+            if hook {
+                eprintln!("{}", line);
+            } else {
+                println!("{}", line);
+            }
+        }
+    }
+    if !hook {
+        println!();
+    }
+
+    if top.installed || (hook && !std::io::stdin().is_terminal()) {
+        return Ok(());
+    }
+
+    let question = format!("Install '{}' via {}?", top.name, top_backend);
+    if !bootstrap::confirm(&question)? {
+        return Ok(());
+    }
+
+    let Some(backend) = multi.get_backend(&top_backend) else {
+        return Ok(());
+    };
+    let results = backend.install(&[top]).await?;
+    print_install_summary(&results);
+    Ok(())
+}
+
+fn format_provider(backend_id: &str, pkg: &Package) -> String {
+    let installed = if pkg.installed {
+        format!(" {}", "[installed]".blue())
+    } else {
+        String::new()
+    };
+    format!(
+        "  {} {} {} {}{}",
+        "•".green(),
+        pkg.name.cyan().bold(),
+        pkg.version.green(),
+        format!("({})", backend_id).bright_black(),
+        installed
+    )
+}
+
+/// Shell code that runs `zap provides --hook` for unknown commands. `zap` is the
+/// path of the running binary, so a missing `zap` cannot recurse into the hook.
+pub fn hook_script(shell: Shell, zap: &str) -> String {
+    let zap = zap.replace('\'', r"'\''");
+    match shell {
+        Shell::Zsh => format!(
+            "command_not_found_handler() {{\n    \
+                 ZAP_DISABLE_UPDATE_CHECK=1 '{zap}' provides --hook -- \"$1\"\n    \
+                 return 127\n\
+             }}\n"
+        ),
+        Shell::Bash => format!(
+            "command_not_found_handle() {{\n    \
+                 ZAP_DISABLE_UPDATE_CHECK=1 '{zap}' provides --hook -- \"$1\"\n    \
+                 return 127\n\
+             }}\n"
+        ),
+        Shell::Fish => format!(
+            "function fish_command_not_found\n    \
+                 env ZAP_DISABLE_UPDATE_CHECK=1 '{zap}' provides --hook -- $argv[1]\n\
+             end\n"
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hook_script() {
+        let zsh = hook_script(Shell::Zsh, "/usr/local/bin/zap");
+        assert!(zsh.starts_with("command_not_found_handler() {\n"));
+        assert!(zsh.contains(
+            "    ZAP_DISABLE_UPDATE_CHECK=1 '/usr/local/bin/zap' provides --hook -- \"$1\"\n"
+        ));
+
+        let fish = hook_script(Shell::Fish, "/home/o'neil/bin/zap");
+        assert!(fish.contains(r"'/home/o'\''neil/bin/zap' provides --hook -- $argv[1]"));
+        assert!(fish.ends_with("end\n"));
+    }
+}
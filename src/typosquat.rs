@@ -0,0 +1,409 @@
+//! Warnings for package names that look like typos of popular packages
+
+use anyhow::Result;
+use std::sync::Arc;
+
+use crate::backend::{bootstrap, Package, PackageManager};
+use crate::ui::print_warning;
+
+/// Packages first published this recently are flagged when they have few downloads
+const NEW_PACKAGE_DAYS: i64 = 30;
+/// Below this many downloads a new package counts as barely used
+const FEW_DOWNLOADS: u64 = 1_000;
+/// A look-alike is "much more popular" with this many times the downloads
+const POPULARITY_RATIO: u64 = 10;
+/// At most this many look-alike names are looked up per package
+const MAX_CANDIDATES: usize = 5;
+
+/// Prefixes typosquats add to or drop from real names, per backend
+const PREFIXES: &[(&str, &[&str])] = &[
+    ("pip", &["python-", "py-", "py"]),
+    ("npm", &["node-", "js-"]),
+    ("cargo", &["rust-"]),
+];
+/// Suffixes typosquats add to real names, per backend
+const SUFFIXES: &[(&str, &[&str])] = &[
+    ("pip", &["-python", "-py"]),
+    ("npm", &["-js", ".js", "js"]),
+    ("cargo", &["-rs", "-rust"]),
+];
+
+/// PyPI has no search or download statistics in its JSON API, so the most
+/// downloaded projects stand in for popularity data
+const POPULAR_PYPI: &[&str] = &[
+    "boto3",
+    "urllib3",
+    "botocore",
+    "requests",
+    "setuptools",
+    "certifi",
+    "charset-normalizer",
+    "idna",
+    "typing-extensions",
+    "python-dateutil",
+    "packaging",
+    "s3transfer",
+    "aiobotocore",
+    "six",
+    "numpy",
+    "pyyaml",
+    "s3fs",
+    "fsspec",
+    "pip",
+    "cryptography",
+    "grpcio-status",
+    "pydantic",
+    "cffi",
+    "attrs",
+    "google-api-core",
+    "pycparser",
+    "pandas",
+    "importlib-metadata",
+    "protobuf",
+    "jmespath",
+    "rsa",
+    "wheel",
+    "zipp",
+    "pyasn1",
+    "click",
+    "platformdirs",
+    "markupsafe",
+    "jinja2",
+    "pytz",
+    "colorama",
+    "awscli",
+    "filelock",
+    "tomli",
+    "virtualenv",
+    "pydantic-core",
+    "pyjwt",
+    "wrapt",
+    "cachetools",
+    "pluggy",
+    "pytest",
+    "jsonschema",
+    "sqlalchemy",
+    "psutil",
+    "pyparsing",
+    "requests-oauthlib",
+    "oauthlib",
+    "aiohttp",
+    "multidict",
+    "yarl",
+    "frozenlist",
+    "scipy",
+    "pillow",
+    "werkzeug",
+    "flask",
+    "django",
+    "fastapi",
+    "uvicorn",
+    "starlette",
+    "httpx",
+    "httpcore",
+    "anyio",
+    "sniffio",
+    "h11",
+    "beautifulsoup4",
+    "soupsieve",
+    "lxml",
+    "matplotlib",
+    "scikit-learn",
+    "tqdm",
+    "openpyxl",
+    "tensorflow",
+    "torch",
+    "transformers",
+    "opencv-python",
+    "selenium",
+    "docutils",
+    "pygments",
+    "rich",
+    "black",
+    "isort",
+    "mypy",
+    "flake8",
+    "pylint",
+    "poetry",
+    "redis",
+    "psycopg2",
+    "psycopg2-binary",
+    "pymysql",
+    "paramiko",
+    "bcrypt",
+    "pynacl",
+    "openai",
+    "python-dotenv",
+    "greenlet",
+    "websocket-client",
+    "websockets",
+    "tabulate",
+    "decorator",
+];
+
+/// Ask before installing language packages that look like typosquats,
+/// returning the ones to go ahead with
+pub async fn confirm(pm: &Arc<dyn PackageManager>, packages: Vec<Package>) -> Result<Vec<Package>> {
+    if !matches!(pm.id(), "npm" | "pip" | "cargo") {
+        return Ok(packages);
+    }
+
+    let mut confirmed = vec![];
+    for pkg in packages {
+        let warnings = check(pm, &pkg).await;
+        if !warnings.is_empty() {
+            for warning in &warnings {
+                print_warning(warning);
+            }
+            if !bootstrap::confirm_unsafe(&format!("Install '{}' anyway?", pkg.name))? {
+                continue;
+            }
+        }
+        confirmed.push(pkg);
+    }
+
+    Ok(confirmed)
+}
+
+/// Reasons to double-check a package before installing it
+async fn check(pm: &Arc<dyn PackageManager>, package: &Package) -> Vec<String> {
+    let mut warnings = vec![];
+    let ecosystem = pm.id();
+
+    let mut package = package.clone();
+    if package.extra.downloads.is_none() {
+        package.extra.downloads = pm.downloads(&package.name).await;
+    }
+    let package = &package;
+
+    if let Some(warning) = check_new(package) {
+        warnings.push(warning);
+    }
+
+    let mut candidates: Vec<(String, &'static str)> = vec![];
+    let mut add = |name: &str, reason: &'static str| {
+        if !same_name(ecosystem, name, &package.name)
+            && !candidates
+                .iter()
+                .any(|(c, _)| same_name(ecosystem, c, name))
+        {
+            candidates.push((name.to_string(), reason));
+        }
+    };
+
+    for variant in variants(ecosystem, &package.name) {
+        add(
+            &variant,
+            "the same name with a different prefix, suffix or scope",
+        );
+    }
+    if ecosystem == "pip" {
+        for popular in POPULAR_PYPI {
+            if let Some(reason) = similarity(popular, &package.name) {
+                add(popular, reason);
+            }
+        }
+    }
+    if let Ok(results) = pm.search(&package.name).await {
+        for result in results {
+            if let Some(reason) = similarity(&result.name, &package.name) {
+                add(&result.name, reason);
+            }
+        }
+    }
+
+    candidates.truncate(MAX_CANDIDATES);
+    if candidates.is_empty() {
+        return warnings;
+    }
+
+    let names: Vec<&str> = candidates.iter().map(|(name, _)| name.as_str()).collect();
+    let mut found = pm.info(&names).await.unwrap_or_default();
+
+    for candidate in &mut found {
+        if candidate.extra.downloads.is_none() {
+            candidate.extra.downloads = pm.downloads(&candidate.name).await;
+        }
+        let Some((_, reason)) = candidates
+            .iter()
+            .find(|(name, _)| same_name(ecosystem, name, &candidate.name))
+        else {
+            continue;
+        };
+        if let Some(popularity) = popularity_gap(ecosystem, candidate, package) {
+            warnings.push(format!(
+                "'{}' looks like '{}' ({}), {}",
+                package.name, candidate.name, reason, popularity
+            ));
+        }
+    }
+
+    warnings
+}
+
+fn check_new(package: &Package) -> Option<String> {
+    let created = package.extra.created_at?;
+    let age_days = (chrono::Utc::now().timestamp() - created) / 86_400;
+    if age_days >= NEW_PACKAGE_DAYS {
+        return None;
+    }
+
+    match package.extra.downloads {
+        Some(downloads) if downloads >= FEW_DOWNLOADS => None,
+        Some(downloads) => Some(format!(
+            "'{}' was first published {} days ago and has only {} downloads",
+            package.name, age_days, downloads
+        )),
+        None => Some(format!(
+            "'{}' was first published {} days ago",
+            package.name, age_days
+        )),
+    }
+}
+
+/// Describe how much more popular `candidate` is, if it is by a wide margin
+fn popularity_gap(ecosystem: &str, candidate: &Package, requested: &Package) -> Option<String> {
+    match (candidate.extra.downloads, requested.extra.downloads) {
+        (Some(theirs), Some(ours)) if theirs >= ours.max(1) * POPULARITY_RATIO => {
+            Some(format!("which has {} downloads to its {}", theirs, ours))
+        }
+        (Some(_), Some(_)) => None,
+        _ if ecosystem == "pip" => {
+            let popular = |name: &str| POPULAR_PYPI.iter().any(|p| same_name("pip", p, name));
+            (popular(&candidate.name) && !popular(&requested.name))
+                .then(|| "one of the most downloaded PyPI projects".to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Names a typosquat of `name` might be imitating
+fn variants(ecosystem: &str, name: &str) -> Vec<String> {
+    let lower = name.to_lowercase();
+    let mut variants = vec![];
+
+    // "@attacker/lodash" imitates "lodash"
+    if let Some((_, unscoped)) = lower.strip_prefix('@').and_then(|n| n.split_once('/')) {
+        variants.push(unscoped.to_string());
+    }
+
+    let affixes = |table: &[(&str, &'static [&'static str])]| {
+        table
+            .iter()
+            .find(|(id, _)| *id == ecosystem)
+            .map(|(_, affixes)| *affixes)
+            .unwrap_or_default()
+    };
+    for prefix in affixes(PREFIXES) {
+        if let Some(stripped) = lower.strip_prefix(prefix).filter(|s| !s.is_empty()) {
+            variants.push(stripped.to_string());
+        }
+    }
+    for suffix in affixes(SUFFIXES) {
+        if let Some(stripped) = lower.strip_suffix(suffix).filter(|s| !s.is_empty()) {
+            variants.push(stripped.to_string());
+        }
+    }
+    // "dateutil" imitates "python-dateutil"
+    if ecosystem == "pip" && !lower.starts_with("python-") {
+        variants.push(format!("python-{}", lower));
+    }
+
+    // PyPI treats separators alike, so only other registries can be confused by them
+    if ecosystem != "pip" {
+        if lower.contains('-') {
+            variants.push(lower.replace('-', "_"));
+        }
+        if lower.contains('_') {
+            variants.push(lower.replace('_', "-"));
+        }
+    }
+
+    variants
+}
+
+/// Why `candidate` could be mistaken for `name`, if it could
+fn similarity(candidate: &str, name: &str) -> Option<&'static str> {
+    let (a, b) = (candidate.to_lowercase(), name.to_lowercase());
+    if a == b {
+        return None;
+    }
+    if strip_separators(&a) == strip_separators(&b) {
+        return Some("the same name with different separators");
+    }
+
+    let distance = edit_distance(&a, &b);
+    let allowed = if b.len() <= 5 { 1 } else { 2 };
+    (distance <= allowed).then_some("a name a typo away")
+}
+
+fn strip_separators(name: &str) -> String {
+    name.chars()
+        .filter(|c| !matches!(c, '-' | '_' | '.'))
+        .collect()
+}
+
+/// Whether two names refer to the same package (PyPI normalizes case and separators)
+fn same_name(ecosystem: &str, a: &str, b: &str) -> bool {
+    if ecosystem == "pip" {
+        let normalize = |name: &str| name.to_lowercase().replace(['_', '.'], "-");
+        normalize(a) == normalize(b)
+    } else {
+        a == b
+    }
+}
+
+/// Optimal string alignment distance: edits, including swapping adjacent characters
+fn edit_distance(a: &str, b: &str) -> usize {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let mut d = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_similarity() {
+        assert_eq!(
+            similarity("requests", "reqeusts"),
+            Some("a name a typo away")
+        );
+        assert_eq!(similarity("lodash", "lodahs"), Some("a name a typo away"));
+        assert_eq!(
+            similarity("serde_json", "serde-json"),
+            Some("the same name with different separators")
+        );
+        assert_eq!(similarity("tokio", "axum"), None);
+        assert_eq!(similarity("numpy", "numpy"), None);
+    }
+
+    #[test]
+    fn test_variants() {
+        assert!(variants("pip", "dateutil").contains(&"python-dateutil".to_string()));
+        assert!(variants("pip", "python-dateutil").contains(&"dateutil".to_string()));
+        assert!(variants("npm", "@evil/lodash").contains(&"lodash".to_string()));
+        assert!(variants("npm", "colors-js").contains(&"colors".to_string()));
+        assert!(variants("cargo", "serde-json").contains(&"serde_json".to_string()));
+        assert!(!variants("pip", "typing-extensions").contains(&"typing_extensions".to_string()));
+    }
+}
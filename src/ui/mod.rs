@@ -14,6 +14,8 @@ use ratatui::{
 use std::io::{self, stdout};
 use std::time::Duration;
 
+use crate::audit::{Advisory, Severity};
+use crate::backend::aur::AurComment;
 use crate::backend::{InstallResult, Package};
 
 /// Safely truncate a string to a maximum number of characters (not bytes)
@@ -665,6 +667,8 @@ pub fn print_package_details(pkg: &Package) {
 
     if let Some(maintainer) = &pkg.maintainer {
         println!("Maintainer: {}", maintainer);
+    } else if pkg.extra.aur_id.is_some() {
+        println!("Maintainer: {}", "None (orphaned)".red());
     }
 
     if let Some(votes) = pkg.extra.aur_votes {
@@ -675,6 +679,34 @@ pub fn print_package_details(pkg: &Package) {
         println!("Popularity: {:.2}", pkg.popularity);
     }
 
+    if let Some(submitted) = pkg.extra.aur_first_submitted {
+        println!("First Submitted: {}", format_timestamp(submitted));
+    }
+
+    if let Some(modified) = pkg.extra.aur_last_modified {
+        println!("Last Updated: {}", format_timestamp(modified));
+    }
+
+    if !pkg.extra.aur_replaces.is_empty() {
+        println!("Replaces: {}", pkg.extra.aur_replaces.join(", ").yellow());
+    }
+
+    if !pkg.extra.use_flags.is_empty() {
+        let flags: Vec<String> = pkg
+            .extra
+            .use_flags
+            .iter()
+            .map(|flag| {
+                if flag.starts_with('-') {
+                    flag.bright_black().to_string()
+                } else {
+                    flag.green().to_string()
+                }
+            })
+            .collect();
+        println!("USE flags: {}", flags.join(" "));
+    }
+
     if !pkg.extra.depends.is_empty() {
         println!(
             "Dependencies: {}",
@@ -682,12 +714,15 @@ pub fn print_package_details(pkg: &Package) {
         );
     }
 
-    if pkg.extra.out_of_date.is_some() {
+    if let Some(flagged) = pkg.extra.out_of_date {
         println!(
             "{}",
-            "WARNING: This package is flagged as out of date!"
-                .red()
-                .bold()
+            format!(
+                "WARNING: This package is flagged as out of date since {}!",
+                format_timestamp(flagged as i64)
+            )
+            .red()
+            .bold()
         );
     }
 
@@ -702,6 +737,88 @@ pub fn print_package_details(pkg: &Package) {
     println!();
 }
 
+pub fn print_aur_comments(comments: &[AurComment]) {
+    if comments.is_empty() {
+        println!("No comments");
+        return;
+    }
+
+    for comment in comments {
+        let pinned = if comment.pinned {
+            format!(" {}", "[pinned]".yellow())
+        } else {
+            String::new()
+        };
+        println!(
+            "{} {}{}",
+            comment.author.cyan().bold(),
+            comment.date.bright_black(),
+            pinned
+        );
+        for line in comment.text.lines() {
+            println!("  {}", line);
+        }
+        println!();
+    }
+}
+
+pub fn print_advisories(advisories: &[Advisory]) {
+    println!();
+    if advisories.is_empty() {
+        print_success("No known vulnerabilities found");
+        return;
+    }
+
+    println!(
+        "{} {} known vulnerabilities found",
+        "-->".red(),
+        advisories.len().to_string().red().bold()
+    );
+    println!();
+
+    for advisory in advisories {
+        let label = advisory.severity.label();
+        let severity = match advisory.severity {
+            Severity::Critical => label.red().bold(),
+            Severity::High => label.red(),
+            Severity::Medium => label.yellow(),
+            Severity::Low | Severity::Unknown => label.bright_black(),
+        };
+        let aliases = if advisory.aliases.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", advisory.aliases.join(", "))
+        };
+
+        println!(
+            "{} {} {} {}{}",
+            severity,
+            advisory.package.cyan().bold(),
+            advisory.version.bright_black(),
+            advisory.id,
+            aliases.bright_black()
+        );
+        if let Some(summary) = &advisory.summary {
+            println!("  {}", summary);
+        }
+        match &advisory.fixed {
+            Some(fixed) => println!("  Fixed in: {} ({})", fixed.green(), advisory.backend),
+            None => println!(
+                "  Fixed in: {} ({})",
+                "no fix yet".yellow(),
+                advisory.backend
+            ),
+        }
+        println!();
+    }
+}
+
+fn format_timestamp(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|d| d.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_else(|| timestamp.to_string())
+}
+
 pub fn print_search_results(packages: &[Package], pm_name: &str) {
     println!();
     println!(